@@ -51,13 +51,7 @@ fn examples_runner() -> anyhow::Result<()> {
 
         // Parsed program -> Flattened program
         let _flattened = unwrap_or_continue!(
-            parsed.compile(
-                &handler,
-                CompileOptions {
-                    skip_optimize: false,
-                    print_flat: false,
-                }
-            ),
+            parsed.compile(&handler, &CompileOptions::default()),
             "compile",
             failed_tests,
             path,
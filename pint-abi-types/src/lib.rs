@@ -9,8 +9,20 @@ pub struct ContractABI {
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct PredicateABI {
     pub name: String,
+    /// The predicate's `///` doc comment, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
     pub vars: Vec<VarABI>,
     pub pub_vars: Vec<VarABI>,
+    /// The number of non-`pub` decision variables, i.e. `vars.len()`.
+    #[serde(default)]
+    pub decision_var_count: usize,
+    /// The number of `pub` decision variables, i.e. `pub_vars.len()`.
+    #[serde(default)]
+    pub pub_var_count: usize,
+    /// The total number of state slots occupied by all the state variables.
+    #[serde(default)]
+    pub state_slot_count: usize,
 }
 
 ////////////////////////////////////////
@@ -21,6 +33,12 @@ pub struct PredicateABI {
 pub struct VarABI {
     pub name: String,
     pub ty: TypeABI,
+    /// The size of the variable's value in bytes, if known. Not provided for storage variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_in_bytes: Option<usize>,
+    /// The variable's `///` doc comment, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -2,7 +2,7 @@
 
 use anyhow::Context;
 use clap::{builder::styling::Style, Parser};
-use pint_pkg::{build::BuiltPkg, manifest::ManifestFile};
+use pint_pkg::{build::BuiltPkg, lock::Lock, manifest::ManifestFile};
 use std::path::{Path, PathBuf};
 
 /// Build a package, writing the generated artifacts to `out/`.
@@ -23,6 +23,9 @@ pub(crate) struct Args {
     /// Don't print anything that wasn't explicitly requested.
     #[arg(long)]
     silent: bool,
+    /// Ignore the pinned sources within `pint.lock` and re-resolve all dependencies.
+    #[arg(long)]
+    update: bool,
 }
 
 // Find the file within the current directory or parent directories with the given name.
@@ -59,9 +62,11 @@ pub(crate) fn cmd(args: Args) -> anyhow::Result<()> {
     // Prepare the compilation plan.
     let manifest = ManifestFile::from_path(&manifest_path).context("failed to load manifest")?;
     let name = manifest.pkg.name.to_string();
+    let lock_path = manifest.dir().join(Lock::FILE_NAME);
     let members = [(name, manifest)].into_iter().collect();
     // TODO: Print fetching process here when remote deps included.
-    let plan = pint_pkg::plan::from_members(&members).context("failed to plan compilation")?;
+    let plan = pint_pkg::plan::from_lock_file(&members, &lock_path, args.update)
+        .context("failed to plan compilation")?;
 
     // Build the given compilation plan.
     let mut builder = pint_pkg::build::build_plan(&plan);
@@ -0,0 +1,58 @@
+//! `pint explain` implementation.
+
+use clap::Parser;
+use pint_pkg::pintc::error::{self, Explanation};
+
+/// Print a detailed explanation of an error code.
+#[derive(Parser, Debug)]
+pub(crate) struct Args {
+    /// The error code to explain, as shown in brackets before a diagnostic, e.g.
+    /// `symbol_not_found`.
+    code: String,
+}
+
+/// Find the explanation for `code`, or produce an error if `code` is unknown or has no
+/// explanation yet.
+fn explanation(code: &str) -> anyhow::Result<&'static str> {
+    match error::explain(code) {
+        Some(Explanation::Found(explanation)) => Ok(explanation),
+        Some(Explanation::Missing) => {
+            anyhow::bail!("there is no detailed explanation for `{code}` yet")
+        }
+        None => anyhow::bail!("`{code}` is not a known error code"),
+    }
+}
+
+pub(crate) fn cmd(args: Args) -> anyhow::Result<()> {
+    print!("{}", explanation(&args.code)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_code() {
+        let text = explanation("symbol_not_found").unwrap();
+        assert!(!text.trim().is_empty());
+    }
+
+    #[test]
+    fn unknown_code() {
+        let err = explanation("not_a_real_code").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`not_a_real_code` is not a known error code"
+        );
+    }
+
+    #[test]
+    fn unexplained_code() {
+        let err = explanation("internal").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "there is no detailed explanation for `internal` yet"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! `pint init` implementation.
+
+use crate::new::{kind_from_bools, print_created};
+use clap::Parser;
+use pint_pkg::new::init_pkg;
+use std::path::PathBuf;
+
+/// Create a new package in an existing directory.
+#[derive(Parser, Debug)]
+pub(crate) struct Args {
+    /// Specify the "contract" package kind.
+    ///
+    /// This is the default behaviour.
+    #[arg(long)]
+    contract: bool,
+    /// Specify the "library" package kind.
+    ///
+    /// By default, new packages are created with the "contract" kind.
+    #[arg(long)]
+    lib: bool,
+    /// Optionally provide a name.
+    ///
+    /// By default, the package name is the last directory in the canonicalized
+    /// representation of the given path.
+    #[arg(long)]
+    name: Option<String>,
+    /// The directory path in which the package should be created.
+    ///
+    /// Unlike `pint new`, the directory may already contain other files.
+    #[arg(default_value = ".")]
+    path: PathBuf,
+}
+
+pub(crate) fn cmd(args: Args) -> anyhow::Result<()> {
+    let name = args.name;
+    let kind = kind_from_bools(args.contract, args.lib)?;
+    let opts = pint_pkg::new::Options { name, kind };
+    let manifest_path = init_pkg(&args.path, opts)?;
+    print_created(&manifest_path)
+}
@@ -2,6 +2,8 @@ use anyhow::Context;
 use clap::{builder::styling::Style, CommandFactory, Parser, Subcommand};
 
 mod build;
+mod explain;
+mod init;
 mod new;
 mod plugin;
 
@@ -20,6 +22,8 @@ struct Pint {
 enum Cmd {
     #[command(alias = "b")]
     Build(build::Args),
+    Explain(explain::Args),
+    Init(init::Args),
     New(new::Args),
     /// Print all pint plugins found in `PATH`.
     Plugins,
@@ -42,7 +46,9 @@ fn run() -> anyhow::Result<()> {
     let pint = Pint::parse();
     match pint.cmd {
         Cmd::New(arg) => new::cmd(arg),
+        Cmd::Init(arg) => init::cmd(arg),
         Cmd::Build(arg) => build::cmd(arg),
+        Cmd::Explain(arg) => explain::cmd(arg),
         Cmd::Plugins => {
             plugin::print_all();
             Ok(())
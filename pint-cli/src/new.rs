@@ -5,7 +5,7 @@ use pint_pkg::{
     manifest::{ManifestFile, PackageKind},
     new::new_pkg,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Create a new package.
 #[derive(Parser, Debug)]
@@ -30,7 +30,7 @@ pub(crate) struct Args {
     path: PathBuf,
 }
 
-fn kind_from_bools(contract: bool, lib: bool) -> anyhow::Result<Option<PackageKind>> {
+pub(crate) fn kind_from_bools(contract: bool, lib: bool) -> anyhow::Result<Option<PackageKind>> {
     let opt = match (contract, lib) {
         (false, false) => None,
         (true, false) => Some(PackageKind::Contract),
@@ -45,7 +45,12 @@ pub(crate) fn cmd(args: Args) -> anyhow::Result<()> {
     let kind = kind_from_bools(args.contract, args.lib)?;
     let opts = pint_pkg::new::Options { name, kind };
     let manifest_path = new_pkg(&args.path, opts)?;
-    let manifest = ManifestFile::from_path(&manifest_path)?;
+    print_created(&manifest_path)
+}
+
+/// Print a summary of the package created with the manifest at the given path.
+pub(crate) fn print_created(manifest_path: &Path) -> anyhow::Result<()> {
+    let manifest = ManifestFile::from_path(manifest_path)?;
     let bold = Style::new().bold();
     println!(
         "     {}Created{} {} [{}] ({})",
@@ -54,6 +54,12 @@ pub struct Package {
     /// - `Library`: "lib.pnt"
     #[serde(rename = "entry-point")]
     pub entry_point: Option<String>,
+    /// The salt of the contract as 32 bytes of hex, optionally prefixed with `0x`. The salt
+    /// contributes to the contract's address, allowing otherwise identical contracts to be
+    /// deployed to distinct addresses.
+    ///
+    /// If unspecified, the salt is all zeroes.
+    pub salt: Option<String>,
 }
 
 /// Whether the package is to be compiled as a contract or library.
@@ -104,6 +110,17 @@ pub enum InvalidName {
     Reserved,
 }
 
+/// The manifest specifies an invalid contract salt.
+#[derive(Debug, Error)]
+pub enum InvalidSalt {
+    /// Must contain only hexadecimal digits.
+    #[error("must contain only hexadecimal digits")]
+    NonHexChar,
+    /// Must be exactly 32 bytes, i.e. 64 hexadecimal digits.
+    #[error("must be exactly 32 bytes, found {0} hexadecimal digits")]
+    InvalidLength(usize),
+}
+
 /// The parsed package kind was invalid.
 #[derive(Debug, Error)]
 #[error(r#"failed to parse package kind, expected "contract" or "library""#)]
@@ -121,6 +138,9 @@ pub enum InvalidManifest {
     /// Dependency name appears more than once.
     #[error("dependency name {0:?} appears more than once")]
     DupDepName(String),
+    /// Manifest specifies an invalid contract salt.
+    #[error("manifest specifies an invalid salt {0:?}: {1}")]
+    Salt(String, InvalidSalt),
 }
 
 /// Failure to parse and construct a manifest from a string.
@@ -160,6 +180,18 @@ impl Manifest {
                 PackageKind::Library => Self::DEFAULT_LIBRARY_ENTRY_POINT,
             })
     }
+
+    /// The specified contract salt, or all zeroes if unspecified.
+    ///
+    /// Panics if the salt is invalid, which is not possible for a manifest which has passed
+    /// [`check`].
+    pub fn salt(&self) -> [u8; 32] {
+        self.pkg
+            .salt
+            .as_deref()
+            .map(|salt| parse_salt(salt).expect("manifest salt was not validated"))
+            .unwrap_or_default()
+    }
 }
 
 impl ManifestFile {
@@ -220,6 +252,7 @@ impl ManifestFile {
                 true => Some(dep.path.to_owned()),
                 false => dir.join(&dep.path).canonicalize().ok(),
             },
+            dependency::Source::Git(_) => None,
         }
     }
 }
@@ -285,9 +318,31 @@ pub fn check(manifest: &Manifest) -> Result<(), InvalidManifest> {
         }
     }
 
+    // Check the salt.
+    if let Some(salt) = &manifest.pkg.salt {
+        parse_salt(salt).map_err(|e| InvalidManifest::Salt(salt.to_string(), e))?;
+    }
+
     Ok(())
 }
 
+/// Parse a contract salt from 32 bytes of hex, optionally prefixed with `0x`.
+pub fn parse_salt(salt: &str) -> Result<[u8; 32], InvalidSalt> {
+    let digits = salt.strip_prefix("0x").unwrap_or(salt);
+    if !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(InvalidSalt::NonHexChar);
+    }
+    if digits.len() != 64 {
+        return Err(InvalidSalt::InvalidLength(digits.len()));
+    }
+    let mut bytes = [0; 32];
+    for (byte, pair) in bytes.iter_mut().zip(digits.as_bytes().chunks(2)) {
+        let pair = str::from_utf8(pair).expect("hex digits are ASCII");
+        *byte = u8::from_str_radix(pair, 16).expect("validated hex digits");
+    }
+    Ok(bytes)
+}
+
 /// Package names must only contain ASCII non-uppercase alphanumeric chars, dashes or underscores.
 pub fn check_name_char(ch: char) -> bool {
     (ch.is_ascii_alphanumeric() && !ch.is_uppercase()) || ch == '-' || ch == '_'
@@ -366,6 +421,8 @@ pub mod dependency {
     pub enum Source {
         /// Depends on another package directly via a path to its root directory.
         Path(Path),
+        /// Depends on a package at the root of a git repository.
+        Git(Git),
     }
 
     /// A path dependency.
@@ -374,6 +431,25 @@ pub mod dependency {
         /// The path to the dependency's root directory.
         pub path: std::path::PathBuf,
     }
+
+    /// A git dependency.
+    ///
+    /// At most one of `branch`, `tag` or `rev` may be specified. If none are
+    /// specified, the latest commit of the repository's default branch is used.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+    pub struct Git {
+        /// The URL of the git repository.
+        pub git: String,
+        /// The branch to track.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub branch: Option<String>,
+        /// The tag to check out.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub tag: Option<String>,
+        /// The revision (e.g. commit hash) to check out.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub rev: Option<String>,
+    }
 }
 
 /// Serialize and Deserialize implementations that serialize via `Option`.
@@ -99,3 +99,38 @@ fn invalid_end_underscore() {
     "#;
     Manifest::from_str(toml_str).unwrap();
 }
+
+#[test]
+fn valid_salt() {
+    let toml_str = r#"
+        [package]
+        name = "foo"
+        salt = "0x000000000000000000000000000000000000000000000000000000000000002a"
+    "#;
+    let manifest = Manifest::from_str(toml_str).unwrap();
+    let mut expected = [0; 32];
+    expected[31] = 42;
+    assert_eq!(manifest.salt(), expected);
+}
+
+#[test]
+#[should_panic]
+fn invalid_salt_length() {
+    let toml_str = r#"
+        [package]
+        name = "foo"
+        salt = "0x2a"
+    "#;
+    Manifest::from_str(toml_str).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn invalid_salt_char() {
+    let toml_str = r#"
+        [package]
+        name = "foo"
+        salt = "0x00000000000000000000000000000000000000000000000000000000000000zz"
+    "#;
+    Manifest::from_str(toml_str).unwrap();
+}
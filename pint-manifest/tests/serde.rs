@@ -28,6 +28,31 @@ fn all_fields() {
     check_roundtrip(&manifest);
 }
 
+#[test]
+fn git_deps() {
+    let toml_str = r#"
+        [package]
+        name = "foo"
+
+        [dependencies]
+        bar = { git = "https://github.com/org/bar" }
+        baz = { git = "https://github.com/org/baz", branch = "dev" }
+        qux = { git = "https://github.com/org/qux", tag = "v1.0.0", package = "quux" }
+
+        [contract-dependencies]
+        corge = { git = "https://github.com/org/corge", rev = "3f2c1e0" }
+    "#;
+    let manifest: Manifest = toml::from_str(toml_str).unwrap();
+    check_roundtrip(&manifest);
+    let baz = &manifest.deps["baz"].source;
+    let pint_manifest::dependency::Source::Git(git) = baz else {
+        panic!("expected a git dependency, found {baz:?}");
+    };
+    assert_eq!(git.git, "https://github.com/org/baz");
+    assert_eq!(git.branch.as_deref(), Some("dev"));
+    assert_eq!(git.tag, None);
+}
+
 #[test]
 fn minimal() {
     let toml_str = r#"
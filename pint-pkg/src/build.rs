@@ -8,7 +8,7 @@ use essential_types::{
     contract::Contract, predicate::Predicate as CompiledPredicate, ContentAddress,
 };
 use pint_abi_types::ContractABI;
-use pintc::asm_gen::compile_contract;
+use pintc::{asm_gen::compile_contract, predicate::CompileOptions};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
@@ -124,6 +124,8 @@ pub enum PintcError {
     TypeCheck,
     #[error("flattening error")]
     Flatten,
+    #[error("optimization error")]
+    Optimize,
     #[error("abi gen")]
     ABIGen,
     #[error("asm-gen error")]
@@ -200,7 +202,7 @@ impl BuildPkgError {
     /// Consume `self` and print the errors and the warnings.
     pub fn print_diagnostics(self) {
         let (errors, warnings) = self.handler.consume();
-        pintc::error::print_errors(&pintc::error::Errors(errors));
+        pintc::error::print_errors(&pintc::error::Errors(errors), None);
         pintc::warning::print_warnings(&pintc::warning::Warnings(warnings));
     }
 }
@@ -265,7 +267,7 @@ fn dependencies<'a>(
 /// Returns the entry point to the library.
 fn contract_dep_lib(
     ca: &ContentAddress,
-    predicates: &[BuiltPredicate],
+    predicates: &[PredicateMetadata],
 ) -> std::io::Result<PathBuf> {
     // Temporary directory for the contract project.
     let temp_dir = std::env::temp_dir().join(format!("{:x}", ca));
@@ -347,7 +349,11 @@ fn build_pkg(
             let optimized = if skip_optimize {
                 flattened
             } else {
-                flattened.optimize(&handler)
+                let Ok(optimized) = handler.scope(|handler| Ok(flattened.optimize(handler))) else {
+                    let kind = BuildPkgErrorKind::from(PintcError::Optimize);
+                    return Err(BuildPkgError { handler, kind });
+                };
+                optimized
             };
 
             // Produce the ABI for the flattened contract.
@@ -357,7 +363,11 @@ fn build_pkg(
             };
 
             // Generate the assembly and the predicates.
-            let Ok(contract) = handler.scope(|h| compile_contract(h, &optimized)) else {
+            let options = CompileOptions {
+                salt: manifest.salt(),
+                ..Default::default()
+            };
+            let Ok(contract) = handler.scope(|h| compile_contract(h, &optimized, &options)) else {
                 let kind = BuildPkgErrorKind::from(PintcError::AsmGen);
                 return Err(BuildPkgError { handler, kind });
             };
@@ -377,22 +387,7 @@ fn build_pkg(
                 })
                 .collect();
 
-            // The CA of the contract.
-            let ca = essential_hash::contract_addr::from_predicate_addrs(
-                predicates.iter().map(|predicate| predicate.ca.clone()),
-                &contract.salt,
-            );
-
-            // Generate a temp lib for providing the contract and predicate CAs to dependents.
-            let lib_entry_point = match contract_dep_lib(&ca, &predicates) {
-                Ok(path) => path,
-                Err(e) => {
-                    let kind = BuildPkgErrorKind::ContractLibrary(pinned.name.clone(), e);
-                    return Err(BuildPkgError { handler, kind });
-                }
-            };
-
-            let (predicate_metadata, predicates) = predicates
+            let (predicate_metadata, predicates): (Vec<_>, _) = predicates
                 .into_iter()
                 .map(
                     |BuiltPredicate {
@@ -402,15 +397,28 @@ fn build_pkg(
                      }| (PredicateMetadata { ca, name }, predicate),
                 )
                 .unzip();
+            let contract = Contract {
+                predicates,
+                salt: contract.salt,
+            };
+
+            // The CA of the contract.
+            let ca = contract_content_address(&contract);
+
+            // Generate a temp lib for providing the contract and predicate CAs to dependents.
+            let lib_entry_point = match contract_dep_lib(&ca, &predicate_metadata) {
+                Ok(path) => path,
+                Err(e) => {
+                    let kind = BuildPkgErrorKind::ContractLibrary(pinned.name.clone(), e);
+                    return Err(BuildPkgError { handler, kind });
+                }
+            };
 
             let contract = BuiltContract {
                 warnings: pintc::warning::Warnings(handler.consume().1),
                 ca,
                 predicate_metadata,
-                contract: Contract {
-                    predicates,
-                    salt: contract.salt,
-                },
+                contract,
                 lib_entry_point,
                 abi,
                 optimized,
@@ -422,6 +430,19 @@ fn build_pkg(
     Ok(built_pkg)
 }
 
+/// Compute the content address of the given compiled contract.
+///
+/// This is the same address assigned to [`BuiltContract::ca`] during the build,
+/// derived from the content address of each predicate in order along with the
+/// contract's salt. It depends only on the given contract, allowing tools to
+/// verify a contract's address without rebuilding the package.
+pub fn contract_content_address(contract: &Contract) -> ContentAddress {
+    essential_hash::contract_addr::from_predicate_addrs(
+        contract.predicates.iter().map(essential_hash::content_addr),
+        &contract.salt,
+    )
+}
+
 /// Given a compilation [`Plan`][crate::plan::Plan], return a [`PlanBuilder`]
 /// that may be used to compile all packages within the graph.
 pub fn build_plan(plan: &Plan) -> PlanBuilder {
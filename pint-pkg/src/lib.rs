@@ -5,6 +5,7 @@ pub use pint_manifest as manifest;
 pub use pintc;
 
 pub mod build;
+pub mod lock;
 pub mod new;
 pub mod plan;
 pub mod source;
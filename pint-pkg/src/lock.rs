@@ -0,0 +1,190 @@
+//! Items related to the `pint.lock` file.
+//!
+//! The lock file records the pinned source of every package within the
+//! package graph so that subsequent builds may reproduce the same graph.
+
+use crate::{
+    plan::{Dep, DepKind, Graph, NodeIx, Pinned},
+    source,
+};
+use petgraph::{visit::EdgeRef, Direction};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt, fs, io,
+    path::Path,
+    str,
+};
+use thiserror::Error;
+
+/// The contents of a `pint.lock` file.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Lock {
+    /// Every package within the graph, ordered by name and then source.
+    #[serde(default, rename = "package")]
+    pub packages: BTreeSet<PkgLock>,
+}
+
+/// A locked package.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+pub struct PkgLock {
+    /// The name declared in the package manifest.
+    pub name: String,
+    /// The pinned source of the package in its string representation.
+    pub source: String,
+    /// The package's library dependencies.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub dependencies: BTreeSet<PkgDepLock>,
+    /// The package's contract dependencies.
+    #[serde(
+        default,
+        rename = "contract-dependencies",
+        skip_serializing_if = "BTreeSet::is_empty"
+    )]
+    pub contract_dependencies: BTreeSet<PkgDepLock>,
+}
+
+/// A locked dependency edge.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+pub struct PkgDepLock {
+    /// The dependency name used to refer to the package.
+    pub name: String,
+    /// The name declared in the dependency's manifest.
+    pub package: String,
+    /// The pinned source of the dependency in its string representation.
+    pub source: String,
+}
+
+/// Failed to read or write a lock file.
+#[derive(Debug, Error)]
+pub enum LockFileError {
+    #[error("an IO error occurred while reading or writing the lock file: {0}")]
+    Io(#[from] io::Error),
+    /// Failed to construct the lock.
+    #[error("{0}")]
+    Lock(#[from] LockError),
+}
+
+/// The lock is invalid.
+#[derive(Debug, Error)]
+pub enum LockError {
+    /// Failed to deserialize the lock from toml.
+    #[error("failed to deserialize lock from toml: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// A package's source could not be parsed.
+    #[error("invalid source for locked package {0:?}: {1}")]
+    Source(String, source::PinnedFromStrError),
+    /// A dependency refers to a package that does not appear within the lock.
+    #[error("locked package {0:?} depends on {1:?} ({2}) which is not in the lock")]
+    DepNotFound(String, String, String),
+}
+
+impl Lock {
+    /// The name of the lock file, located alongside the member's `pint.toml`.
+    pub const FILE_NAME: &'static str = "pint.lock";
+
+    /// Create a lock from the given package graph.
+    pub fn from_graph(graph: &Graph) -> Self {
+        let packages = graph.node_indices().map(|n| pkg_lock(graph, n)).collect();
+        Self { packages }
+    }
+
+    /// Construct the package graph described by the lock.
+    pub fn to_graph(&self) -> Result<Graph, LockError> {
+        let mut graph = Graph::default();
+        let mut nodes: HashMap<(&str, &str), NodeIx> = HashMap::default();
+        for pkg in &self.packages {
+            let source = pkg
+                .source
+                .parse()
+                .map_err(|e| LockError::Source(pkg.name.clone(), e))?;
+            let name = pkg.name.clone();
+            let node = graph.add_node(Pinned { name, source });
+            nodes.insert((&pkg.name[..], &pkg.source[..]), node);
+        }
+        for pkg in &self.packages {
+            let node = nodes[&(&pkg.name[..], &pkg.source[..])];
+            let deps = pkg
+                .dependencies
+                .iter()
+                .map(|dep| (dep, DepKind::Library))
+                .chain(
+                    pkg.contract_dependencies
+                        .iter()
+                        .map(|dep| (dep, DepKind::Contract)),
+                );
+            for (dep, kind) in deps {
+                let Some(&dep_node) = nodes.get(&(&dep.package[..], &dep.source[..])) else {
+                    return Err(LockError::DepNotFound(
+                        pkg.name.clone(),
+                        dep.package.clone(),
+                        dep.source.clone(),
+                    ));
+                };
+                let name = dep.name.clone();
+                graph.update_edge(node, dep_node, Dep { kind, name });
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Load the lock file at the given path.
+    pub fn from_path(path: &Path) -> Result<Self, LockFileError> {
+        let string = fs::read_to_string(path)?;
+        let lock: Self = string.parse()?;
+        Ok(lock)
+    }
+
+    /// Write the lock to the given path.
+    ///
+    /// The file is only written if its contents would change, returning
+    /// whether or not it was written.
+    pub fn write_to_path(&self, path: &Path) -> Result<bool, LockFileError> {
+        let string = self.to_string();
+        if fs::read_to_string(path).is_ok_and(|existing| existing == string) {
+            return Ok(false);
+        }
+        fs::write(path, string)?;
+        Ok(true)
+    }
+}
+
+impl str::FromStr for Lock {
+    type Err = LockError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lock: Self = toml::from_str(s)?;
+        Ok(lock)
+    }
+}
+
+impl fmt::Display for Lock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = toml::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{string}")
+    }
+}
+
+/// Create the locked form of the package at the given node.
+fn pkg_lock(graph: &Graph, n: NodeIx) -> PkgLock {
+    let pinned = &graph[n];
+    let mut dependencies = BTreeSet::new();
+    let mut contract_dependencies = BTreeSet::new();
+    for edge in graph.edges_directed(n, Direction::Outgoing) {
+        let dep = &graph[edge.target()];
+        let dep_lock = PkgDepLock {
+            name: edge.weight().name.clone(),
+            package: dep.name.clone(),
+            source: dep.source.to_string(),
+        };
+        match edge.weight().kind {
+            DepKind::Library => dependencies.insert(dep_lock),
+            DepKind::Contract => contract_dependencies.insert(dep_lock),
+        };
+    }
+    PkgLock {
+        name: pinned.name.clone(),
+        source: pinned.source.to_string(),
+        dependencies,
+        contract_dependencies,
+    }
+}
@@ -8,7 +8,7 @@ use std::{
 };
 use thiserror::Error;
 
-/// Options for the `new_pkg` function.
+/// Options for the `new_pkg` and `init_pkg` functions.
 #[derive(Debug, Default)]
 pub struct Options {
     /// A name for the package.
@@ -32,6 +32,9 @@ pub enum NewPkgError {
     /// The given directory already contains a pint manifest.
     #[error("the given directory already contains a pint manifest: {0:?}")]
     ManifestExists(PathBuf),
+    /// The given directory is not empty.
+    #[error("the given directory is not empty: {0:?}")]
+    DirectoryNotEmpty(PathBuf),
     /// Failed to retrieve a package name from the given path.
     #[error("failed to retrieve package name from given path: {0:?}")]
     NameFromPath(PathBuf),
@@ -42,10 +45,24 @@ pub enum NewPkgError {
 
 /// Create a new package at the given path.
 ///
-/// If the directory does not yet exist, it will be created.
+/// If the directory does not yet exist, it will be created. If it does exist, it must be empty.
+/// Use [`init_pkg`] to create a package within a directory that already has other contents.
 ///
 /// On success, returns the path to the package's manifest.
 pub fn new_pkg(path: &Path, opts: Options) -> Result<PathBuf, NewPkgError> {
+    if path.is_dir() && path.read_dir()?.next().is_some() {
+        return Err(NewPkgError::DirectoryNotEmpty(path.to_path_buf()));
+    }
+    init_pkg(path, opts)
+}
+
+/// Create a new package in the given directory, which may already contain other files.
+///
+/// If the directory does not yet exist, it will be created. Existing files are left untouched,
+/// though the directory must not already contain a pint manifest.
+///
+/// On success, returns the path to the package's manifest.
+pub fn init_pkg(path: &Path, opts: Options) -> Result<PathBuf, NewPkgError> {
     let manifest_path = path.join(ManifestFile::FILE_NAME);
     let src_path = path.join("src");
 
@@ -93,12 +110,18 @@ pub fn new_pkg(path: &Path, opts: Options) -> Result<PathBuf, NewPkgError> {
         fs::write(pnt_path, pnt_string)?;
     }
 
-    // Create or append to .gitignore file.
+    // Create or append to .gitignore file, making sure an existing last line is terminated.
     let gitignore_path = path.join(".gitignore");
+    let needs_newline = fs::read_to_string(&gitignore_path)
+        .map(|existing| !existing.is_empty() && !existing.ends_with('\n'))
+        .unwrap_or(false);
     let mut gitignore_file = fs::OpenOptions::new()
         .append(true)
         .create(true)
         .open(gitignore_path)?;
+    if needs_newline {
+        gitignore_file.write_all(b"\n")?;
+    }
     gitignore_file.write_all(GITIGNORE.as_bytes())?;
 
     Ok(manifest_path)
@@ -1,6 +1,7 @@
 //! Items related to construction of the compilation [`Plan`].
 
 use crate::{
+    lock::{self, Lock},
     manifest::{self, Dependency, ManifestFile},
     source::{self, Source},
 };
@@ -116,6 +117,12 @@ pub enum PlanError {
     /// A cycle was detected in the package graph.
     #[error("{0}")]
     DependencyCycle(#[from] DependencyCycle),
+    /// Failed to read or write the lock file.
+    #[error("failed to load or update lock file: {0}")]
+    LockFile(#[from] lock::LockFileError),
+    /// The lock file's package graph is invalid.
+    #[error("invalid lock file: {0}")]
+    Lock(#[from] lock::LockError),
 }
 
 #[derive(Debug, Error)]
@@ -298,9 +305,61 @@ impl str::FromStr for PinnedId {
 ///
 /// Fetches and pins all packages as a part of constructing the full compilation plan.
 pub fn from_members(members: &MemberManifests) -> Result<Plan, PlanError> {
-    // Fetch the graph and populate the pinned manifests.
-    let mut graph = Graph::default();
-    let mut pinned_manifests = PinnedManifests::default();
+    let graph = Graph::default();
+    let pinned_manifests = PinnedManifests::default();
+    complete_plan(members, graph, pinned_manifests)
+}
+
+/// Construct a compilation plan from the given member manifests, preferring
+/// the pinned sources recorded within the given lock.
+///
+/// Locked packages that are no longer valid for the member manifests are
+/// removed, and any dependencies that are not locked are fetched and pinned.
+pub fn from_lock(members: &MemberManifests, lock: &Lock) -> Result<Plan, PlanError> {
+    let mut graph = lock.to_graph()?;
+
+    // Restore any locked git checkouts that are missing. Failures here are
+    // left to the graph check below, after which the package is re-pinned.
+    let fetch_id = source::fetch_graph_id(Path::new(Lock::FILE_NAME), std::time::Instant::now());
+    for pinned in graph.node_weights() {
+        if let source::Pinned::Git(git) = &pinned.source {
+            let _ = git.checkout(&pinned.name, fetch_id);
+        }
+    }
+
+    // Remove invalid dependencies along with the packages no longer reachable from a member.
+    let (mut pinned_manifests, invalid_deps) = check_graph(&graph, members);
+    remove_deps(&mut graph, members, &invalid_deps);
+    pinned_manifests.retain(|id, _| graph.node_weights().any(|pinned| pinned.id() == *id));
+
+    complete_plan(members, graph, pinned_manifests)
+}
+
+/// Construct a compilation plan for the members using the lock file at the given path.
+///
+/// Unless `update` is `true`, the pinned sources within an existing lock file
+/// are preferred. The lock file is then written to reflect the new plan.
+pub fn from_lock_file(
+    members: &MemberManifests,
+    lock_path: &Path,
+    update: bool,
+) -> Result<Plan, PlanError> {
+    let plan = match update || !lock_path.exists() {
+        true => from_members(members)?,
+        false => from_lock(members, &Lock::from_path(lock_path)?)?,
+    };
+    Lock::from_graph(plan.graph()).write_to_path(lock_path)?;
+    Ok(plan)
+}
+
+/// Complete a compilation plan from the given, potentially partial, graph and
+/// its associated `PinnedManifests` map.
+fn complete_plan(
+    members: &MemberManifests,
+    mut graph: Graph,
+    mut pinned_manifests: PinnedManifests,
+) -> Result<Plan, PlanError> {
+    // Fetch the remainder of the graph and populate the pinned manifests.
     fetch_graph(members, &mut graph, &mut pinned_manifests)?;
 
     // TODO: Remove this block, just a sanity check.
@@ -457,7 +516,7 @@ fn fetch_deps(
             hash_map::Entry::Vacant(entry) => {
                 let pkg = entry.key();
                 let ctx = source::PinCtx {
-                    _fetch_id: ctx.id,
+                    fetch_id: ctx.id,
                     path_root,
                     pkg_name: &pkg.name,
                 };
@@ -489,7 +548,7 @@ fn fetch_deps(
             .map_err(|e| FetchGraphError::DepManifest(graph[node].name.clone(), dep_name, e))?;
 
         let path_root = match dep_pinned.source {
-            source::Pinned::Member(_) => dep_pkg_id,
+            source::Pinned::Member(_) | source::Pinned::Git(_) => dep_pkg_id,
             source::Pinned::Path(_) => path_root,
         };
 
@@ -532,6 +591,25 @@ fn member_nodes_with_name<'a>(
     member_nodes(g).filter(move |&n| g[n].name == pkg_name)
 }
 
+/// Remove the given invalid dependency edges from the graph, along with all
+/// nodes that are no longer reachable from one of the given members.
+fn remove_deps(graph: &mut Graph, members: &MemberManifests, invalid_deps: &InvalidDeps) {
+    for &edge in invalid_deps.keys() {
+        graph.remove_edge(edge);
+    }
+    let roots: Vec<_> = member_nodes(graph)
+        .filter(|&n| members.contains_key(&graph[n].name))
+        .collect();
+    let mut reachable = HashSet::new();
+    for root in roots {
+        let mut dfs = petgraph::visit::Dfs::new(&*graph, root);
+        while let Some(n) = dfs.next(&*graph) {
+            reachable.insert(n);
+        }
+    }
+    graph.retain_nodes(|_, n| reachable.contains(&n));
+}
+
 /// Validate the graph against the given member manifests.
 ///
 /// Returns a map of `PinnedManifests` for all valid nodes within the graph,
@@ -707,7 +785,7 @@ fn find_path_root(graph: &Graph, mut node: NodeIx) -> Result<NodeIx, FindPathRoo
     loop {
         let pkg = &graph[node];
         match pkg.source {
-            source::Pinned::Member(_) => return Ok(node),
+            source::Pinned::Member(_) | source::Pinned::Git(_) => return Ok(node),
             source::Pinned::Path(ref src) => {
                 let parent = graph
                     .edges_directed(node, Direction::Incoming)
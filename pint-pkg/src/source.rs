@@ -10,9 +10,11 @@ use std::{
     fmt,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    str,
 };
 use thiserror::Error;
 
+pub mod git;
 mod member;
 mod path;
 
@@ -45,6 +47,8 @@ pub enum Source {
     Member(member::Source),
     /// A path to a directory with a `pint.toml` manifest at its root.
     Path(path::Source),
+    /// A git repository with a `pint.toml` manifest at its root.
+    Git(git::Source),
 }
 
 // The pinned form of a package source.
@@ -52,14 +56,14 @@ pub enum Source {
 pub enum Pinned {
     Member(member::Pinned),
     Path(path::Pinned),
+    Git(git::Pinned),
 }
 
 /// The context provided to the pinning and fetching of a source type.
 #[derive(Clone)]
 pub(crate) struct PinCtx<'a> {
     /// A unique ID associated with the current fetch.
-    // TODO: Use this for remote deps.
-    pub(crate) _fetch_id: FetchId,
+    pub(crate) fetch_id: FetchId,
     /// The current package graph path root.
     pub(crate) path_root: PinnedId,
     /// The name of the package being pinned.
@@ -70,8 +74,6 @@ pub(crate) enum DependencyPath {
     /// The dependency is another member of the workspace.
     Member,
     /// The dependency is located at this specific path.
-    // TODO: Rm this `allow` when introducing remote deps.
-    #[allow(dead_code)]
     ManifestPath(PathBuf),
     /// Path is pinned via manifest, relative to the given root node.
     Root(PinnedId),
@@ -82,8 +84,15 @@ pub(crate) enum DependencyPath {
 pub enum SourceError {
     #[error("failed to canonicalize path {0:?}: {1}")]
     FailedToCanonicalizePath(std::path::PathBuf, std::io::Error),
+    #[error("git dependency {0:?} must specify at most one of `branch`, `tag` or `rev`")]
+    MultipleGitReferences(String),
 }
 
+/// Failed to parse a pinned source from its string representation.
+#[derive(Debug, Error)]
+#[error("failed to parse pinned source from {0:?}")]
+pub struct PinnedFromStrError(String);
+
 /// Failed to pin or fetch the source of a dependency.
 #[derive(Debug, Error)]
 pub enum PinAndFetchError {
@@ -93,6 +102,9 @@ pub enum PinAndFetchError {
     /// Failed to pin and fetch a member dependency.
     #[error("{0}")]
     Member(#[from] PinAndFetchErrorKind<member::Source>),
+    /// Failed to pin and fetch a git dependency.
+    #[error("{0}")]
+    Git(#[from] PinAndFetchErrorKind<git::Source>),
 }
 
 /// Failed to pin or fetch the source of a particular dependency source type.
@@ -146,6 +158,17 @@ impl Source {
             manifest::dependency::Source::Path(path) => {
                 Self::from_relative_path(manifest_dir, &path.path, member_manifests)
             }
+            manifest::dependency::Source::Git(git) => {
+                let reference = match (&git.branch, &git.tag, &git.rev) {
+                    (None, None, None) => git::Reference::DefaultBranch,
+                    (Some(branch), None, None) => git::Reference::Branch(branch.clone()),
+                    (None, Some(tag), None) => git::Reference::Tag(tag.clone()),
+                    (None, None, Some(rev)) => git::Reference::Rev(rev.clone()),
+                    _ => return Err(SourceError::MultipleGitReferences(git.git.clone())),
+                };
+                let repo = git.git.clone();
+                Ok(Source::Git(git::Source { repo, reference }))
+            }
         }
     }
 
@@ -162,6 +185,7 @@ impl Source {
         match self {
             Source::Member(source) => Ok(Pinned::Member(pin_and_fetch(source, ctx, manifests)?)),
             Source::Path(source) => Ok(Pinned::Path(pin_and_fetch(source, ctx, manifests)?)),
+            Source::Git(source) => Ok(Pinned::Git(pin_and_fetch(source, ctx, manifests)?)),
         }
     }
 }
@@ -175,6 +199,7 @@ impl Pinned {
         match self {
             Self::Member(_) => Source::Member(member::Source(path.to_owned())),
             Self::Path(_) => Source::Path(path.to_owned()),
+            Self::Git(pinned) => Source::Git(pinned.source.clone()),
         }
     }
 
@@ -183,6 +208,7 @@ impl Pinned {
         match self {
             Self::Member(pinned) => Ok(pinned.dep_path(name).expect("infallible")),
             Self::Path(pinned) => Ok(pinned.dep_path(name).expect("infallible")),
+            Self::Git(pinned) => Ok(pinned.dep_path(name).expect("infallible")),
         }
     }
 }
@@ -192,6 +218,26 @@ impl fmt::Display for Pinned {
         match self {
             Self::Member(p) => p.fmt(f),
             Self::Path(p) => p.fmt(f),
+            Self::Git(p) => p.fmt(f),
+        }
+    }
+}
+
+impl str::FromStr for Pinned {
+    type Err = PinnedFromStrError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || PinnedFromStrError(s.to_string());
+        if s == "member" {
+            Ok(Self::MEMBER)
+        } else if let Some(path_root) = s.strip_prefix("path+root=") {
+            let path_root = path_root.parse().map_err(|_| err())?;
+            Ok(Self::Path(path::Pinned { path_root }))
+        } else if let Some(url) = s.strip_prefix("git+") {
+            git::Pinned::from_url_str(url)
+                .map(Self::Git)
+                .ok_or_else(err)
+        } else {
+            Err(err())
         }
     }
 }
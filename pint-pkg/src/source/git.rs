@@ -0,0 +1,434 @@
+//! Git source implementation.
+//!
+//! Git repositories are retrieved using the `git` executable. Each repository
+//! is fetched into a bare clone within the [`db_dir`], which is then used to
+//! check out commits into the [`checkouts_dir`], where each checkout is keyed
+//! by its commit hash.
+
+use crate::{
+    manifest::{ManifestFile, ManifestFileError},
+    source,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use thiserror::Error;
+
+/// A git repository along with the reference that should be checked out.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+pub struct Source {
+    /// The URL of the git repository.
+    pub repo: String,
+    /// The reference to check out.
+    pub reference: Reference,
+}
+
+/// A reference to a commit within a git repository.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+pub enum Reference {
+    /// The latest commit of the given branch.
+    Branch(String),
+    /// The commit pointed to by the given tag.
+    Tag(String),
+    /// A specific revision, e.g. a commit hash.
+    Rev(String),
+    /// The latest commit of the repository's default branch.
+    DefaultBranch,
+}
+
+/// A pinned instance of a git source.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub struct Pinned {
+    /// The git source that was pinned.
+    pub source: Source,
+    /// The full hash of the commit that the source's reference resolved to.
+    pub commit_hash: String,
+}
+
+/// Failed to pin or fetch a git source.
+#[derive(Debug, Error)]
+pub enum GitError {
+    /// The `git` executable could not be run.
+    #[error("failed to run `git`, ensure that it is installed: {0}")]
+    Command(io::Error),
+    /// The repository URL would be interpreted by `git` as an option.
+    #[error("invalid git repository {0:?}, the URL must not begin with `-`")]
+    InvalidRepo(String),
+    /// The reference would be interpreted by `git` as an option.
+    #[error("invalid {0} for git repository {1:?}, it must not begin with `-`")]
+    InvalidReference(Reference, String),
+    /// The repository could not be retrieved, e.g. due to the network being unavailable.
+    #[error(
+        "failed to fetch git repository {0:?}, check that the URL is correct \
+        and that you are online: {1}"
+    )]
+    Fetch(String, String),
+    /// The reference does not point to a commit within the repository.
+    #[error("failed to resolve {0} in git repository {1:?}: {2}")]
+    Resolve(Reference, String, String),
+    /// The resolved commit could not be checked out.
+    #[error("failed to check out commit {0} of git repository {1:?}: {2}")]
+    Checkout(String, String, String),
+    /// An I/O error occurred while managing the checkout directory.
+    #[error("an I/O error occurred: {0}")]
+    Io(#[from] io::Error),
+    /// The checked out package's manifest is missing or invalid.
+    #[error("{0}")]
+    ManifestFile(#[from] ManifestFileError),
+}
+
+impl Source {
+    /// Reject a repository URL or reference which `git` would interpret as an option, e.g.
+    /// `--upload-pack=<command>`.
+    fn validate(&self) -> Result<(), GitError> {
+        if self.repo.starts_with('-') {
+            return Err(GitError::InvalidRepo(self.repo.clone()));
+        }
+        match &self.reference {
+            Reference::Branch(name) | Reference::Tag(name) | Reference::Rev(name)
+                if name.starts_with('-') =>
+            {
+                Err(GitError::InvalidReference(
+                    self.reference.clone(),
+                    self.repo.clone(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolve the reference to a full commit hash within the repository's bare clone at `db`.
+    fn resolve(&self, db: &Path) -> Result<String, GitError> {
+        let rev = match &self.reference {
+            Reference::Branch(branch) => format!("refs/remotes/origin/{branch}"),
+            Reference::Tag(tag) => format!("refs/tags/{tag}"),
+            Reference::Rev(rev) => rev.clone(),
+            Reference::DefaultBranch => "refs/remotes/origin/HEAD".to_string(),
+        };
+        let rev = format!("{rev}^{{commit}}");
+        git(git_cmd()
+            .arg("-C")
+            .arg(db)
+            .args(["rev-parse", "--verify", "--end-of-options", &rev]))?
+        .map_err(|e| GitError::Resolve(self.reference.clone(), self.repo.clone(), e))
+    }
+}
+
+impl Pinned {
+    /// Ensure that the pinned commit is checked out, fetching the repository
+    /// if its bare clone doesn't already contain the commit.
+    ///
+    /// Used to restore packages that were pinned by a previous fetch, e.g.
+    /// those recorded within a lock file.
+    pub(crate) fn checkout(
+        &self,
+        pkg_name: &str,
+        fetch_id: source::FetchId,
+    ) -> Result<(), GitError> {
+        self.source.validate()?;
+        let repo = &self.source.repo;
+        let repo_dir = repo_checkouts_dir(pkg_name, repo);
+        let path = repo_dir.join(&self.commit_hash);
+        if path.join(ManifestFile::FILE_NAME).exists() {
+            return Ok(());
+        }
+        let db = repo_db_dir(pkg_name, repo);
+        if !has_commit(&db, &self.commit_hash)? {
+            fetch_db(repo, &db)?;
+        }
+        with_staging_clone(&db, repo, &repo_dir, fetch_id, |staging| {
+            checkout(staging, &self.commit_hash, repo)?;
+            move_checkout(staging, &path)
+        })
+    }
+}
+
+impl source::Pin for Source {
+    type Pinned = Pinned;
+    type Error = GitError;
+    fn pin(&self, ctx: source::PinCtx) -> Result<(Self::Pinned, PathBuf), Self::Error> {
+        self.validate()?;
+        let repo_dir = repo_checkouts_dir(ctx.pkg_name, &self.repo);
+
+        // A full commit hash needs no resolving, so if it is already checked
+        // out there's no need to access the repository at all.
+        if let Reference::Rev(rev) = &self.reference {
+            let path = repo_dir.join(rev);
+            if is_commit_hash(rev) && path.join(ManifestFile::FILE_NAME).exists() {
+                let source = self.clone();
+                let commit_hash = rev.clone();
+                return Ok((
+                    Pinned {
+                        source,
+                        commit_hash,
+                    },
+                    path,
+                ));
+            }
+        }
+
+        let db = repo_db_dir(ctx.pkg_name, &self.repo);
+        fetch_db(&self.repo, &db)?;
+        let commit_hash = self.resolve(&db)?;
+        let path = repo_dir.join(&commit_hash);
+        if !path.join(ManifestFile::FILE_NAME).exists() {
+            with_staging_clone(&db, &self.repo, &repo_dir, ctx.fetch_id, |staging| {
+                checkout(staging, &commit_hash, &self.repo)?;
+                move_checkout(staging, &path)
+            })?;
+        }
+        let source = self.clone();
+        Ok((
+            Pinned {
+                source,
+                commit_hash,
+            },
+            path,
+        ))
+    }
+}
+
+impl source::Fetch for Pinned {
+    type Error = GitError;
+    fn fetch(&self, _ctx: source::PinCtx, local: &Path) -> Result<ManifestFile, Self::Error> {
+        let manifest_path = local.join(ManifestFile::FILE_NAME);
+        let manifest = ManifestFile::from_path(&manifest_path)?;
+        Ok(manifest)
+    }
+}
+
+impl source::DepPath for Pinned {
+    type Error = core::convert::Infallible;
+    fn dep_path(&self, name: &str) -> Result<source::DependencyPath, Self::Error> {
+        let path = repo_checkouts_dir(name, &self.source.repo).join(&self.commit_hash);
+        Ok(source::DependencyPath::ManifestPath(path))
+    }
+}
+
+impl Reference {
+    /// The reference in the form used within a pinned source's string representation.
+    fn to_query(&self) -> String {
+        match self {
+            Self::Branch(branch) => format!("branch={branch}"),
+            Self::Tag(tag) => format!("tag={tag}"),
+            Self::Rev(rev) => format!("rev={rev}"),
+            Self::DefaultBranch => "default-branch".to_string(),
+        }
+    }
+
+    /// Parse a reference from the form produced by `to_query`.
+    fn from_query(s: &str) -> Option<Self> {
+        let reference = match s.split_once('=') {
+            Some(("branch", branch)) => Self::Branch(branch.to_string()),
+            Some(("tag", tag)) => Self::Tag(tag.to_string()),
+            Some(("rev", rev)) => Self::Rev(rev.to_string()),
+            None if s == "default-branch" => Self::DefaultBranch,
+            _ => return None,
+        };
+        Some(reference)
+    }
+}
+
+impl Pinned {
+    /// Parse a pinned git source from the form produced by its `Display`
+    /// implementation, without the leading `git+`.
+    pub(crate) fn from_url_str(s: &str) -> Option<Self> {
+        let (s, commit_hash) = s.rsplit_once('#')?;
+        let (repo, query) = s.rsplit_once('?')?;
+        let reference = Reference::from_query(query)?;
+        let repo = repo.to_string();
+        let source = Source { repo, reference };
+        // The commit hash names the checkout directory, so it mustn't be able to name any other.
+        if !is_commit_hash(commit_hash) {
+            return None;
+        }
+        let commit_hash = commit_hash.to_string();
+        Some(Self {
+            source,
+            commit_hash,
+        })
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Branch(branch) => write!(f, "branch `{branch}`"),
+            Self::Tag(tag) => write!(f, "tag `{tag}`"),
+            Self::Rev(rev) => write!(f, "rev `{rev}`"),
+            Self::DefaultBranch => write!(f, "the default branch"),
+        }
+    }
+}
+
+impl fmt::Display for Pinned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let query = self.source.reference.to_query();
+        write!(f, "git+{}?{query}#{}", self.source.repo, self.commit_hash)
+    }
+}
+
+impl From<Pinned> for source::Pinned {
+    fn from(p: Pinned) -> Self {
+        source::Pinned::Git(p)
+    }
+}
+
+/// The directory in which git dependencies are checked out.
+///
+/// This is `$PINT_HOME/git/checkouts`, where `PINT_HOME` defaults to `$HOME/.pint`.
+pub fn checkouts_dir() -> PathBuf {
+    git_dir().join("checkouts")
+}
+
+/// The directory in which the bare clones of git dependencies' repositories are kept.
+///
+/// This is `$PINT_HOME/git/db`, where `PINT_HOME` defaults to `$HOME/.pint`.
+pub fn db_dir() -> PathBuf {
+    git_dir().join("db")
+}
+
+/// The `$PINT_HOME/git` directory.
+fn git_dir() -> PathBuf {
+    let pint_home = match std::env::var_os("PINT_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir)
+            .join(".pint"),
+    };
+    pint_home.join("git")
+}
+
+/// The name of the directories used for the given package's repository.
+///
+/// The repository URL's hash is stable across builds of `pint` so that
+/// previous checkouts and clones are found again.
+fn repo_dirname(pkg_name: &str, repo: &str) -> String {
+    let hash = essential_hash::hash(&repo);
+    let hex: String = hash[..8].iter().map(|b| format!("{b:02x}")).collect();
+    format!("{pkg_name}-{hex}")
+}
+
+/// The directory containing all checkouts of the given package's repository.
+fn repo_checkouts_dir(pkg_name: &str, repo: &str) -> PathBuf {
+    checkouts_dir().join(repo_dirname(pkg_name, repo))
+}
+
+/// The bare clone of the given package's repository.
+fn repo_db_dir(pkg_name: &str, repo: &str) -> PathBuf {
+    db_dir().join(repo_dirname(pkg_name, repo))
+}
+
+/// Whether the given revision is a full, lowercase SHA-1 commit hash.
+fn is_commit_hash(rev: &str) -> bool {
+    rev.len() == 40 && rev.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+/// Fetch the branches and tags of `repo` into its bare clone at `db`, creating
+/// the clone first if it doesn't exist.
+///
+/// Branches are fetched as `refs/remotes/origin/*` and the remote's default
+/// branch as `refs/remotes/origin/HEAD`.
+fn fetch_db(repo: &str, db: &Path) -> Result<(), GitError> {
+    if !db.join("HEAD").exists() {
+        git(git_cmd().args(["init", "--quiet", "--bare"]).arg(db))?
+            .map_err(|e| GitError::Fetch(repo.to_string(), e))?;
+    }
+    git(git_cmd()
+        .arg("-C")
+        .arg(db)
+        .args(["fetch", "--quiet", "--force", "--prune", "--"])
+        .arg(repo)
+        .args([
+            "+HEAD:refs/remotes/origin/HEAD",
+            "+refs/heads/*:refs/remotes/origin/*",
+            "+refs/tags/*:refs/tags/*",
+        ]))?
+    .map_err(|e| GitError::Fetch(repo.to_string(), e))?;
+    Ok(())
+}
+
+/// Whether the bare clone at `db` exists and contains the given commit.
+fn has_commit(db: &Path, commit_hash: &str) -> Result<bool, GitError> {
+    if !db.join("HEAD").exists() {
+        return Ok(false);
+    }
+    let rev = format!("{commit_hash}^{{commit}}");
+    let res = git(git_cmd()
+        .arg("-C")
+        .arg(db)
+        .args(["cat-file", "-e", "--end-of-options", &rev]))?;
+    Ok(res.is_ok())
+}
+
+/// Clone the bare clone at `db` into a staging directory unique to the given
+/// fetch and call `f` with its path.
+///
+/// The staging directory is removed afterwards unless `f` moved it into place.
+fn with_staging_clone<T>(
+    db: &Path,
+    repo: &str,
+    repo_dir: &Path,
+    fetch_id: source::FetchId,
+    f: impl FnOnce(&Path) -> Result<T, GitError>,
+) -> Result<T, GitError> {
+    let staging = repo_dir.join(format!(".fetch-{fetch_id:016x}"));
+    fs::create_dir_all(repo_dir)?;
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let res = git(git_cmd()
+        .args(["clone", "--quiet", "--no-checkout", "--"])
+        .arg(db)
+        .arg(&staging))?
+    .map_err(|e| GitError::Fetch(repo.to_string(), e))
+    .and_then(|_| f(&staging));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    res
+}
+
+/// Move a completed checkout from the staging directory to its final location.
+fn move_checkout(staging: &Path, path: &Path) -> Result<(), GitError> {
+    if path.exists() {
+        fs::remove_dir_all(path)?;
+    }
+    fs::rename(staging, path)?;
+    Ok(())
+}
+
+/// Check out the given commit within the repository at `dir`.
+fn checkout(dir: &Path, commit_hash: &str, repo: &str) -> Result<(), GitError> {
+    git(git_cmd().arg("-C").arg(dir).args([
+        "checkout",
+        "--quiet",
+        "--detach",
+        "--end-of-options",
+        commit_hash,
+    ]))?
+    .map_err(|e| GitError::Checkout(commit_hash.to_string(), repo.to_string(), e))
+}
+
+/// A `git` command that fails rather than prompting the user for credentials.
+fn git_cmd() -> Command {
+    let mut cmd = Command::new("git");
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+    cmd
+}
+
+/// Run the given `git` command.
+///
+/// Returns the command's trimmed stdout on success, or its trimmed stderr on failure.
+fn git(cmd: &mut Command) -> Result<Result<String, String>, GitError> {
+    let output = cmd.output().map_err(GitError::Command)?;
+    let res = match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    };
+    Ok(res)
+}
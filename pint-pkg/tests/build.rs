@@ -4,7 +4,7 @@
 
 use essential_types::{ContentAddress, Word};
 use pint_pkg::{
-    build::{build_plan, BuiltPkg},
+    build::{build_plan, contract_content_address, BuiltPkg},
     manifest::PackageKind,
 };
 use util::{edit_manifest, insert_dep, new_pkg, with_temp_dir};
@@ -35,6 +35,60 @@ fn build_default_library() {
     });
 }
 
+#[test]
+fn build_contract_content_address() {
+    with_temp_dir(|dir| {
+        let foo = new_pkg(&dir.join("foo"), PackageKind::Contract);
+        let members = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+        let plan = pint_pkg::plan::from_members(&members).unwrap();
+        let build = || {
+            let mut built_pkgs = build_plan(&plan)
+                .build_all(false /* skip_optimize */)
+                .unwrap();
+            let n = *plan.compilation_order().last().unwrap();
+            match built_pkgs.remove(&n) {
+                Some(BuiltPkg::Contract(contract)) => contract,
+                _ => panic!("expected `foo` to be a contract"),
+            }
+        };
+        let built = build();
+
+        // The address is deterministic and matches that produced by the build.
+        let ca = contract_content_address(&built.contract);
+        assert_eq!(ca, contract_content_address(&built.contract));
+        assert_eq!(ca, built.ca);
+
+        // Building again produces the same address.
+        assert_eq!(build().ca, built.ca);
+    });
+}
+
+#[test]
+fn build_contract_with_salt() {
+    const SALT: &str = "0x0101010101010101020202020202020203030303030303030404040404040404";
+    with_temp_dir(|dir| {
+        let mut foo = new_pkg(&dir.join("foo"), PackageKind::Contract);
+        edit_manifest(&mut foo, |m| m.pkg.salt = Some(SALT.to_string()));
+        let members = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+        let plan = pint_pkg::plan::from_members(&members).unwrap();
+        let mut built_pkgs = build_plan(&plan)
+            .build_all(false /* skip_optimize */)
+            .unwrap();
+        let n = *plan.compilation_order().last().unwrap();
+        let Some(BuiltPkg::Contract(built)) = built_pkgs.remove(&n) else {
+            panic!("expected `foo` to be a contract");
+        };
+
+        // The salt from the manifest is used for the contract and contributes to its address.
+        let mut expected = [0; 32];
+        for (i, chunk) in expected.chunks_mut(8).enumerate() {
+            chunk.fill(i as u8 + 1);
+        }
+        assert_eq!(built.contract.salt, expected);
+        assert_eq!(built.ca, contract_content_address(&built.contract));
+    });
+}
+
 // Simple graph `foo` -> `bar`, i.e. foo depends on bar.
 #[test]
 fn build_contract_one_lib_dep() {
@@ -0,0 +1,146 @@
+//! Tests for the `pint.lock` file.
+
+#![allow(clippy::disallowed_names)]
+
+use pint_pkg::{
+    lock::Lock,
+    manifest::{dependency, PackageKind},
+    plan::{self, MemberManifests, Plan},
+    source,
+};
+use std::{fs, path::Path};
+use util::{
+    edit_manifest, git, insert_dep, insert_git_dep, new_git_pkg, new_pkg, set_pint_home,
+    with_temp_dir,
+};
+
+mod util;
+
+/// The commit that the git dependency named `name` is pinned to within the plan.
+fn pinned_commit(plan: &Plan, name: &str) -> String {
+    let pinned = plan
+        .graph()
+        .node_weights()
+        .find(|pinned| pinned.name == name)
+        .unwrap();
+    match &pinned.source {
+        source::Pinned::Git(git) => git.commit_hash.clone(),
+        source => panic!("expected git source, found {source:?}"),
+    }
+}
+
+/// Add a new commit to the `main` branch of the given bare repo via a clone at `clone_dir`.
+fn push_commit(repo_dir: &Path, clone_dir: &Path) -> String {
+    let repo = repo_dir.display().to_string();
+    let clone = clone_dir.display().to_string();
+    git(repo_dir, &["clone", "--quiet", &repo, &clone]);
+    fs::write(clone_dir.join("README.md"), "bar").unwrap();
+    git(clone_dir, &["add", "--all"]);
+    git(clone_dir, &["commit", "--quiet", "--message", "readme"]);
+    git(clone_dir, &["push", "--quiet", "origin", "main"]);
+    git(repo_dir, &["rev-parse", "main"])
+}
+
+#[test]
+fn stable_lock() {
+    set_pint_home();
+    with_temp_dir(|dir| {
+        // Create dependencies foo -> bar via path and foo -> baz via git.
+        let mut foo = new_pkg(&dir.join("foo"), PackageKind::Contract);
+        let bar = new_pkg(&dir.join("bar"), PackageKind::Library);
+        let baz_repo = new_git_pkg(dir, "baz", PackageKind::Library);
+        let baz = dependency::Git {
+            git: baz_repo.display().to_string(),
+            branch: None,
+            tag: None,
+            rev: None,
+        };
+        edit_manifest(&mut foo, |m| {
+            insert_dep(m, &bar);
+            insert_git_dep(m, "baz", PackageKind::Library, baz);
+        });
+        let lock_path = foo.dir().join(Lock::FILE_NAME);
+        let members: MemberManifests = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+
+        // The first plan creates the lock file.
+        let plan = plan::from_lock_file(&members, &lock_path, false).unwrap();
+        let lock_str = fs::read_to_string(&lock_path).unwrap();
+        let lock: Lock = lock_str.parse().unwrap();
+        assert_eq!(lock, Lock::from_graph(plan.graph()));
+        assert_eq!(lock.packages.len(), 3);
+
+        // Planning again from the lock file reproduces the same lock file.
+        let plan2 = plan::from_lock_file(&members, &lock_path, false).unwrap();
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), lock_str);
+        assert_eq!(plan2.graph().node_count(), 3);
+        assert_eq!(pinned_commit(&plan, "baz"), pinned_commit(&plan2, "baz"));
+
+        // The lock's graph matches the plan's graph.
+        let graph = lock.to_graph().unwrap();
+        assert_eq!(graph.node_count(), plan.graph().node_count());
+        assert_eq!(graph.edge_count(), plan.graph().edge_count());
+    });
+}
+
+#[test]
+fn update_lock() {
+    set_pint_home();
+    with_temp_dir(|dir| {
+        // Create dependency foo -> bar via git.
+        let mut foo = new_pkg(&dir.join("foo"), PackageKind::Contract);
+        let bar_repo = new_git_pkg(dir, "bar", PackageKind::Library);
+        let first_commit = git(&bar_repo, &["rev-parse", "main"]);
+        let bar = dependency::Git {
+            git: bar_repo.display().to_string(),
+            branch: Some("main".to_string()),
+            tag: None,
+            rev: None,
+        };
+        edit_manifest(&mut foo, |m| {
+            insert_git_dep(m, "bar", PackageKind::Library, bar)
+        });
+        let lock_path = foo.dir().join(Lock::FILE_NAME);
+        let members: MemberManifests = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+
+        // Lock `bar` to the first commit.
+        let plan = plan::from_lock_file(&members, &lock_path, false).unwrap();
+        assert_eq!(pinned_commit(&plan, "bar"), first_commit);
+
+        // After `main` moves on, the locked commit is still used unless updating.
+        let second_commit = push_commit(&bar_repo, &dir.join("bar-clone"));
+        let plan = plan::from_lock_file(&members, &lock_path, false).unwrap();
+        assert_eq!(pinned_commit(&plan, "bar"), first_commit);
+        let plan = plan::from_lock_file(&members, &lock_path, true).unwrap();
+        assert_eq!(pinned_commit(&plan, "bar"), second_commit);
+
+        // Tamper with the lock file to pin the first commit again. This is
+        // only re-resolved when updating.
+        let lock_str = fs::read_to_string(&lock_path).unwrap();
+        let tampered = lock_str.replace(&second_commit, &first_commit);
+        fs::write(&lock_path, &tampered).unwrap();
+        let plan = plan::from_lock_file(&members, &lock_path, false).unwrap();
+        assert_eq!(pinned_commit(&plan, "bar"), first_commit);
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), tampered);
+        let plan = plan::from_lock_file(&members, &lock_path, true).unwrap();
+        assert_eq!(pinned_commit(&plan, "bar"), second_commit);
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), lock_str);
+    });
+}
+
+#[test]
+fn invalid_pinned_commit_hash() {
+    // The commit hash names the checkout directory, so anything else is rejected.
+    let hash = "0123456789abcdef0123456789abcdef01234567";
+    let valid = format!("git+https://example.com/bar.git?branch=main#{hash}");
+    assert!(valid.parse::<source::Pinned>().is_ok());
+    for commit_hash in [
+        "../../..",
+        "main",
+        "--detach",
+        &hash[..39],
+        &hash.to_uppercase(),
+    ] {
+        let pinned = format!("git+https://example.com/bar.git?branch=main#{commit_hash}");
+        assert!(pinned.parse::<source::Pinned>().is_err(), "{pinned}");
+    }
+}
@@ -1,6 +1,9 @@
 //! Tests for the `new_pkg` fn.
 
-use pint_pkg::{manifest::ManifestFile, new::new_pkg};
+use pint_pkg::{
+    manifest::ManifestFile,
+    new::{init_pkg, new_pkg, NewPkgError},
+};
 use std::path::Path;
 use util::with_temp_dir;
 
@@ -80,3 +83,61 @@ fn current_dir() {
         assert_eq!(manifest.pkg.name, "foo");
     });
 }
+
+#[test]
+fn entry_point_exists() {
+    with_temp_dir(|dir| {
+        for kind in ["contract", "library"] {
+            let path = dir.join(format!("my-{kind}"));
+            let opts = pint_pkg::new::Options {
+                name: None,
+                kind: Some(kind.parse().unwrap()),
+            };
+            let manifest_path = new_pkg(&path, opts).unwrap();
+            let manifest = ManifestFile::from_path(&manifest_path).unwrap();
+            assert_eq!(manifest.pkg.kind.to_string(), kind);
+            assert!(manifest.entry_point().is_file());
+        }
+    });
+}
+
+#[test]
+fn new_non_empty_dir() {
+    with_temp_dir(|dir| {
+        let path = dir.join("foo");
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("README.md"), "# foo").unwrap();
+
+        let opts = pint_pkg::new::Options::default();
+        let err = new_pkg(&path, opts).unwrap_err();
+        assert!(matches!(err, NewPkgError::DirectoryNotEmpty(_)));
+        assert!(!path.join(ManifestFile::FILE_NAME).exists());
+    });
+}
+
+#[test]
+fn init_non_empty_dir() {
+    with_temp_dir(|dir| {
+        let path = dir.join("foo");
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("README.md"), "# foo").unwrap();
+        std::fs::write(path.join(".gitignore"), "target").unwrap();
+
+        let opts = pint_pkg::new::Options::default();
+        let manifest_path = init_pkg(&path, opts).unwrap();
+        let manifest = ManifestFile::from_path(&manifest_path).unwrap();
+        assert_eq!(manifest.pkg.name, "foo");
+        assert!(manifest.entry_point().is_file());
+
+        // Existing files are left alone, other than appending to the `.gitignore`.
+        let readme = std::fs::read_to_string(path.join("README.md")).unwrap();
+        assert_eq!(readme, "# foo");
+        let gitignore = std::fs::read_to_string(path.join(".gitignore")).unwrap();
+        assert_eq!(gitignore, "target\nout");
+
+        // A second `init` would overwrite the manifest.
+        let opts = pint_pkg::new::Options::default();
+        let err = init_pkg(&path, opts).unwrap_err();
+        assert!(matches!(err, NewPkgError::ManifestExists(_)));
+    });
+}
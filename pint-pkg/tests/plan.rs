@@ -2,8 +2,11 @@
 
 #![allow(clippy::disallowed_names)]
 
-use pint_pkg::manifest::PackageKind;
-use util::{edit_manifest, insert_dep, new_pkg, with_temp_dir};
+use pint_pkg::{manifest::PackageKind, source};
+use util::{
+    edit_manifest, git, insert_dep, insert_git_dep, new_git_pkg, new_pkg, set_pint_home,
+    with_temp_dir,
+};
 
 mod util;
 
@@ -161,3 +164,120 @@ fn cycle() {
         let _plan = pint_pkg::plan::from_members(&members).unwrap();
     });
 }
+
+#[test]
+fn git_dep() {
+    set_pint_home();
+    with_temp_dir(|dir| {
+        // Commit a library package to a git repo, then clone it as a bare repo.
+        let repo_dir = new_git_pkg(dir, "bar", PackageKind::Library);
+        let commit_hash = git(&repo_dir, &["rev-parse", "HEAD"]);
+
+        // Create dependency foo -> bar via the bare repo.
+        let mut foo = new_pkg(&dir.join("foo"), PackageKind::Library);
+        let repo = repo_dir.display().to_string();
+        let bar = pint_pkg::manifest::dependency::Git {
+            git: repo.clone(),
+            branch: Some("main".to_string()),
+            tag: None,
+            rev: None,
+        };
+        edit_manifest(&mut foo, |m| {
+            insert_git_dep(m, "bar", PackageKind::Library, bar)
+        });
+
+        // Create the plan.
+        let members = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+        let plan = pint_pkg::plan::from_members(&members).unwrap();
+
+        // Bar should be pinned to the commit at the head of `main`.
+        let graph = plan.graph();
+        let order = plan.compilation_order();
+        assert_eq!(graph[order[0]].name, "bar");
+        assert_eq!(graph[order[1]].name, "foo");
+        let source::Pinned::Git(pinned) = &graph[order[0]].source else {
+            panic!("expected git source, found {:?}", graph[order[0]].source);
+        };
+        assert_eq!(pinned.source.repo, repo);
+        assert_eq!(pinned.commit_hash, commit_hash);
+
+        // The package should be checked out at the pinned commit.
+        let bar_manifest = &plan.manifests()[&graph[order[0]].id()];
+        let checkouts_dir = source::git::checkouts_dir().canonicalize().unwrap();
+        assert!(bar_manifest.dir().starts_with(checkouts_dir));
+        assert!(bar_manifest.dir().ends_with(&commit_hash));
+        assert_eq!(bar_manifest.pkg.name, "bar");
+
+        // The checkout was made from a bare clone of the repository.
+        let dirname = bar_manifest.dir().parent().unwrap().file_name().unwrap();
+        let db = source::git::db_dir().join(dirname);
+        assert_eq!(git(&db, &["cat-file", "-t", &commit_hash]), "commit");
+    });
+}
+
+#[test]
+fn git_dep_unreachable() {
+    set_pint_home();
+    with_temp_dir(|dir| {
+        // Depend on a repo that does not exist, as though we were offline.
+        let mut foo = new_pkg(&dir.join("foo"), PackageKind::Library);
+        let repo = dir.join("missing.git").display().to_string();
+        let bar = pint_pkg::manifest::dependency::Git {
+            git: repo,
+            branch: None,
+            tag: None,
+            rev: None,
+        };
+        edit_manifest(&mut foo, |m| {
+            insert_git_dep(m, "bar", PackageKind::Library, bar)
+        });
+
+        // Creating the plan should fail with a clear error.
+        let members = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+        let err = pint_pkg::plan::from_members(&members).unwrap_err();
+        assert!(
+            err.to_string().contains("failed to fetch git repository"),
+            "{err}"
+        );
+    });
+}
+
+#[test]
+fn git_dep_option_injection() {
+    set_pint_home();
+    with_temp_dir(|dir| {
+        let repo_dir = new_git_pkg(dir, "bar", PackageKind::Library);
+        let marker = dir.join("injected");
+
+        // Neither a repo URL nor a reference may be passed to `git` as an option.
+        let cases = [
+            (
+                format!("--upload-pack=touch {}", marker.display()),
+                None,
+                "the URL must not begin with `-`",
+            ),
+            (
+                repo_dir.display().to_string(),
+                Some(format!("--output={}", marker.display())),
+                "it must not begin with `-`",
+            ),
+        ];
+        for (ix, (repo, rev, expected)) in cases.into_iter().enumerate() {
+            let mut foo = new_pkg(&dir.join(format!("foo{ix}")), PackageKind::Library);
+            let bar = pint_pkg::manifest::dependency::Git {
+                git: repo,
+                branch: None,
+                tag: None,
+                rev,
+            };
+            edit_manifest(&mut foo, |m| {
+                insert_git_dep(m, "bar", PackageKind::Library, bar)
+            });
+
+            let members = [(foo.pkg.name.to_string(), foo)].into_iter().collect();
+            let err = pint_pkg::plan::from_members(&members).unwrap_err();
+            assert!(err.to_string().contains(expected), "{err}");
+            assert!(!marker.exists());
+        }
+    });
+}
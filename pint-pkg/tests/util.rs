@@ -3,7 +3,12 @@
 #![allow(dead_code)]
 
 use pint_pkg::manifest::{self, Manifest, ManifestFile};
-use std::{fs, panic::UnwindSafe, path::Path};
+use std::{
+    fs,
+    panic::UnwindSafe,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 /// Create a temporary directory with a random hash based on current timestamp
 /// and call the given function with access to it.
@@ -68,3 +73,62 @@ pub(crate) fn insert_dep(manifest: &mut Manifest, dep: &ManifestFile) {
         manifest::PackageKind::Library => manifest.deps.insert(name, dep),
     };
 }
+
+/// Add a dependency on the package at the root of the given git `repo`.
+pub(crate) fn insert_git_dep(
+    manifest: &mut Manifest,
+    name: &str,
+    kind: manifest::PackageKind,
+    git: manifest::dependency::Git,
+) {
+    let source = manifest::dependency::Source::Git(git);
+    let package = None;
+    let dep = manifest::Dependency { source, package };
+    match kind {
+        manifest::PackageKind::Contract => manifest.contract_deps.insert(name.to_string(), dep),
+        manifest::PackageKind::Library => manifest.deps.insert(name.to_string(), dep),
+    };
+}
+
+/// Run `git` with the given args within `dir`, returning the trimmed stdout.
+///
+/// Panics if the command fails.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=pint", "-c", "user.email=pint@example.com"])
+        .args(args)
+        .output()
+        .expect("failed to run `git`");
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// Create a new package of the given kind within a git repo at `dir/name`,
+/// commit it, then clone it into the bare repo `dir/name.git`.
+///
+/// Returns the path to the bare repo.
+pub(crate) fn new_git_pkg(dir: &Path, name: &str, kind: manifest::PackageKind) -> PathBuf {
+    let pkg_dir = dir.join(name);
+    new_pkg(&pkg_dir, kind);
+    git(&pkg_dir, &["init", "--quiet", "--initial-branch=main"]);
+    git(&pkg_dir, &["add", "--all"]);
+    git(&pkg_dir, &["commit", "--quiet", "--message", "init"]);
+    let repo_name = format!("{name}.git");
+    git(dir, &["clone", "--quiet", "--bare", name, &repo_name]);
+    dir.join(repo_name)
+}
+
+/// Point `PINT_HOME` at a directory shared by all tests in this process so
+/// that git checkouts do not end up in the user's home directory.
+///
+/// Checkouts are keyed by repository URL, so tests using distinct
+/// repositories do not interfere with one another.
+pub(crate) fn set_pint_home() {
+    static PINT_HOME: std::sync::Once = std::sync::Once::new();
+    PINT_HOME.call_once(|| {
+        let dir = std::env::temp_dir().join("pint-pkg-tests-home");
+        std::env::set_var("PINT_HOME", dir);
+    });
+}
@@ -4,4 +4,8 @@ use thiserror::Error;
 pub enum SolveError {
     #[error("solver internal error: {msg}")]
     Internal { msg: &'static str },
+    #[error("solve directive objective `{name}` is not a declared variable")]
+    UnknownObjective { name: String },
+    #[error("cannot enumerate solutions for `real` variable `{name}`")]
+    RealEnumeration { name: String },
 }
@@ -7,11 +7,16 @@ mod variable;
 
 use crate::{
     error::SolveError,
-    flatpint::{evaluate::evaluate_expr, Decl, FlatPint, Immediate, Solve},
+    flatpint::{
+        evaluate::evaluate_expr, BinaryOp, Constraint, Decl, Expr, FlatPint, Immediate, Solve,
+    },
 };
 use fxhash::FxHashMap;
 use russcip::{prelude::*, ProblemCreated, Solved};
 
+/// A single solution which maps decision variable names to their values.
+pub type Solution = FxHashMap<String, Immediate>;
+
 pub struct Solver<'a, State> {
     model: Model<State>,
     flatpint: &'a FlatPint,
@@ -54,6 +59,19 @@ impl<'a, State> Solver<'a, State> {
 
 impl<'a> Solver<'a, ProblemCreated> {
     pub fn solve(mut self) -> Result<Solver<'a, Solved>, SolveError> {
+        // The objective, if any, must be one of the declared variables. Otherwise the objective
+        // function would silently be 0.
+        if let Solve::Minimize(name) | Solve::Maximize(name) = &self.flatpint.solve {
+            if !self
+                .flatpint
+                .decls
+                .iter()
+                .any(|decl| matches!(decl, Decl::Var(var) if var.name == *name))
+            {
+                return Err(SolveError::UnknownObjective { name: name.clone() });
+            }
+        }
+
         // Convert all variables first
         for decl in &self.flatpint.decls {
             if let Decl::Var(var) = decl {
@@ -77,6 +95,74 @@ impl<'a> Solver<'a, ProblemCreated> {
     }
 }
 
+impl<'a> Solver<'a, ProblemCreated> {
+    /// Find up to `max` distinct solutions for the decision variables.
+    ///
+    /// After each solve the solution found is excluded from the problem by an extra constraint
+    /// and the problem is solved again, until either `max` solutions are found or the problem
+    /// becomes infeasible. For optimization problems the solutions are returned from best to
+    /// worst. Only `bool` and `int` decision variables can be enumerated.
+    pub fn solve_n(&self, max: usize) -> Result<Vec<Solution>, SolveError> {
+        let mut flatpint = self.flatpint.clone();
+        let mut solutions = Vec::new();
+
+        while solutions.len() < max {
+            let solution = {
+                let solved = Solver::<ProblemCreated>::new(&flatpint).solve()?;
+                if !matches!(solved.model.status(), Status::Optimal) {
+                    break;
+                }
+                solved.solution()
+            };
+
+            let exclusion = Self::exclude_solution(&solution)?;
+            flatpint.decls.push(Decl::Constraint(Constraint(exclusion)));
+            solutions.push(solution);
+        }
+
+        Ok(solutions)
+    }
+
+    /// Build a constraint expression which is violated by `solution` alone, i.e., at least one of
+    /// the decision variables must take a different value.
+    fn exclude_solution(solution: &Solution) -> Result<Expr, SolveError> {
+        // Sort by name so that the produced constraint is deterministic.
+        let mut assignments = solution.iter().collect::<Vec<_>>();
+        assignments.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        assignments
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Immediate::Bool(val) => Immediate::Int(*val as i64),
+                    Immediate::Int(val) => Immediate::Int(*val),
+                    Immediate::Real(_) => {
+                        return Err(SolveError::RealEnumeration { name: name.clone() })
+                    }
+                };
+
+                Ok(Expr::BinaryOp {
+                    op: BinaryOp::NotEqual,
+                    lhs: Box::new(Expr::Path(name.clone())),
+                    rhs: Box::new(Expr::Immediate(value)),
+                })
+            })
+            .try_fold(None, |acc, expr| {
+                let expr = expr?;
+                Ok(Some(match acc {
+                    Some(acc) => Expr::BinaryOp {
+                        op: BinaryOp::LogicalOr,
+                        lhs: Box::new(acc),
+                        rhs: Box::new(expr),
+                    },
+                    None => expr,
+                }))
+            })
+            // Without any decision variables there is only a single solution.
+            .map(|expr| expr.unwrap_or(Expr::Immediate(Immediate::Bool(false))))
+    }
+}
+
 impl<'a> super::Solver<'a, Solved> {
     /// Returns a `FxHashMap` that contains a solution. It maps decision variable names, as
     /// `String`s, to `flatpint::Immediate` values. Returns an empty `FxHashMap` in case a solution
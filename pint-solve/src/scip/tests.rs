@@ -119,3 +119,75 @@ solve minimize obj;
             objective: -9.000"#]],
     );
 }
+
+#[test]
+fn minimize_linear() {
+    let src = r#"
+var x: int;
+var y: int;
+var obj: int;
+constraint (x >= 3);
+constraint (y >= (x - 1));
+constraint (obj == ((2 * x) + y));
+solve minimize obj;
+"#;
+
+    check(
+        &run_solver!(fyp::FlatPintParser::new(), src),
+        expect_test::expect![[r#"
+            x: 3
+            y: 2
+            obj: 8
+            objective: 8.000"#]],
+    );
+}
+
+#[test]
+fn unknown_objective() {
+    let src = r#"
+var x: int;
+constraint (x >= 3);
+solve minimize obj;
+"#;
+
+    check(
+        &run_solver!(fyp::FlatPintParser::new(), src),
+        expect_test::expect!["solve directive objective `obj` is not a declared variable"],
+    );
+}
+
+#[test]
+fn solve_n() {
+    let src = r#"
+var x: int;
+var y: bool;
+constraint (x >= 0);
+constraint (x <= 2);
+solve satisfy;
+"#;
+
+    let flatpint = fyp::FlatPintParser::new().parse(src).unwrap();
+    let solver = crate::solver(&flatpint);
+
+    // There are exactly 6 feasible assignments of `x` and `y`.
+    let solutions = solver.solve_n(10).unwrap();
+    assert_eq!(solutions.len(), 6);
+
+    let mut distinct = solutions
+        .iter()
+        .map(|solution| {
+            let mut assignments = solution
+                .iter()
+                .map(|(name, value)| format!("{name}: {value:?}"))
+                .collect::<Vec<_>>();
+            assignments.sort();
+            assignments.join(", ")
+        })
+        .collect::<Vec<_>>();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 6);
+
+    // The number of solutions is capped by `max`.
+    assert_eq!(solver.solve_n(4).unwrap().len(), 4);
+}
@@ -1,31 +1,128 @@
 use crate::{
     error::{CompileError, Error, ErrorEmitted, Handler},
     expr::{Expr, ExternalIntrinsic, Immediate, IntrinsicKind},
-    predicate::{ConstraintDecl, Contract, Predicate},
-    span::empty_span,
+    predicate::{
+        CompileOptions, ConstraintDecl, Contract, Predicate, State, VisitorKind,
+        MAX_REAL_SCALE_BITS,
+    },
+    span::{empty_span, Span},
 };
 use asm_builder::AsmBuilder;
 use essential_types::{predicate::Predicate as CompiledPredicate, ContentAddress};
 use petgraph::{graph::NodeIndex, Graph};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+};
 
 mod asm_builder;
 mod display;
+mod peephole;
 #[cfg(test)]
 mod tests;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CompiledContract {
     pub names: Vec<String>,
     pub salt: [u8; 32],
     pub predicates: Vec<CompiledPredicate>,
+    /// The source location of every constraint, one `Vec` per predicate in `predicates`, with each
+    /// entry corresponding to the constraint at the same index in that predicate.  Constraints
+    /// generated by the compiler have an empty span.
+    #[serde(default)]
+    pub constraint_spans: Vec<Vec<SourceSpan>>,
+    /// Notes describing the source construct behind some of the ops of every program, one entry
+    /// per predicate in `predicates`. These are only recorded if `CompileOptions::record_asm_notes`
+    /// is set, are only used by `explain_asm` and aren't serialized.
+    #[serde(skip)]
+    pub asm_notes: Vec<PredicateAsmNotes>,
+}
+
+/// A map from the index of an op within an assembly program to a note describing the source
+/// construct that produced it, e.g. "decision var `::x` slot".
+pub type AsmNotes = BTreeMap<usize, String>;
+
+/// The `AsmNotes` of every state read program and constraint program of a compiled predicate, in
+/// the same order as the programs of the `CompiledPredicate`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PredicateAsmNotes {
+    pub state_reads: Vec<AsmNotes>,
+    pub constraints: Vec<AsmNotes>,
+}
+
+/// A source location, as a pair of byte offsets into a source file, which can be serialized along
+/// with the compiled contract for source mapping.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub file: PathBuf,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<&Span> for SourceSpan {
+    fn from(span: &Span) -> Self {
+        SourceSpan {
+            file: span.context().to_path_buf(),
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+}
+
+impl CompiledContract {
+    /// Serializes this compiled contract, including its predicate names, to a JSON string.
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a compiled contract from a JSON string produced by `to_json_string`.
+    pub fn from_json_str(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Renders a human-readable assembly listing of every predicate in this contract, using the
+    /// same textual format as its `Display` implementation.
+    pub fn disassemble(&self) -> String {
+        self.to_string()
+    }
+
+    /// Like `disassemble` but each op is followed by a note describing the source construct that
+    /// produced it, where one was recorded during asm gen with `CompileOptions::record_asm_notes`
+    /// set. For example, the op pushing the slot of decision variable `x` is followed by
+    /// `; decision var `::x` slot`.
+    pub fn explain_asm(&self) -> String {
+        display::ExplainedContract(self).to_string()
+    }
 }
 
 /// Convert a `Contract` into `CompiledContract`
 pub fn compile_contract(
     handler: &Handler,
     contract: &Contract,
+    options: &CompileOptions,
 ) -> Result<CompiledContract, ErrorEmitted> {
+    compile_contract_with_entry(handler, contract, options, None)
+}
+
+/// Like `compile_contract` but if `entry` is given then only that predicate, and the predicates
+/// whose addresses it depends on, are compiled. The name may be given with or without the leading
+/// `::`. Emits `CompileError::UnknownPredicate` if there is no such predicate.
+pub fn compile_contract_with_entry(
+    handler: &Handler,
+    contract: &Contract,
+    options: &CompileOptions,
+    entry: Option<&str>,
+) -> Result<CompiledContract, ErrorEmitted> {
+    if options.real_scale_bits > MAX_REAL_SCALE_BITS {
+        return Err(handler.emit_err(Error::Compile {
+            error: CompileError::RealScaleBitsOutOfRange {
+                bits: options.real_scale_bits,
+                span: empty_span(),
+            },
+        }));
+    }
+
     // This is a dependency graph between predicates. Predicates may depend on other predicates via
     // predicate instances that reference other predicates in the same contract
     let mut dep_graph = Graph::<String, ()>::new();
@@ -67,6 +164,44 @@ pub fn compile_contract(
         }
     }
 
+    // If there's an entry predicate then only it and its dependencies, i.e., those predicates from
+    // which it is reachable in the dependency graph, are compiled.
+    let included = match entry {
+        Some(entry) => {
+            let entry_name = if entry.starts_with("::") {
+                entry.to_string()
+            } else {
+                format!("::{entry}")
+            };
+            let Some(&entry_node) = names_to_indices.get(&entry_name) else {
+                return Err(handler.emit_err(Error::Compile {
+                    error: CompileError::UnknownPredicate {
+                        name: entry.to_string(),
+                        available: contract
+                            .preds
+                            .values()
+                            .map(|pred| pred.name.clone())
+                            .collect(),
+                        span: empty_span(),
+                    },
+                }));
+            };
+
+            let reversed = petgraph::visit::Reversed(&dep_graph);
+            let mut dfs = petgraph::visit::Dfs::new(reversed, entry_node);
+            let mut included = HashSet::new();
+            while let Some(node) = dfs.next(reversed) {
+                included.insert(dep_graph[node].clone());
+            }
+            Some(included)
+        }
+        None => None,
+    };
+    let is_included = |name: &String| match &included {
+        Some(included) => included.contains(name),
+        None => true,
+    };
+
     // Predicates should be sorted topologically based on the dependency graph. That way, predicate
     // addresses that are required by other predicates are known in time.
     let Ok(sorted_nodes) = petgraph::algo::toposort(&dep_graph, None) else {
@@ -84,18 +219,35 @@ pub fn compile_contract(
     let mut compiled_predicates: HashMap<String, (CompiledPredicate, ContentAddress)> =
         HashMap::new();
 
+    // The source spans of the constraints of each compiled predicate, keyed by predicate name.
+    let mut constraint_spans: HashMap<String, Vec<SourceSpan>> = HashMap::new();
+
+    // The asm notes of each compiled predicate, keyed by predicate name.
+    let mut asm_notes: HashMap<String, PredicateAsmNotes> = HashMap::new();
+
     // Now compile all predicates in topological order
     for idx in &sorted_nodes {
         let predicate = indices_to_predicates[idx];
+        if !is_included(&predicate.name) {
+            continue;
+        }
 
-        if let Ok(compiled_predicate) = handler
-            .scope(|handler| compile_predicate(handler, contract, &compiled_predicates, predicate))
-        {
+        if let Ok((compiled_predicate, spans, notes)) = handler.scope(|handler| {
+            compile_predicate_with_notes(
+                handler,
+                contract,
+                options,
+                &compiled_predicates,
+                predicate,
+            )
+        }) {
             let compiled_predicate_address = essential_hash::content_addr(&compiled_predicate);
             compiled_predicates.insert(
                 predicate.name.clone(),
                 (compiled_predicate, compiled_predicate_address),
             );
+            constraint_spans.insert(predicate.name.clone(), spans);
+            asm_notes.insert(predicate.name.clone(), notes);
         }
     }
 
@@ -105,6 +257,7 @@ pub fn compile_contract(
     let (names, predicates) = contract
         .preds
         .iter()
+        .filter(|(_, pred)| is_included(&pred.name))
         .map(|(_, pred)| {
             compiled_predicates
                 .remove(&pred.name)
@@ -127,8 +280,20 @@ pub fn compile_contract(
     } else {
         Ok(CompiledContract {
             names,
-            salt: Default::default(), // Salt is not used by pint yet.
+            salt: options.salt,
             predicates,
+            constraint_spans: contract
+                .preds
+                .iter()
+                .filter(|(_, pred)| is_included(&pred.name))
+                .map(|(_, pred)| constraint_spans.remove(&pred.name).unwrap_or_default())
+                .collect(),
+            asm_notes: contract
+                .preds
+                .iter()
+                .filter(|(_, pred)| is_included(&pred.name))
+                .map(|(_, pred)| asm_notes.remove(&pred.name).unwrap_or_default())
+                .collect(),
         })
     }
 }
@@ -138,29 +303,64 @@ pub fn compile_contract(
 pub fn compile_predicate(
     handler: &Handler,
     contract: &Contract,
+    options: &CompileOptions,
     compiled_predicates: &HashMap<String, (CompiledPredicate, ContentAddress)>,
     pred: &Predicate,
 ) -> Result<CompiledPredicate, ErrorEmitted> {
-    let mut builder = AsmBuilder::new(compiled_predicates);
+    compile_predicate_with_spans(handler, contract, options, compiled_predicates, pred)
+        .map(|(compiled_predicate, _)| compiled_predicate)
+}
+
+/// Like `compile_predicate` but also returns the source span of each compiled constraint, in the
+/// same order as the constraints of the `CompiledPredicate`.
+pub fn compile_predicate_with_spans(
+    handler: &Handler,
+    contract: &Contract,
+    options: &CompileOptions,
+    compiled_predicates: &HashMap<String, (CompiledPredicate, ContentAddress)>,
+    pred: &Predicate,
+) -> Result<(CompiledPredicate, Vec<SourceSpan>), ErrorEmitted> {
+    compile_predicate_with_notes(handler, contract, options, compiled_predicates, pred)
+        .map(|(compiled_predicate, spans, _)| (compiled_predicate, spans))
+}
+
+/// Like `compile_predicate_with_spans` but also returns the notes recorded for the ops of each
+/// state read program and constraint program.
+fn compile_predicate_with_notes(
+    handler: &Handler,
+    contract: &Contract,
+    options: &CompileOptions,
+    compiled_predicates: &HashMap<String, (CompiledPredicate, ContentAddress)>,
+    pred: &Predicate,
+) -> Result<(CompiledPredicate, Vec<SourceSpan>, PredicateAsmNotes), ErrorEmitted> {
+    let mut builder = AsmBuilder::new(
+        compiled_predicates,
+        options.real_scale_bits,
+        options.salt,
+        options.record_asm_notes,
+    );
 
     // Compile all state declarations into state programs
-    for (_, state) in pred.states() {
-        builder.compile_state(handler, state, contract, pred)?;
+    for states in group_states(contract, pred) {
+        builder.compile_state(handler, &states, contract, pred)?;
     }
 
-    // Compile all constraint declarations into constraint programs
+    // Compile all constraint declarations into constraint programs, one program per constraint
+    let mut spans = Vec::with_capacity(pred.constraints.len());
     for ConstraintDecl {
-        expr: constraint, ..
+        expr: constraint,
+        span,
     } in &pred.constraints
     {
         builder.compile_constraint(handler, constraint, contract, pred)?;
+        spans.push(SourceSpan::from(span));
     }
 
     if handler.has_errors() {
         return Err(handler.cancel());
     }
 
-    Ok(CompiledPredicate {
+    let compiled_predicate = CompiledPredicate {
         state_read: builder
             .state_programs
             .iter()
@@ -173,5 +373,85 @@ pub fn compile_predicate(
                 constraint_asm::to_bytes(constraint_programs.iter().copied()).collect()
             })
             .collect(),
-    })
+    };
+
+    let notes = PredicateAsmNotes {
+        state_reads: builder.state_notes,
+        constraints: builder.constraint_notes,
+    };
+
+    Ok((compiled_predicate, spans, notes))
+}
+
+/// Partitions the state variables of `pred` into groups which are each compiled into a single
+/// state program. A state variable whose initializer refers to other state variables is placed
+/// in the same group as them, after them, so that it can load their values from the state slots
+/// of that program rather than reading storage again. Independent state variables get a group of
+/// their own, in declaration order.
+fn group_states<'a>(contract: &Contract, pred: &'a Predicate) -> Vec<Vec<&'a State>> {
+    let states: Vec<&State> = pred.states().map(|(_, state)| state).collect();
+
+    // The indices, into `states`, of the state variables referred to by each state initializer.
+    let deps: Vec<Vec<usize>> = states
+        .iter()
+        .map(|state| {
+            let mut deps = Vec::new();
+            contract.visitor_from_key(
+                VisitorKind::DepthFirstParentsBeforeChildren,
+                state.expr,
+                &mut |_, expr| {
+                    if let Expr::Path(path, _) = expr {
+                        if let Some(idx) = states.iter().position(|dep| &dep.name == path) {
+                            if !deps.contains(&idx) {
+                                deps.push(idx);
+                            }
+                        }
+                    }
+                },
+            );
+            deps
+        })
+        .collect();
+
+    // Order the state variables such that each comes after its dependencies.
+    fn visit(idx: usize, deps: &[Vec<usize>], visited: &mut Vec<bool>, order: &mut Vec<usize>) {
+        if !visited[idx] {
+            visited[idx] = true;
+            for &dep in &deps[idx] {
+                visit(dep, deps, visited, order);
+            }
+            order.push(idx);
+        }
+    }
+    let mut visited = vec![false; states.len()];
+    let mut order = Vec::with_capacity(states.len());
+    for idx in 0..states.len() {
+        visit(idx, &deps, &mut visited, &mut order);
+    }
+
+    // Now build the groups, merging the groups of a state variable's dependencies into one.
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for idx in order {
+        let mut group = Vec::new();
+        let mut position = groups.len();
+        let mut group_idx = 0;
+        while group_idx < groups.len() {
+            if groups[group_idx]
+                .iter()
+                .any(|member| deps[idx].contains(member))
+            {
+                position = position.min(group_idx);
+                group.append(&mut groups.remove(group_idx));
+            } else {
+                group_idx += 1;
+            }
+        }
+        group.push(idx);
+        groups.insert(position, group);
+    }
+
+    groups
+        .into_iter()
+        .map(|group| group.into_iter().map(|idx| states[idx]).collect())
+        .collect()
 }
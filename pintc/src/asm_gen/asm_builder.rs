@@ -1,11 +1,12 @@
+use super::{peephole::remove_redundant_ops, AsmNotes};
 use crate::{
     error::{CompileError, Error, ErrorEmitted, Handler},
     expr::{
-        BinaryOp, Expr, ExternalIntrinsic, Immediate, InternalIntrinsic, IntrinsicKind,
-        TupleAccess, UnaryOp,
+        evaluate::Evaluator, BinaryOp, Expr, ExternalIntrinsic, Immediate, InternalIntrinsic,
+        IntrinsicKind, TupleAccess, UnaryOp,
     },
     predicate::{Contract, ExprKey, Predicate, State as StateVar},
-    span::empty_span,
+    span::{empty_span, Span},
     types::Type,
 };
 use essential_types::{predicate::Predicate as CompiledPredicate, ContentAddress};
@@ -25,6 +26,10 @@ pub struct AsmBuilder<'a> {
     // Opcodes to specify constraints
     pub constraint_programs: Vec<Vec<ConstraintOp>>,
 
+    // Notes describing the source of the ops in each state program and in each constraint program
+    pub state_notes: Vec<AsmNotes>,
+    pub constraint_notes: Vec<AsmNotes>,
+
     // A reference to a `HahsMap` from predicate names to the compiled predicates and their
     // addresses
     compiled_predicates: &'a HashMap<String, (CompiledPredicate, ContentAddress)>,
@@ -33,49 +38,88 @@ pub struct AsmBuilder<'a> {
     // is stored in a single slot.
     state_var_to_slot_indices: HashMap<String, usize>,
 
+    // A map from names of the state variables stored so far by the current state program to their
+    // local state slot indices within that program.
+    state_var_to_local_slot_indices: HashMap<String, usize>,
+
     // A map from storage access expressions to their chosen state slot indices. Each storage
     // access spans one or more consecutive slots, hence the `Range`.
     storage_access_to_slot_indices: HashMap<ExprKey, std::ops::Range<usize>>,
 
     // This keeps track of the total number of slots allocated so far, globally
     global_state_slots: usize,
+
+    // The number of fractional bits used to encode `real` values as fixed-point integers
+    real_scale_bits: u32,
+
+    // The salt of the contract, as returned by `__this_contract_salt()`
+    salt: [u8; 32],
+
+    // Whether to record notes describing the source constructs that produced the ops
+    record_notes: bool,
+}
+
+/// A single assembly program which may be a "constraint program" or a "state program", along
+/// with notes describing the source constructs that produced some of its ops, if recording them.
+struct Asm {
+    ops: AsmOps,
+    notes: AsmNotes,
+    record_notes: bool,
 }
 
-/// A single assembly program which may be a "constraint program" or a "state program"
-enum Asm {
+/// The ops of an `Asm` program.
+enum AsmOps {
     Constraint(Vec<ConstraintOp>),
     State(Vec<StateOp>),
 }
 
 impl Asm {
+    /// Creates an empty "constraint program".
+    fn constraint(record_notes: bool) -> Self {
+        Self {
+            ops: AsmOps::Constraint(Vec::new()),
+            notes: AsmNotes::new(),
+            record_notes,
+        }
+    }
+
+    /// Creates an empty "state program".
+    fn state(record_notes: bool) -> Self {
+        Self {
+            ops: AsmOps::State(Vec::new()),
+            notes: AsmNotes::new(),
+            record_notes,
+        }
+    }
+
     /// Push a single `ConstraintOp` onto the program. Convert the op to a `StateOp` if `self` is a
     /// "state program".
     fn push(&mut self, op: ConstraintOp) {
-        match self {
-            Self::Constraint(ref mut ops) => ops.push(op),
-            Self::State(ref mut ops) => ops.push(StateOp::Constraint(op)),
+        match self.ops {
+            AsmOps::Constraint(ref mut ops) => ops.push(op),
+            AsmOps::State(ref mut ops) => ops.push(StateOp::Constraint(op)),
         }
     }
 
     /// Set the op at `index` to `op`. Panics if `index` is out of bounds.
     fn set(&mut self, index: usize, op: ConstraintOp) {
-        match self {
-            Self::Constraint(ref mut ops) => ops[index] = op,
-            Self::State(ref mut ops) => ops[index] = StateOp::Constraint(op),
+        match self.ops {
+            AsmOps::Constraint(ref mut ops) => ops[index] = op,
+            AsmOps::State(ref mut ops) => ops[index] = StateOp::Constraint(op),
         }
     }
 
     /// Try to push a single `SatetOp` onto the program. Because the op is a `StateOp`, this could
     /// fail if `self` is a "constraint program"
     fn try_push(&mut self, handler: &Handler, op: StateOp) -> Result<(), ErrorEmitted> {
-        match self {
-            Self::Constraint(_) => Err(handler.emit_err(Error::Compile {
+        match self.ops {
+            AsmOps::Constraint(_) => Err(handler.emit_err(Error::Compile {
                 error: CompileError::Internal {
                     msg: "These expressions should have been lowered by now",
                     span: empty_span(),
                 },
             })),
-            Self::State(ref mut ops) => {
+            AsmOps::State(ref mut ops) => {
                 ops.push(op);
                 Ok(())
             }
@@ -83,19 +127,41 @@ impl Asm {
     }
 
     /// Insert a single `ConstraintOp` in the program at a given index. Convert the op to a
-    /// `StateOp` if `self` is a "state program".
+    /// `StateOp` if `self` is a "state program". Notes attached to ops at or after `index` move
+    /// along with their ops.
     fn insert(&mut self, index: usize, op: ConstraintOp) {
-        match self {
-            Self::Constraint(ref mut ops) => ops.insert(index, op),
-            Self::State(ref mut ops) => ops.insert(index, StateOp::Constraint(op)),
+        match self.ops {
+            AsmOps::Constraint(ref mut ops) => ops.insert(index, op),
+            AsmOps::State(ref mut ops) => ops.insert(index, StateOp::Constraint(op)),
         }
+        let moved = self.notes.split_off(&index);
+        self.notes
+            .extend(moved.into_iter().map(|(idx, note)| (idx + 1, note)));
+    }
+
+    /// Attach the note produced by `note` to the op at `index`. An op may carry several notes,
+    /// which are joined in the order they were attached. Does nothing, and so doesn't format the
+    /// note, unless recording notes.
+    fn note(&mut self, index: usize, note: impl FnOnce() -> String) {
+        if !self.record_notes {
+            return;
+        }
+
+        let note = note();
+        self.notes
+            .entry(index)
+            .and_modify(|existing| {
+                existing.push_str("; ");
+                existing.push_str(&note);
+            })
+            .or_insert(note);
     }
 
     /// Returns the number of ops in asm program `self`
     fn len(&self) -> usize {
-        match self {
-            Self::Constraint(ops) => ops.len(),
-            Self::State(ops) => ops.len(),
+        match &self.ops {
+            AsmOps::Constraint(ops) => ops.len(),
+            AsmOps::State(ops) => ops.len(),
         }
     }
 }
@@ -109,134 +175,182 @@ impl Asm {
 ///    (i.e. requires `KeyRangeExtern`) and `false` otherwise.
 /// 4. `PubVar` expressions refer to expressions that require the `PubVar` opcode.
 /// 5. `Value` expressions are just raw values such as immediates or the outputs of binary ops.
+/// 6. `StateSlot` expressions refer to state variables which were stored earlier in the current
+///    state program and can be read back using the `Load` opcode.
 enum Location {
     DecisionVar,
     State(bool),
     Storage(bool),
     PubVar,
     Value,
+    StateSlot,
 }
 
 impl<'a> AsmBuilder<'a> {
-    /// Creates a new `AsmBuilder` given a set of compiled predicates and their addresses.
+    /// Creates a new `AsmBuilder` given a set of compiled predicates and their addresses, the
+    /// number of fractional bits used to encode `real` values, the salt of the contract and
+    /// whether to record asm notes.
     pub fn new(
         compiled_predicates: &'a HashMap<String, (CompiledPredicate, ContentAddress)>,
+        real_scale_bits: u32,
+        salt: [u8; 32],
+        record_notes: bool,
     ) -> Self {
         Self {
             state_programs: Vec::new(),
             constraint_programs: Vec::new(),
+            state_notes: Vec::new(),
+            constraint_notes: Vec::new(),
             compiled_predicates,
             state_var_to_slot_indices: HashMap::new(),
+            state_var_to_local_slot_indices: HashMap::new(),
             storage_access_to_slot_indices: HashMap::new(),
             global_state_slots: 0,
+            real_scale_bits,
+            salt,
+            record_notes,
         }
     }
 
+    /// The scaling factor used to encode `real` values as fixed-point integers.
+    fn real_scale(&self) -> i64 {
+        1 << self.real_scale_bits
+    }
+
     /// Given an asm program `asm`, push it to the appropridate vector of programs in `self`. This
-    /// may be a "constraint program" or a "state program"
-    fn push_asm_program(&mut self, asm: Asm) {
-        match asm {
-            Asm::Constraint(ops) => self.constraint_programs.push(ops),
-            Asm::State(ops) => self.state_programs.push(ops),
+    /// may be a "constraint program" or a "state program". Redundant ops are removed first.
+    fn push_asm_program(&mut self, mut asm: Asm) {
+        match asm.ops {
+            AsmOps::Constraint(mut ops) => {
+                remove_redundant_ops(&mut ops, &mut asm.notes);
+                self.constraint_programs.push(ops);
+                self.constraint_notes.push(asm.notes);
+            }
+            AsmOps::State(mut ops) => {
+                remove_redundant_ops(&mut ops, &mut asm.notes);
+                self.state_programs.push(ops);
+                self.state_notes.push(asm.notes);
+            }
         }
     }
 
-    /// Generates assembly for a given state read and adds the resulting program to `self.
+    /// Generates a single state program for a group of state variables and adds it to `self`. The
+    /// states must be ordered such that a state variable comes after all the state variables that
+    /// its initializer refers to, which are then loaded from the state slots of this program
+    /// rather than being read again.
     pub(super) fn compile_state(
         &mut self,
         handler: &Handler,
-        state: &StateVar,
+        states: &[&StateVar],
         contract: &Contract,
         pred: &Predicate,
     ) -> Result<(), ErrorEmitted> {
-        let mut asm: Asm = Asm::State(Vec::new());
+        let mut asm: Asm = Asm::state(self.record_notes);
         let mut local_state_slots = 0;
 
-        // Allocates `num_slots` number of state slots. Returns the local and global indices of the
-        // first slot allocated. The local index is the index in a given state program, which
-        // starts at 0 for every state program. The global index is a unique index across all the
-        // state programs.
-        let mut allocate = |num_slots: usize| -> Result<(usize, usize), ErrorEmitted> {
-            asm.push(Stack::Push(num_slots as i64).into());
-            asm.try_push(handler, StateMemory::AllocSlots.into())?;
-            local_state_slots += num_slots;
-            self.global_state_slots += num_slots;
-            Ok((
-                local_state_slots - num_slots,
-                self.global_state_slots - num_slots,
-            ))
-        };
+        for state in states {
+            // Allocate a single slot for the state var. Keep track of the local and global indices
+            // for this newly allocated slot.
+            let (state_var_local_slot_index, state_var_global_slot_index) =
+                self.allocate_slots(handler, &mut asm, &mut local_state_slots, 1)?;
+
+            // Collect all storage accesses used in the state var initializer, and allocate enough
+            // slots for all of them. We do this ahead of time so that we know exactly how many
+            // slots are allocated. Due to short-circuting, this also means that some allocations
+            // may not be used, but this is okay for now.
+            for access in state.expr.collect_storage_accesses(contract) {
+                // This is how many slots this storage access requires
+                let num_keys_to_read = access.get_ty(contract).storage_slots(handler, contract)?;
+
+                // Now, allocate
+                let base_slot_index = self
+                    .allocate_slots(handler, &mut asm, &mut local_state_slots, num_keys_to_read)?
+                    .0;
+
+                // Keep track of the local indices of the newly allocated slots
+                self.storage_access_to_slot_indices
+                    .insert(access, base_slot_index..base_slot_index + num_keys_to_read);
+            }
 
-        // Allocate a single slot for the state var. Keep track of the local and global indices for
-        // this newly allocated slot.
-        let (state_var_local_slot_index, state_var_global_slot_index) = allocate(1)?;
+            // Prepare for the `StateMemory::Store` opcode
+            asm.push(Stack::Push(state_var_local_slot_index as i64).into()); // slot_ix
+            asm.push(Stack::Push(0).into()); // value_ix
 
-        // Collect all storage accesses used in the state var initializer, and allocate enough
-        // slots for all of them. We do this ahead of time so that we know exactly how many slots
-        // are allocated. Due to short-circuting, this also means that some allocations may not be
-        // used, but this is okay for now.
-        for access in state.expr.collect_storage_accesses(contract) {
-            // This is how many slots this storage access requires
-            let num_keys_to_read = access.get_ty(contract).storage_slots(handler, contract)?;
+            if let Some(state_slots) =
+                self.compile_expr_pointer_deref(handler, &mut asm, &state.expr, contract, pred)?
+            {
+                // If the result is stored state slots, then load those slots to the stack
+                for i in state_slots.start..state_slots.end {
+                    asm.push(Stack::Push(i as i64).into()); // slot_ix
 
-            // Now, allocate
-            let base_slot_index = allocate(num_keys_to_read)?.0;
+                    asm.push(Stack::Push(0).into()); // value_ix
 
-            // Keep track of the local indices of the newly allocated slots
-            self.storage_access_to_slot_indices
-                .insert(access, base_slot_index..base_slot_index + num_keys_to_read);
-        }
+                    asm.push(Stack::Push(i as i64).into());
+                    asm.try_push(handler, StateMemory::ValueLen.into())?; // len, using `ValueLen`
 
-        // Prepare for the `StateMemory::Store` opcode
-        asm.push(Stack::Push(state_var_local_slot_index as i64).into()); // slot_ix
-        asm.push(Stack::Push(0).into()); // value_ix
+                    asm.try_push(handler, StateMemory::Load.into())?;
+                }
 
-        if let Some(state_slots) =
-            self.compile_expr_pointer_deref(handler, &mut asm, &state.expr, contract, pred)?
-        {
-            // If the result is stored state slots, then load those slots to the stack
-            for i in state_slots.start..state_slots.end {
-                asm.push(Stack::Push(i as i64).into()); // slot_ix
+                // Then, find the _total_ number of words loaded
+                asm.push(Stack::Push(0).into());
+                for i in state_slots.start..state_slots.end {
+                    asm.push(Stack::Push(i as i64).into());
+                    asm.try_push(handler, StateMemory::ValueLen.into())?;
+                    asm.push(Alu::Add.into());
+                }
+            } else {
+                // Otherwise, the data is already on the stack. Just follow with the size of the
+                // data according to the state expr type.
+                asm.push(
+                    Stack::Push(state.expr.get_ty(contract).size(handler, contract)? as i64).into(),
+                );
+            }
 
-                asm.push(Stack::Push(0).into()); // value_ix
+            // Now, store the result into the slot allocated for the state var
+            asm.try_push(handler, StateMemory::Store.into())?;
 
-                asm.push(Stack::Push(i as i64).into());
-                asm.try_push(handler, StateMemory::ValueLen.into())?; // len, using `ValueLen`
+            // Keep track of the global index of the state slot where this state variable lives,
+            // and of its local index so that later states in this program can load it
+            self.state_var_to_slot_indices
+                .insert(state.name.clone(), state_var_global_slot_index);
+            self.state_var_to_local_slot_indices
+                .insert(state.name.clone(), state_var_local_slot_index);
 
-                asm.try_push(handler, StateMemory::Load.into())?;
-            }
-
-            // Then, find the _total_ number of words loaded
-            asm.push(Stack::Push(0).into());
-            for i in state_slots.start..state_slots.end {
-                asm.push(Stack::Push(i as i64).into());
-                asm.try_push(handler, StateMemory::ValueLen.into())?;
-                asm.push(Alu::Add.into());
-            }
-        } else {
-            // Otherwise, the data is already on the stack. Just follow with the size of the data
-            // according to the state expr type.
-            asm.push(
-                Stack::Push(state.expr.get_ty(contract).size(handler, contract)? as i64).into(),
-            );
+            // Clear out this map because it's local to each state variable
+            self.storage_access_to_slot_indices.clear();
         }
 
-        // Now, store the result into the slot allocated for the state var
-        asm.try_push(handler, StateMemory::Store.into())?;
         asm.try_push(handler, TotalControlFlow::Halt.into())?;
 
-        // Keep track of the global index of the state slot where this state variable lives
-        self.state_var_to_slot_indices
-            .insert(state.name.clone(), state_var_global_slot_index);
-
-        // Clear out this map because it's local to each state variable
-        self.storage_access_to_slot_indices.clear();
+        // Clear out this map because it's local to each state program
+        self.state_var_to_local_slot_indices.clear();
 
         self.push_asm_program(asm);
 
         Ok(())
     }
 
+    /// Allocates `num_slots` number of state slots in the state program `asm`. Returns the local
+    /// and global indices of the first slot allocated. The local index is the index in a given
+    /// state program, which starts at 0 for every state program. The global index is a unique
+    /// index across all the state programs.
+    fn allocate_slots(
+        &mut self,
+        handler: &Handler,
+        asm: &mut Asm,
+        local_state_slots: &mut usize,
+        num_slots: usize,
+    ) -> Result<(usize, usize), ErrorEmitted> {
+        asm.push(Stack::Push(num_slots as i64).into());
+        asm.try_push(handler, StateMemory::AllocSlots.into())?;
+        *local_state_slots += num_slots;
+        self.global_state_slots += num_slots;
+        Ok((
+            *local_state_slots - num_slots,
+            self.global_state_slots - num_slots,
+        ))
+    }
+
     /// Generates assembly for a given constraint and adds the resulting program to `self.
     pub(super) fn compile_constraint(
         &mut self,
@@ -245,7 +359,7 @@ impl<'a> AsmBuilder<'a> {
         contract: &Contract,
         pred: &Predicate,
     ) -> Result<(), ErrorEmitted> {
-        let mut asm: Asm = Asm::Constraint(Vec::new());
+        let mut asm: Asm = Asm::constraint(self.record_notes);
         self.compile_expr(handler, &mut asm, expr, contract, pred)?;
         self.push_asm_program(asm);
         Ok(())
@@ -296,6 +410,9 @@ impl<'a> AsmBuilder<'a> {
         match self.compile_expr_pointer(handler, asm, expr, contract, pred)? {
             Location::DecisionVar => {
                 asm.push(Stack::Push(expr_ty.size(handler, contract)? as i64).into()); // len
+                asm.note(asm.len(), || {
+                    format!("read `{}`", contract.with_ctrct(expr))
+                });
                 asm.push(Access::DecisionVar.into());
                 Ok(None)
             }
@@ -303,6 +420,9 @@ impl<'a> AsmBuilder<'a> {
             Location::State(next_state) => {
                 asm.push(Stack::Push(expr_ty.size(handler, contract)? as i64).into()); // value_len
                 asm.push(Stack::Push(next_state as i64).into()); // delta
+                asm.note(asm.len(), || {
+                    format!("read `{}`", contract.with_ctrct(expr))
+                });
                 asm.push(Access::State.into());
                 Ok(None)
             }
@@ -331,10 +451,22 @@ impl<'a> AsmBuilder<'a> {
 
             Location::PubVar => {
                 asm.push(Stack::Push(expr_ty.size(handler, contract)? as i64).into()); // value_len
+                asm.note(asm.len(), || {
+                    format!("read `{}`", contract.with_ctrct(expr))
+                });
                 asm.push(ConstraintOp::Access(Access::PubVar));
                 Ok(None)
             }
 
+            Location::StateSlot => {
+                asm.push(Stack::Push(expr_ty.size(handler, contract)? as i64).into()); // len
+                asm.note(asm.len(), || {
+                    format!("read `{}`", contract.with_ctrct(expr))
+                });
+                asm.try_push(handler, StateMemory::Load.into())?;
+                Ok(None)
+            }
+
             Location::Value => Ok(None),
         }
     }
@@ -351,12 +483,47 @@ impl<'a> AsmBuilder<'a> {
         contract: &Contract,
         pred: &Predicate,
     ) -> Result<Location, ErrorEmitted> {
-        fn compile_immediate(asm: &mut Asm, imm: &Immediate) -> usize {
-            match imm {
+        /// Push the words of `imm` onto the stack and return how many were pushed.
+        ///
+        /// A `real` is encoded as a single fixed-point word: the value multiplied by `real_scale`
+        /// and rounded to the nearest integer.  E.g., with the default scale of 2^32, `1.5` is
+        /// pushed as `6442450944`.  A `real` which doesn't fit in a word once scaled is reported as
+        /// `CompileError::IntegerOverflow`.
+        ///
+        /// An `OverflowedInt` cannot be represented in a word.  Overflowing expressions are
+        /// reported and left unfolded by the optimizer, so this is reported as
+        /// `CompileError::IntegerOverflow` only as a fallback.
+        ///
+        /// A `nil` has no encoding.  Comparisons to `nil` have already been lowered to `__size_of`
+        /// checks, so any other `nil` is reported as `CompileError::NilNotSupportedHere`.
+        fn compile_immediate(
+            handler: &Handler,
+            asm: &mut Asm,
+            imm: &Immediate,
+            real_scale: i64,
+            span: &Span,
+        ) -> Result<usize, ErrorEmitted> {
+            Ok(match imm {
                 Immediate::Int(val) => {
                     asm.push(Stack::Push(*val).into());
                     1
                 }
+                Immediate::OverflowedInt(_) => {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::IntegerOverflow { span: span.clone() },
+                    }));
+                }
+                Immediate::Real(val) => {
+                    // `i64::MAX as f64` rounds up to 2^63, which is out of range itself.
+                    let scaled = (val * real_scale as f64).round();
+                    if !(i64::MIN as f64..i64::MAX as f64).contains(&scaled) {
+                        return Err(handler.emit_err(Error::Compile {
+                            error: CompileError::IntegerOverflow { span: span.clone() },
+                        }));
+                    }
+                    asm.push(Stack::Push(scaled as i64).into());
+                    1
+                }
                 Immediate::Bool(val) => {
                     asm.push(Stack::Push(*val as i64).into());
                     1
@@ -371,14 +538,14 @@ impl<'a> AsmBuilder<'a> {
                 Immediate::Array(elements) => {
                     let mut value_size = 0;
                     for element in elements {
-                        value_size += compile_immediate(asm, element);
+                        value_size += compile_immediate(handler, asm, element, real_scale, span)?;
                     }
                     value_size
                 }
                 Immediate::Tuple(fields) => {
                     let mut value_size = 0;
                     for (_, field) in fields {
-                        value_size += compile_immediate(asm, field);
+                        value_size += compile_immediate(handler, asm, field, real_scale, span)?;
                     }
                     value_size
                 }
@@ -392,7 +559,7 @@ impl<'a> AsmBuilder<'a> {
 
                     let mut value_size = 0;
                     if let Some(value) = value {
-                        value_size = compile_immediate(asm, value);
+                        value_size = compile_immediate(handler, asm, value, real_scale, span)?;
                     }
                     while value_size < *max_size {
                         asm.push(Stack::Push(0).into());
@@ -400,15 +567,31 @@ impl<'a> AsmBuilder<'a> {
                     }
                     1 + value_size
                 }
-                Immediate::Error | Immediate::Nil | Immediate::Real(_) | Immediate::String(_) => {
+                Immediate::String(val) => {
+                    let words = string_to_words(val);
+                    for word in &words {
+                        asm.push(Stack::Push(*word).into());
+                    }
+                    words.len()
+                }
+                Immediate::Nil => {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::NilNotSupportedHere { span: span.clone() },
+                    }));
+                }
+                Immediate::Error => {
                     unreachable!("Unexpected literal")
                 }
-            }
+            })
         }
 
         match expr.get(contract) {
-            Expr::Immediate { value, .. } => {
-                compile_immediate(asm, value);
+            Expr::Immediate { value, span } => {
+                let start = asm.len();
+                compile_immediate(handler, asm, value, self.real_scale(), span)?;
+                asm.note(start, || {
+                    format!("literal `{}`", contract.with_ctrct(value))
+                });
                 Ok(Location::Value)
             }
             Expr::Array { elements, .. } => {
@@ -430,8 +613,14 @@ impl<'a> AsmBuilder<'a> {
             Expr::UnaryOp { op, expr, .. } => {
                 self.compile_unary_op(handler, asm, op, expr, contract, pred)
             }
+            Expr::Cast { value, ty, span } => {
+                self.compile_cast(handler, asm, value, ty, span, contract, pred)
+            }
             Expr::BinaryOp { op, lhs, rhs, .. } => {
-                self.compile_binary_op(handler, asm, op, lhs, rhs, contract, pred)
+                let location =
+                    self.compile_binary_op(handler, asm, op, lhs, rhs, contract, pred)?;
+                asm.note(asm.len() - 1, || format!("`{op}`"));
+                Ok(location)
             }
             Expr::IntrinsicCall { kind, args, .. } => {
                 self.compile_intrinsic_call(handler, asm, &kind.0, args, contract, pred)
@@ -460,9 +649,10 @@ impl<'a> AsmBuilder<'a> {
             | Expr::StorageAccess { .. }
             | Expr::ExternalStorageAccess { .. }
             | Expr::MacroCall { .. }
-            | Expr::Cast { .. }
             | Expr::In { .. }
             | Expr::Range { .. }
+            | Expr::ArraySlice { .. }
+            | Expr::RangeArray { .. }
             | Expr::Generator { .. }
             | Expr::Match { .. } => Err(handler.emit_err(Error::Compile {
                 error: CompileError::Internal {
@@ -489,10 +679,17 @@ impl<'a> AsmBuilder<'a> {
             .enumerate()
             .find(|(_, (_, var))| &var.name == path)
         {
+            asm.note(asm.len(), || format!("decision var `{path}` slot"));
             asm.push(Stack::Push(var_index as i64).into()); // slot
             asm.push(Stack::Push(0).into()); // placeholder for index computation
             Ok(Location::DecisionVar)
+        } else if let Some(local_slot_index) = self.state_var_to_local_slot_indices.get(path) {
+            asm.note(asm.len(), || format!("state var `{path}` local slot"));
+            asm.push(Stack::Push(*local_slot_index as i64).into()); // slot_ix
+            asm.push(Stack::Push(0).into()); // placeholder for index computation
+            Ok(Location::StateSlot)
         } else if pred.states().any(|(_, state)| &state.name == path) {
+            asm.note(asm.len(), || format!("state var `{path}` slot"));
             asm.push(Stack::Push(self.state_var_to_slot_indices[path] as i64).into()); // slot
             asm.push(Stack::Push(0).into()); // placeholder for index computation
             Ok(Location::State(false))
@@ -508,6 +705,37 @@ impl<'a> AsmBuilder<'a> {
         }
     }
 
+    /// Compile a cast expression. Most casts are lowered away before asm gen, but any that remain
+    /// are handled here. Casts to `int` from `bool`, `int`, or an enumeration union are no-ops
+    /// since all of those are already represented as a single integer word.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_cast(
+        &mut self,
+        handler: &Handler,
+        asm: &mut Asm,
+        value: &ExprKey,
+        to_ty: &Type,
+        span: &Span,
+        contract: &Contract,
+        pred: &Predicate,
+    ) -> Result<Location, ErrorEmitted> {
+        let from_ty = value.get_ty(contract);
+        if to_ty.is_int()
+            && (from_ty.is_bool() || from_ty.is_int() || from_ty.is_enumeration_union(contract))
+        {
+            self.compile_expr(handler, asm, value, contract, pred)?;
+            Ok(Location::Value)
+        } else {
+            Err(handler.emit_err(Error::Compile {
+                error: CompileError::UnsupportedCast {
+                    from_ty: contract.with_ctrct(from_ty).to_string(),
+                    to_ty: contract.with_ctrct(to_ty).to_string(),
+                    span: span.clone(),
+                },
+            }))
+        }
+    }
+
     fn compile_unary_op(
         &mut self,
         handler: &Handler,
@@ -541,6 +769,198 @@ impl<'a> AsmBuilder<'a> {
         }
     }
 
+    /// Returns whether the `rhs` of a binary op can be compiled as a `Dup` of the `lhs`, rather than
+    /// recomputing it.  This is the case when both operands are structurally identical single word
+    /// expressions, as in `x + x`, and `op` doesn't insert any opcodes between its operands.
+    ///
+    /// Only the two operands of a single binary op are shared this way.  Repeated subexpressions
+    /// elsewhere in a constraint are still compiled once per occurrence, since reaching an earlier
+    /// value with `DupFrom` would need its stack position tracked through every op emitted since,
+    /// including the short-circuiting jumps of `&&` and `||`.
+    fn is_dup_of_lhs(
+        &self,
+        handler: &Handler,
+        op: &BinaryOp,
+        lhs: &ExprKey,
+        rhs: &ExprKey,
+        contract: &Contract,
+    ) -> bool {
+        !matches!(op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr)
+            && lhs.get(contract).struct_eq(rhs.get(contract), contract)
+            && matches!(lhs.get_ty(contract).size(handler, contract), Ok(1))
+    }
+
+    /// Multiply the two fixed-point `real` words on top of the stack, `[a, b] -> [a * b]`.
+    ///
+    /// Both operands carry the scale `2^S`, so the full product must be shifted right by `S`, but
+    /// computing it in full would overflow for any product of magnitude 0.5 or more.  Instead each
+    /// operand is split into its integer part and its fractional part, e.g. `a = ah * 2^S + al`
+    /// with `0 <= al < 2^S`, and the scaled product is summed from the partial products:
+    ///
+    ///   `a * bh + ah * bl + (al * bl) >> S`
+    ///
+    /// Only `a * bh` can overflow when the result doesn't, and then only if the result is within
+    /// `|a|` of overflowing itself.  `al * bl` fits in a word for up to 31 fractional bits.  Beyond
+    /// that the lowest bits of `al` and `bl` are dropped first, which only affects the least
+    /// significant bits of the result.
+    fn compile_real_mul(&self, asm: &mut Asm) {
+        let scale_bits = self.real_scale_bits as i64;
+        let drop_bits = (2 * scale_bits - 63).max(0);
+
+        // Pushes the fractional part of the word at `index`: `x - ((x >> S) << S)`.
+        let push_fraction = |asm: &mut Asm, index: i64| {
+            asm.push(Stack::Push(index).into());
+            asm.push(Stack::DupFrom.into());
+            asm.push(Stack::Dup.into());
+            asm.push(Stack::Push(scale_bits).into());
+            asm.push(Alu::ShrI.into());
+            asm.push(Stack::Push(scale_bits).into());
+            asm.push(Alu::Shl.into());
+            asm.push(Alu::Sub.into());
+        };
+
+        // [a, b] -> [a, b, bl]
+        push_fraction(asm, 0);
+
+        // [a, b, bl] -> [a, b, bl, a * bh]
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(scale_bits).into());
+        asm.push(Alu::ShrI.into());
+        asm.push(Alu::Mul.into());
+
+        // [a, b, bl, a * bh] -> [a, b, bl, a * bh + ah * bl]
+        asm.push(Stack::Push(3).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(scale_bits).into());
+        asm.push(Alu::ShrI.into());
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Alu::Mul.into());
+        asm.push(Alu::Add.into());
+
+        // [a, b, bl, sum] -> [a, b, bl, sum + (al * bl) >> S]
+        push_fraction(asm, 3);
+        if drop_bits > 0 {
+            asm.push(Stack::Push(drop_bits / 2 + drop_bits % 2).into());
+            asm.push(Alu::ShrI.into());
+        }
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        if drop_bits > 1 {
+            asm.push(Stack::Push(drop_bits / 2).into());
+            asm.push(Alu::ShrI.into());
+        }
+        asm.push(Alu::Mul.into());
+        asm.push(Stack::Push(scale_bits - drop_bits).into());
+        asm.push(Alu::ShrI.into());
+        asm.push(Alu::Add.into());
+
+        // [a, b, bl, product] -> [product]
+        asm.push(Stack::Push(3).into());
+        asm.push(Stack::SwapIndex.into());
+        asm.push(Stack::Pop.into());
+        asm.push(Stack::Pop.into());
+        asm.push(Stack::Pop.into());
+    }
+
+    /// Divide the two fixed-point `real` words on top of the stack, `[a, b] -> [a / b]`.
+    ///
+    /// Dividing the operands cancels out the scale `2^S`, and scaling up `a` first would overflow
+    /// for any `a` of magnitude 1 or more.  Instead the integer quotient and the remainder are
+    /// computed first and the fractional bits of the result are recovered from the remainder:
+    ///
+    ///   `(a / b) * 2^S + (r << S) / b` where `r = a % b`
+    ///
+    /// `r << S` fits in a word while `|b| < 2^(63 - S)`.  For a larger `b`, both `r` and `b` are
+    /// shifted right by the `n` bits of `|b| >> (63 - S)` first, which keeps `63 - S` significant
+    /// bits of the divisor.  `n` is found with a binary search over the bits of `|b|`.
+    fn compile_real_div(&self, asm: &mut Asm) {
+        let scale_bits = self.real_scale_bits as i64;
+
+        // [a, b] -> [a, b, (a / b) * 2^S, a % b]
+        asm.push(Stack::Push(1).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(1).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Alu::Div.into());
+        asm.push(Stack::Push(self.real_scale()).into());
+        asm.push(Alu::Mul.into());
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Alu::Mod.into());
+
+        // [.., r] -> [.., r, |b| >> (63 - S), 0], computing `|b|` as `b * (1 - 2 * (b < 0))`
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Dup.into());
+        asm.push(Stack::Push(0).into());
+        asm.push(Pred::Lt.into());
+        asm.push(Stack::Push(-2).into());
+        asm.push(Alu::Mul.into());
+        asm.push(Stack::Push(1).into());
+        asm.push(Alu::Add.into());
+        asm.push(Alu::Mul.into());
+        asm.push(Stack::Push(63 - scale_bits).into());
+        asm.push(Alu::ShrI.into());
+        asm.push(Stack::Push(0).into());
+
+        // Count the bits of `y = |b| >> (63 - S)`, which is less than `2^S`, into `n`.  Each step
+        // shifts `y` right by `s` and adds `s` to `n` if `y >= 2^s`:
+        //   [y, n] -> [y, n, t * s] -> [y, n, t * s, y, t * s] -> [y >> t * s, n, t * s, y]
+        //          -> [y >> t * s, n + t * s]
+        for shift in [32, 16, 8, 4, 2, 1].into_iter().filter(|s| *s < scale_bits) {
+            asm.push(Stack::Push(1).into());
+            asm.push(Stack::DupFrom.into());
+            asm.push(Stack::Push(1 << shift).into());
+            asm.push(Pred::Gte.into());
+            asm.push(Stack::Push(shift).into());
+            asm.push(Alu::Mul.into());
+            asm.push(Stack::Dup.into());
+            asm.push(Stack::Push(3).into());
+            asm.push(Stack::DupFrom.into());
+            asm.push(Stack::Push(1).into());
+            asm.push(Stack::SwapIndex.into());
+            asm.push(Alu::ShrI.into());
+            asm.push(Stack::Push(3).into());
+            asm.push(Stack::SwapIndex.into());
+            asm.push(Stack::Pop.into());
+            asm.push(Alu::Add.into());
+        }
+
+        // `y` is now 0 or 1, its last bit.  [.., r, y, n] -> [.., r, n]
+        asm.push(Alu::Add.into());
+
+        // [a, b, q, r, n] -> [a, b, q, r, n, ((r >> n) << S) / (b >> n)]
+        asm.push(Stack::Push(1).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(1).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Alu::ShrI.into());
+        asm.push(Stack::Push(scale_bits).into());
+        asm.push(Alu::Shl.into());
+        asm.push(Stack::Push(4).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Stack::Push(2).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Alu::ShrI.into());
+        asm.push(Alu::Div.into());
+
+        // [a, b, q, r, n, f] -> [q + f]
+        asm.push(Stack::Push(3).into());
+        asm.push(Stack::DupFrom.into());
+        asm.push(Alu::Add.into());
+        asm.push(Stack::Push(5).into());
+        asm.push(Stack::SwapIndex.into());
+        for _ in 0..5 {
+            asm.push(Stack::Pop.into());
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn compile_binary_op(
         &mut self,
@@ -552,16 +972,47 @@ impl<'a> AsmBuilder<'a> {
         contract: &Contract,
         pred: &Predicate,
     ) -> Result<Location, ErrorEmitted> {
+        if *op == BinaryOp::Pow {
+            return self.compile_pow(handler, asm, lhs, rhs, contract, pred);
+        }
+
         let lhs_len = self.compile_expr(handler, asm, lhs, contract, pred)?;
-        let rhs_len = self.compile_expr(handler, asm, rhs, contract, pred)?;
+        let rhs_len = if self.is_dup_of_lhs(handler, op, lhs, rhs, contract) {
+            // The `lhs` value is on top of the stack already so just duplicate it.
+            asm.push(Stack::Dup.into());
+            1
+        } else {
+            self.compile_expr(handler, asm, rhs, contract, pred)?
+        };
 
         match op {
             BinaryOp::Add => asm.push(Alu::Add.into()),
             BinaryOp::Sub => asm.push(Alu::Sub.into()),
+            BinaryOp::Mul if lhs.get_ty(contract).is_real() => self.compile_real_mul(asm),
             BinaryOp::Mul => asm.push(Alu::Mul.into()),
+            BinaryOp::Div if lhs.get_ty(contract).is_real() => self.compile_real_div(asm),
             BinaryOp::Div => asm.push(Alu::Div.into()),
             BinaryOp::Mod => asm.push(Alu::Mod.into()),
-            BinaryOp::Equal => {
+            BinaryOp::Pow => unreachable!("`**` is compiled by `compile_pow()`"),
+            BinaryOp::BitwiseAnd => asm.push(Pred::BitAnd.into()),
+            BinaryOp::BitwiseOr => asm.push(Pred::BitOr.into()),
+            BinaryOp::BitwiseXor => {
+                // There is no XOR opcode so compute `x ^ y` as `(x | y) - (x & y)`.  Copy both
+                // operands for the `|`, then swap the result below the originals for the `&`:
+                //   [x, y] -> [x, y, x, y] -> [x, y, x | y] -> [x | y, y, x] -> [x | y, x & y]
+                asm.push(Stack::Push(1).into());
+                asm.push(Stack::DupFrom.into());
+                asm.push(Stack::Push(1).into());
+                asm.push(Stack::DupFrom.into());
+                asm.push(Pred::BitOr.into());
+                asm.push(Stack::Push(2).into());
+                asm.push(Stack::SwapIndex.into());
+                asm.push(Pred::BitAnd.into());
+                asm.push(Alu::Sub.into());
+            }
+            BinaryOp::ShiftLeft => asm.push(Alu::Shl.into()),
+            BinaryOp::ShiftRight => asm.push(Alu::ShrI.into()),
+            BinaryOp::Equal | BinaryOp::NotEqual => {
                 let type_size = lhs.get_ty(contract).size(handler, contract)?;
                 if type_size == 1 {
                     asm.push(Pred::Eq.into());
@@ -569,10 +1020,9 @@ impl<'a> AsmBuilder<'a> {
                     asm.push(Stack::Push(type_size as i64).into());
                     asm.push(Pred::EqRange.into());
                 }
-            }
-            BinaryOp::NotEqual => {
-                asm.push(Pred::Eq.into());
-                asm.push(Pred::Not.into());
+                if *op == BinaryOp::NotEqual {
+                    asm.push(Pred::Not.into());
+                }
             }
             BinaryOp::LessThanOrEqual => asm.push(Pred::Lte.into()),
             BinaryOp::LessThan => asm.push(Pred::Lt.into()),
@@ -647,6 +1097,60 @@ impl<'a> AsmBuilder<'a> {
         Ok(Location::Value)
     }
 
+    /// Compile `lhs ** rhs` using square-and-multiply, since there is no opcode for
+    /// exponentiation. This requires `rhs` to be a non-negative integer constant.
+    ///
+    /// Working through the bits of `rhs` from the most significant, the result so far is squared
+    /// for each bit using `Dup` and `Mul`, and then multiplied by `lhs` again if the bit is set.
+    /// So `x ** 5` becomes `((x * x) * (x * x)) * x`, compiling `lhs` once per set bit of `rhs`
+    /// and emitting at most two multiplications per bit.
+    fn compile_pow(
+        &mut self,
+        handler: &Handler,
+        asm: &mut Asm,
+        lhs: &ExprKey,
+        rhs: &ExprKey,
+        contract: &Contract,
+        pred: &Predicate,
+    ) -> Result<Location, ErrorEmitted> {
+        let exponent =
+            match Evaluator::new(contract).evaluate_key(rhs, &Handler::default(), contract) {
+                Ok(Immediate::Int(exponent)) if exponent >= 0 => exponent,
+                Ok(Immediate::Int(_)) => {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::NegativeExponent {
+                            span: contract.expr_key_to_span(*rhs),
+                        },
+                    }))
+                }
+                _ => {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::NonConstExponent {
+                            span: contract.expr_key_to_span(*rhs),
+                        },
+                    }))
+                }
+            };
+
+        if exponent == 0 {
+            asm.push(Stack::Push(1).into());
+            return Ok(Location::Value);
+        }
+
+        // The most significant bit is always set and accounts for the initial `lhs`.
+        self.compile_expr(handler, asm, lhs, contract, pred)?;
+        for bit in (0..exponent.ilog2()).rev() {
+            asm.push(Stack::Dup.into());
+            asm.push(Alu::Mul.into());
+            if exponent & (1 << bit) != 0 {
+                self.compile_expr(handler, asm, lhs, contract, pred)?;
+                asm.push(Alu::Mul.into());
+            }
+        }
+
+        Ok(Location::Value)
+    }
+
     fn compile_intrinsic_call(
         &mut self,
         handler: &Handler,
@@ -731,12 +1235,20 @@ impl<'a> AsmBuilder<'a> {
                 match self.compile_expr_pointer(handler, asm, &args[0], contract, pred)? {
                     Location::State(next_state) => {
                         // Remove the placeholder for index computation since it is not needed for
-                        // the `SizeOf` opcode.
+                        // the `SizeOf` opcode. A state variable always lives in a single slot,
+                        // regardless of how many storage keys were read to initialize it, so the
+                        // slot index left on the stack is all `StateLen` needs.
                         asm.push(Stack::Pop.into());
 
                         asm.push(Stack::Push(next_state as i64).into()); // delta
                         asm.push(Access::StateLen.into()); // Range length for State
                     }
+                    Location::StateSlot => {
+                        // Likewise, the local slot index left on the stack is all `ValueLen`
+                        // needs.
+                        asm.push(Stack::Pop.into());
+                        asm.try_push(handler, StateMemory::ValueLen.into())?;
+                    }
                     Location::Storage(is_extern) => {
                         let num_keys_to_read =
                             args[0].get_ty(contract).storage_slots(handler, contract)?;
@@ -785,16 +1297,107 @@ impl<'a> AsmBuilder<'a> {
                 asm.push(ConstraintOp::Crypto(Crypto::VerifyEd25519))
             }
 
+            ExternalIntrinsic::Abs => {
+                // Push `x` and `-x` as the two candidates, followed by the `x < 0` comparison
+                // that decides whether `-x` is selected.  `x` is computed once and copied:
+                //   [x] -> [x, 0, x] -> [x, -x] -> [x, -x, x] -> [x, -x, x < 0]
+                self.compile_expr(handler, asm, &args[0], contract, pred)?;
+                asm.push(ConstraintOp::Stack(Stack::Push(0)));
+                asm.push(ConstraintOp::Stack(Stack::Push(1)));
+                asm.push(ConstraintOp::Stack(Stack::DupFrom));
+                asm.push(Alu::Sub.into());
+                asm.push(ConstraintOp::Stack(Stack::Push(1)));
+                asm.push(ConstraintOp::Stack(Stack::DupFrom));
+                asm.push(ConstraintOp::Stack(Stack::Push(0)));
+                asm.push(Pred::Lt.into());
+                asm.push(ConstraintOp::Stack(Stack::Select))
+            }
+
+            ExternalIntrinsic::Max | ExternalIntrinsic::Min => {
+                // Push `rhs` then `lhs` as the two candidates, followed by the comparison that
+                // decides whether `lhs` is selected.  Each argument is computed once and both are
+                // copied for the comparison, which therefore has its operands swapped:
+                //   [rhs, lhs] -> [rhs, lhs, rhs, lhs] -> [rhs, lhs, rhs < lhs]
+                self.compile_expr(handler, asm, &args[1], contract, pred)?;
+                self.compile_expr(handler, asm, &args[0], contract, pred)?;
+                asm.push(ConstraintOp::Stack(Stack::Push(1)));
+                asm.push(ConstraintOp::Stack(Stack::DupFrom));
+                asm.push(ConstraintOp::Stack(Stack::Push(1)));
+                asm.push(ConstraintOp::Stack(Stack::DupFrom));
+                asm.push(if matches!(kind, ExternalIntrinsic::Max) {
+                    Pred::Lt.into()
+                } else {
+                    Pred::Gt.into()
+                });
+                asm.push(ConstraintOp::Stack(Stack::Select))
+            }
+
+            ExternalIntrinsic::Word => {
+                // The index has already been evaluated to a constant from 0 to 3.
+                let Some(Expr::Immediate {
+                    value: Immediate::Int(index),
+                    ..
+                }) = args[1].try_get(contract)
+                else {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::Internal {
+                            msg: "__word index should have been lowered to an immediate by now",
+                            span: empty_span(),
+                        },
+                    }));
+                };
+
+                match self.compile_expr_pointer(handler, asm, &args[0], contract, pred)? {
+                    location @ (Location::DecisionVar
+                    | Location::State(_)
+                    | Location::PubVar
+                    | Location::StateSlot) => {
+                        // Offset the pointer to the selected word and let the caller read just
+                        // that word.
+                        asm.push(Stack::Push(*index).into());
+                        asm.push(Alu::Add.into());
+                        return Ok(location);
+                    }
+
+                    Location::Value => {
+                        // All four words are on the stack.  Drop the words above the selected
+                        // one, then select over each word below it.
+                        for _ in *index..3 {
+                            asm.push(Stack::Pop.into());
+                        }
+                        for _ in 0..*index {
+                            asm.push(Stack::Push(1).into());
+                            asm.push(Stack::Select.into());
+                        }
+                    }
+
+                    Location::Storage(_) => {
+                        return Err(handler.emit_err(Error::Compile {
+                            error: CompileError::Internal {
+                                msg: "unexpected __word of `Location::Storage`",
+                                span: empty_span(),
+                            },
+                        }));
+                    }
+                }
+            }
+
             // All other external intrinsics can be handled generically
             _ => {
                 for (i, arg) in args.iter().enumerate() {
                     self.compile_expr(handler, asm, arg, contract, pred)?;
 
-                    // if the type of the arg is `Any`, then follow with its size
+                    // if the type of the arg is `Any`, then follow with its size. String literals
+                    // don't have a fixed size type so their size is taken from their encoding.
                     if kind.args()[i].is_any() {
-                        asm.push(ConstraintOp::Stack(Stack::Push(
-                            arg.get_ty(contract).size(handler, contract)? as i64,
-                        )));
+                        let size = match arg.get(contract) {
+                            Expr::Immediate {
+                                value: Immediate::String(val),
+                                ..
+                            } => string_to_words(val).len(),
+                            _ => arg.get_ty(contract).size(handler, contract)?,
+                        };
+                        asm.push(ConstraintOp::Stack(Stack::Push(size as i64)));
                     }
                 }
 
@@ -803,6 +1406,18 @@ impl<'a> AsmBuilder<'a> {
                         asm.push(ConstraintOp::Access(Access::PredicateAt))
                     }
 
+                    ExternalIntrinsic::RecoverAddress => {
+                        // Recover the public key and then hash it, as `__sha256` would.
+                        let key_size = ExternalIntrinsic::RecoverSECP256k1
+                            .ty()
+                            .size(handler, contract)?;
+                        asm.push(ConstraintOp::Crypto(Crypto::RecoverSecp256k1));
+                        asm.push(ConstraintOp::Stack(Stack::Push(key_size as i64)));
+                        asm.push(ConstraintOp::Stack(Stack::Push(8)));
+                        asm.push(Alu::Mul.into());
+                        asm.push(ConstraintOp::Crypto(Crypto::Sha256))
+                    }
+
                     ExternalIntrinsic::RecoverSECP256k1 => {
                         asm.push(ConstraintOp::Crypto(Crypto::RecoverSecp256k1))
                     }
@@ -821,13 +1436,24 @@ impl<'a> AsmBuilder<'a> {
                         asm.push(ConstraintOp::Access(Access::ThisContractAddress))
                     }
 
+                    ExternalIntrinsic::ThisContractSalt => {
+                        // The salt is known at compile time, so push it one word at a time.
+                        for word in essential_types::convert::word_4_from_u8_32(self.salt) {
+                            asm.push(ConstraintOp::Stack(Stack::Push(word)));
+                        }
+                    }
+
                     ExternalIntrinsic::ThisPathway => {
                         asm.push(ConstraintOp::Access(Access::ThisPathway))
                     }
 
-                    ExternalIntrinsic::AddressOf
+                    ExternalIntrinsic::Abs
+                    | ExternalIntrinsic::AddressOf
+                    | ExternalIntrinsic::Max
+                    | ExternalIntrinsic::Min
                     | ExternalIntrinsic::SizeOf
-                    | ExternalIntrinsic::VerifyEd25519 => {
+                    | ExternalIntrinsic::VerifyEd25519
+                    | ExternalIntrinsic::Word => {
                         unreachable!("SizeOf and AddressOf are handled above")
                     }
 
@@ -839,6 +1465,15 @@ impl<'a> AsmBuilder<'a> {
                             },
                         }))
                     }
+
+                    ExternalIntrinsic::Length => {
+                        return Err(handler.emit_err(Error::Compile {
+                            error: CompileError::Internal {
+                                msg: "__length should have been lowered to an immediate by now",
+                                span: empty_span(),
+                            },
+                        }))
+                    }
                 }
             }
         }
@@ -1034,7 +1669,14 @@ impl<'a> AsmBuilder<'a> {
                         .as_ref()
                         .map_or(false, |name| name.name == ident.name)
                 })
-                .expect("field name must exist, this was checked in type checking"),
+                .ok_or_else(|| {
+                    handler.emit_err(Error::Compile {
+                        error: CompileError::UnknownTupleField {
+                            field: ident.name.clone(),
+                            span: ident.span.clone(),
+                        },
+                    })
+                })?,
             TupleAccess::Error => {
                 return Err(handler.emit_err(Error::Compile {
                     error: CompileError::Internal {
@@ -1126,6 +1768,7 @@ impl<'a> AsmBuilder<'a> {
             Location::State(_)
             | Location::Storage { .. }
             | Location::PubVar { .. }
+            | Location::StateSlot
             | Location::Value => todo!("support union matches in non- decision variables?"),
         }
 
@@ -1142,7 +1785,10 @@ impl<'a> AsmBuilder<'a> {
     ) -> Result<Location, ErrorEmitted> {
         let location = self.compile_expr_pointer(handler, asm, union_expr_key, contract, pred)?;
         match location {
-            Location::State(_) | Location::PubVar { .. } | Location::DecisionVar => {
+            Location::State(_)
+            | Location::PubVar { .. }
+            | Location::DecisionVar
+            | Location::StateSlot => {
                 // Skip the tag.
                 asm.push(Stack::Push(1).into());
                 asm.push(Alu::Add.into());
@@ -1155,3 +1801,14 @@ impl<'a> AsmBuilder<'a> {
         }
     }
 }
+
+/// Encodes a string as its UTF-8 bytes packed into big-endian words, zero-padded to a multiple
+/// of 32 bytes so that every 32 byte chunk occupies 4 words, just like a `b256`.
+fn string_to_words(val: &str) -> Vec<i64> {
+    let mut bytes = val.as_bytes().to_vec();
+    bytes.resize(bytes.len().div_ceil(32) * 32, 0);
+    bytes
+        .chunks_exact(8)
+        .map(|chunk| i64::from_be_bytes(chunk.try_into().expect("chunk is 8 bytes")))
+        .collect()
+}
@@ -1,26 +1,61 @@
-use super::CompiledContract;
+use super::{AsmNotes, CompiledContract, PredicateAsmNotes};
 use essential_types::predicate::Predicate as CompiledPredicate;
 use state_asm::{Constraint, Op as StateRead};
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 
 impl Display for CompiledContract {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        for (name, compiled_predicate) in self.names.iter().zip(self.predicates.iter()) {
-            writeln!(f, "predicate {name} {{")?;
-            fmt_compiled_predicate_with_indent(compiled_predicate, f, 1)?;
-            writeln!(f, "}}\n")?;
-        }
+        fmt_compiled_contract(self, f, false)
+    }
+}
+
+/// Displays a `CompiledContract` with each op followed by the note recorded for it during asm gen,
+/// if any.
+pub(super) struct ExplainedContract<'a>(pub(super) &'a CompiledContract);
 
-        Ok(())
+impl Display for ExplainedContract<'_> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        fmt_compiled_contract(self.0, f, true)
     }
 }
 
+fn fmt_compiled_contract(
+    compiled_contract: &CompiledContract,
+    f: &mut Formatter,
+    explain: bool,
+) -> std::fmt::Result {
+    for (idx, (name, compiled_predicate)) in compiled_contract
+        .names
+        .iter()
+        .zip(compiled_contract.predicates.iter())
+        .enumerate()
+    {
+        let notes = compiled_contract.asm_notes.get(idx).filter(|_| explain);
+        writeln!(f, "predicate {name} {{")?;
+        fmt_compiled_predicate_with_notes(compiled_predicate, notes, f, 1)?;
+        writeln!(f, "}}\n")?;
+    }
+
+    Ok(())
+}
+
 /// Given a `CompiledPredicate`, print the contained assembly. This prints both the constraints assembly as
 /// well as the state reads assembly.
 pub fn fmt_compiled_predicate_with_indent(
     compiled_predicate: &CompiledPredicate,
     f: &mut Formatter,
     indent: usize,
+) -> std::fmt::Result {
+    fmt_compiled_predicate_with_notes(compiled_predicate, None, f, indent)
+}
+
+/// Like `fmt_compiled_predicate_with_indent` but if `notes` are provided, each op that has a note
+/// is followed by it.
+pub fn fmt_compiled_predicate_with_notes(
+    compiled_predicate: &CompiledPredicate,
+    notes: Option<&PredicateAsmNotes>,
+    f: &mut Formatter,
+    indent: usize,
 ) -> std::fmt::Result {
     let indent = " ".repeat(4 * indent);
     writeln!(f, "{}--- Constraints ---", indent)?;
@@ -29,9 +64,8 @@ pub fn fmt_compiled_predicate_with_indent(
             .collect::<Result<_, _>>()
             .unwrap();
         writeln!(f, "{}constraint {idx}", indent)?;
-        for op in ops {
-            writeln!(f, "{}  {:?}", indent, op)?;
-        }
+        let notes = notes.and_then(|notes| notes.constraints.get(idx));
+        fmt_ops(&ops, notes, f, &indent)?;
     }
     writeln!(f, "{}--- State Reads ---", indent)?;
     for (idx, state_read) in compiled_predicate.state_read.iter().enumerate() {
@@ -39,8 +73,24 @@ pub fn fmt_compiled_predicate_with_indent(
             .collect::<Result<_, _>>()
             .unwrap();
         writeln!(f, "{}state read {idx}", indent)?;
-        for op in ops {
-            writeln!(f, "{}  {:?}", indent, op)?;
+        let notes = notes.and_then(|notes| notes.state_reads.get(idx));
+        fmt_ops(&ops, notes, f, &indent)?;
+    }
+
+    Ok(())
+}
+
+/// Print each op in `ops` on its own line, followed by its note in `notes`, if any.
+fn fmt_ops<Op: Debug>(
+    ops: &[Op],
+    notes: Option<&AsmNotes>,
+    f: &mut Formatter,
+    indent: &str,
+) -> std::fmt::Result {
+    for (idx, op) in ops.iter().enumerate() {
+        match notes.and_then(|notes| notes.get(&idx)) {
+            Some(note) => writeln!(f, "{}  {:<32} ; {note}", indent, format!("{:?}", op))?,
+            None => writeln!(f, "{}  {:?}", indent, op)?,
         }
     }
 
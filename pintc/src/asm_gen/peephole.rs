@@ -0,0 +1,211 @@
+use super::AsmNotes;
+use state_asm::{Alu, Constraint as ConstraintOp, Op as StateOp, Pred, Stack, TotalControlFlow};
+
+/// An op in an assembly program which the peephole pass can inspect.  State programs may contain
+/// state specific ops which aren't constraint ops; those are treated as opaque.
+pub(super) trait PeepholeOp: Sized {
+    fn as_constraint_op(&self) -> Option<&ConstraintOp>;
+    fn from_constraint_op(op: ConstraintOp) -> Self;
+}
+
+impl PeepholeOp for ConstraintOp {
+    fn as_constraint_op(&self) -> Option<&ConstraintOp> {
+        Some(self)
+    }
+
+    fn from_constraint_op(op: ConstraintOp) -> Self {
+        op
+    }
+}
+
+impl PeepholeOp for StateOp {
+    fn as_constraint_op(&self) -> Option<&ConstraintOp> {
+        match self {
+            StateOp::Constraint(op) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn from_constraint_op(op: ConstraintOp) -> Self {
+        StateOp::Constraint(op)
+    }
+}
+
+/// Removes adjacent `Push(_); Pop` and `Dup; Pop` pairs from `ops`, repeatedly, so that e.g.
+/// `Push(1); Push(2); Pop; Pop` is removed entirely.  Each pair leaves the stack exactly as it
+/// was, so removing it doesn't change the result of the program.
+///
+/// The only control flow is `JumpForwardIf`, which jumps forward by a distance which is on the
+/// stack below the condition.  Where that distance is pushed by a known `Push` op the target of
+/// the jump is known, and:
+/// - a pair is never removed if a jump lands on any of its ops but the first, since then the
+///   remaining op would no longer cancel out, and
+/// - the distance of every jump over a removed pair is shortened to match.
+///
+/// Where the distance of a jump can't be determined nothing after that jump is removed.
+///
+/// Notes attached to removed ops are dropped and the remaining notes move along with their ops.
+pub(super) fn remove_redundant_ops<Op: PeepholeOp>(ops: &mut Vec<Op>, notes: &mut AsmNotes) {
+    let jumps = find_jumps(ops);
+
+    // Nothing at or after the first jump with an unknown target may be removed.
+    let removal_limit = jumps
+        .iter()
+        .filter(|jump| jump.distance.is_none())
+        .map(|jump| jump.jump_idx)
+        .min()
+        .unwrap_or(ops.len());
+
+    let is_target = |idx: usize| {
+        jumps
+            .iter()
+            .any(|jump| jump.distance.map(|(_, target)| target) == Some(idx))
+    };
+
+    // Scan the ops, keeping a stack of the indices of the ops kept so far.  A `Pop` cancels the
+    // most recently kept op if it's a `Push` or `Dup` and everything since has been removed.
+    let mut kept: Vec<usize> = Vec::with_capacity(ops.len());
+    for (idx, op) in ops.iter().enumerate() {
+        let cancels = idx < removal_limit
+            && matches!(op.as_constraint_op(), Some(ConstraintOp::Stack(Stack::Pop)))
+            && kept.last().is_some_and(|&prev_idx| {
+                matches!(
+                    ops[prev_idx].as_constraint_op(),
+                    Some(ConstraintOp::Stack(Stack::Push(_) | Stack::Dup))
+                ) && !(prev_idx + 1..=idx).any(is_target)
+            });
+
+        if cancels {
+            kept.pop();
+        } else {
+            kept.push(idx);
+        }
+    }
+
+    if kept.len() == ops.len() {
+        return;
+    }
+
+    // Map every old index, including one past the end, to its new index.  Removed ops map to the
+    // index of the next kept op, which is where a jump to them now lands.
+    let new_indices = (0..=ops.len())
+        .map(|old_idx| kept.partition_point(|&kept_idx| kept_idx < old_idx))
+        .collect::<Vec<_>>();
+
+    // Fix the distances of the known jumps.  Only jumps before `removal_limit` can be known, and
+    // their distance pushes are always kept since they are consumed by the jump, not a `Pop`.
+    let mut new_distances = Vec::new();
+    for jump in &jumps {
+        if let Some((distance_idx, target)) = jump.distance {
+            let new_distance = new_indices[target] - new_indices[jump.jump_idx];
+            new_distances.push((new_indices[distance_idx], new_distance as i64));
+        }
+    }
+
+    let mut old_ops = std::mem::take(ops)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    ops.extend(
+        kept.iter()
+            .map(|&idx| old_ops[idx].take().expect("each op is kept once")),
+    );
+    for (distance_idx, distance) in new_distances {
+        ops[distance_idx] = Op::from_constraint_op(Stack::Push(distance).into());
+    }
+
+    let old_notes = std::mem::take(notes);
+    notes.extend(
+        old_notes
+            .into_iter()
+            .filter(|(idx, _)| kept.binary_search(idx).is_ok())
+            .map(|(idx, note)| (new_indices[idx], note)),
+    );
+}
+
+/// A `JumpForwardIf` op and, if known, the index of the `Push` of its distance and its target.
+struct Jump {
+    jump_idx: usize,
+    distance: Option<(usize, usize)>,
+}
+
+fn find_jumps<Op: PeepholeOp>(ops: &[Op]) -> Vec<Jump> {
+    let mut jumps = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| {
+            matches!(
+                op.as_constraint_op(),
+                Some(ConstraintOp::TotalControlFlow(
+                    TotalControlFlow::JumpForwardIf
+                ))
+            )
+        })
+        .map(|(jump_idx, _)| Jump {
+            jump_idx,
+            distance: find_jump_distance(ops, jump_idx),
+        })
+        .collect::<Vec<_>>();
+
+    // The distance is found by walking back over straight line code.  If another jump lands
+    // somewhere within that code then the stack may differ, so treat the distance as unknown.
+    let targets = jumps
+        .iter()
+        .filter_map(|jump| jump.distance.map(|(_, target)| target))
+        .collect::<Vec<_>>();
+    for jump in &mut jumps {
+        if let Some((distance_idx, _)) = jump.distance {
+            if targets
+                .iter()
+                .any(|&target| target > distance_idx && target <= jump.jump_idx)
+            {
+                jump.distance = None;
+            }
+        }
+    }
+
+    jumps
+}
+
+/// Find the `Push` of the distance for the `JumpForwardIf` at `jump_idx` by walking back over the
+/// ops before it, tracking the depth of the distance on the stack.  Returns the index of the
+/// `Push` and the target of the jump, or `None` if an op with an unknown stack effect is reached
+/// first.
+fn find_jump_distance<Op: PeepholeOp>(ops: &[Op], jump_idx: usize) -> Option<(usize, usize)> {
+    // The condition is on top of the stack and the distance is just below it.
+    let mut depth = 1;
+    for idx in (0..jump_idx).rev() {
+        let op = ops[idx].as_constraint_op()?;
+        let (pops, pushes) = stack_effect(op)?;
+        if depth < pushes {
+            // This op produced the distance.
+            return match (op, depth) {
+                (ConstraintOp::Stack(Stack::Push(distance)), 0) if *distance > 0 => {
+                    let target = jump_idx.checked_add(usize::try_from(*distance).ok()?)?;
+                    (target <= ops.len()).then_some((idx, target))
+                }
+                _ => None,
+            };
+        }
+        depth = depth - pushes + pops;
+    }
+    None
+}
+
+/// The number of words popped and pushed by `op`, if fixed.
+fn stack_effect(op: &ConstraintOp) -> Option<(usize, usize)> {
+    match op {
+        ConstraintOp::Stack(Stack::Push(_)) => Some((0, 1)),
+        ConstraintOp::Stack(Stack::Pop) => Some((1, 0)),
+        ConstraintOp::Stack(Stack::Dup) => Some((1, 2)),
+        ConstraintOp::Stack(Stack::Select) => Some((3, 1)),
+        ConstraintOp::Pred(Pred::Not) => Some((1, 1)),
+        ConstraintOp::Pred(
+            Pred::Eq | Pred::Lt | Pred::Lte | Pred::Gt | Pred::Gte | Pred::BitAnd | Pred::BitOr,
+        ) => Some((2, 1)),
+        ConstraintOp::Alu(
+            Alu::Add | Alu::Sub | Alu::Mul | Alu::Div | Alu::Mod | Alu::Shl | Alu::ShrI,
+        ) => Some((2, 1)),
+        _ => None,
+    }
+}
@@ -1,12 +1,15 @@
 use crate::{
     asm_gen::{compile_contract, CompiledContract},
-    error::Handler,
+    error::{Handler, ReportableError},
+    expr::{Expr, Immediate, TupleAccess},
     parser::parse_project,
+    predicate::{CompileOptions, Contract, MAX_REAL_SCALE_BITS},
 };
 use std::io::Write;
 
 mod intrinsics;
 mod multi_predicates;
+mod peephole;
 mod pub_vars;
 
 #[cfg(test)]
@@ -14,26 +17,43 @@ pub(super) fn check(actual: &str, expect: expect_test::Expect) {
     expect.assert_eq(actual);
 }
 
-/// Compile some code into `CompiledContract`. Panics if anything fails.
+/// Parse, type check, flatten and optimize some code into a `Contract`. Panics if anything fails.
 #[cfg(test)]
-pub(super) fn compile(code: &str) -> CompiledContract {
-    use crate::predicate::CompileOptions;
+pub(super) fn flatten(code: &str) -> Contract {
+    flatten_with_options(code, &CompileOptions::default())
+}
 
+/// Like `flatten()` but with custom `CompileOptions`.
+#[cfg(test)]
+pub(super) fn flatten_with_options(code: &str, options: &CompileOptions) -> Contract {
     let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
     write!(tmpfile.as_file_mut(), "{}", code).unwrap();
     let handler = Handler::default();
     let deps = Default::default();
-    let contract = parse_project(&handler, &deps, tmpfile.path())
+    parse_project(&handler, &deps, tmpfile.path())
         .unwrap()
-        .compile(
-            &handler,
-            CompileOptions {
-                skip_optimize: false,
-                print_flat: false,
-            },
-        )
-        .unwrap();
-    compile_contract(&handler, &contract).unwrap()
+        .compile(&handler, options)
+        .unwrap()
+}
+
+/// Compile some code into `CompiledContract`. Panics if anything fails.
+#[cfg(test)]
+pub(super) fn compile(code: &str) -> CompiledContract {
+    compile_contract(
+        &Handler::default(),
+        &flatten(code),
+        &CompileOptions::default(),
+    )
+    .unwrap()
+}
+
+/// Compile some code into a `CompiledContract`, expecting asm generation to fail. Returns the
+/// first error emitted.
+#[cfg(test)]
+pub(super) fn compile_with_error(contract: &Contract) -> String {
+    let handler = Handler::default();
+    assert!(compile_contract(&handler, contract, &CompileOptions::default()).is_err());
+    handler.consume().0[0].display_raw()
 }
 
 #[test]
@@ -116,6 +136,401 @@ fn int_literals() {
     assert_eq!(compiled_contract.predicates.len(), 1);
 }
 
+#[test]
+fn bitwise_xor() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                var y: int;
+                constraint x ^ y == 6;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Pred(BitOr)
+                  Stack(Push(2))
+                  Stack(SwapIndex)
+                  Pred(BitAnd)
+                  Alu(Sub)
+                  Stack(Push(6))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[cfg(feature = "experimental-types")]
+#[test]
+fn real_literals() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: real = 1.5;
+                constraint x > 1.0;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(6442450944))
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(4294967296))
+                  Pred(Gt)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[cfg(feature = "experimental-types")]
+#[test]
+fn real_mul() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var y: real; var z: real;
+                constraint y * 2.0 == z;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(8589934592))
+                  Stack(Push(0))
+                  Stack(DupFrom)
+                  Stack(Dup)
+                  Stack(Push(32))
+                  Alu(ShrI)
+                  Stack(Push(32))
+                  Alu(Shl)
+                  Alu(Sub)
+                  Stack(Push(2))
+                  Stack(DupFrom)
+                  Stack(Push(2))
+                  Stack(DupFrom)
+                  Stack(Push(32))
+                  Alu(ShrI)
+                  Alu(Mul)
+                  Stack(Push(3))
+                  Stack(DupFrom)
+                  Stack(Push(32))
+                  Alu(ShrI)
+                  Stack(Push(2))
+                  Stack(DupFrom)
+                  Alu(Mul)
+                  Alu(Add)
+                  Stack(Push(3))
+                  Stack(DupFrom)
+                  Stack(Dup)
+                  Stack(Push(32))
+                  Alu(ShrI)
+                  Stack(Push(32))
+                  Alu(Shl)
+                  Alu(Sub)
+                  Stack(Push(1))
+                  Alu(ShrI)
+                  Stack(Push(2))
+                  Stack(DupFrom)
+                  Alu(Mul)
+                  Stack(Push(31))
+                  Alu(ShrI)
+                  Alu(Add)
+                  Stack(Push(3))
+                  Stack(SwapIndex)
+                  Stack(Pop)
+                  Stack(Pop)
+                  Stack(Pop)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[cfg(feature = "experimental-types")]
+#[test]
+fn real_mul_div_precision() {
+    use state_asm::{Access, Alu, Constraint as Op, Pred, Stack};
+
+    let compiled_contract = compile(
+        r#"
+        predicate test {
+            var a: real; var b: real; var c: real; var d: real; var e: real; var f: real;
+            constraint a * b == 3.0;
+            constraint c / b == 1.5;
+            constraint c / d == 3145728.0;
+            constraint d * e == f;
+            constraint c / 0.00001 > 299997.0;
+            constraint c / 0.00001 < 299998.0;
+        }
+        "#,
+    );
+
+    // Run a constraint program which only reads single word decision variables and does
+    // arithmetic, failing on overflow just like the VM does.
+    let eval = |constraint: &[u8], vars: &[i64]| -> Option<bool> {
+        let mut stack = Vec::<i64>::new();
+        for op in constraint_asm::from_bytes(constraint.iter().copied()) {
+            match op.unwrap() {
+                Op::Stack(Stack::Push(val)) => stack.push(val),
+                Op::Stack(Stack::Pop) => {
+                    stack.pop()?;
+                }
+                Op::Stack(Stack::Dup) => stack.push(*stack.last()?),
+                Op::Stack(Stack::DupFrom) => {
+                    let idx = stack.pop()? as usize;
+                    stack.push(stack[stack.len().checked_sub(idx + 1)?]);
+                }
+                Op::Stack(Stack::SwapIndex) => {
+                    let idx = stack.pop()? as usize;
+                    let top = stack.len() - 1;
+                    stack.swap(top, top.checked_sub(idx)?);
+                }
+                Op::Access(Access::DecisionVar) => {
+                    stack.truncate(stack.len() - 2);
+                    let slot = stack.pop()?;
+                    stack.push(vars[slot as usize]);
+                }
+                Op::Alu(alu) => {
+                    let rhs = stack.pop()?;
+                    let lhs = stack.pop()?;
+                    stack.push(match alu {
+                        Alu::Add => lhs.checked_add(rhs)?,
+                        Alu::Sub => lhs.checked_sub(rhs)?,
+                        Alu::Mul => lhs.checked_mul(rhs)?,
+                        Alu::Div => lhs.checked_div(rhs)?,
+                        Alu::Mod => lhs.checked_rem(rhs)?,
+                        Alu::Shl => lhs << rhs,
+                        Alu::ShrI => lhs >> rhs,
+                        _ => unimplemented!(),
+                    });
+                }
+                Op::Pred(pred) => {
+                    let rhs = stack.pop()?;
+                    let lhs = stack.pop()?;
+                    stack.push(match pred {
+                        Pred::Eq => lhs == rhs,
+                        Pred::Lt => lhs < rhs,
+                        Pred::Gt => lhs > rhs,
+                        Pred::Gte => lhs >= rhs,
+                        _ => unimplemented!(),
+                    } as i64);
+                }
+                _ => unimplemented!(),
+            }
+        }
+        (stack.len() == 1).then(|| stack[0] == 1)
+    };
+
+    // With the default scale of 2^32, `1.5 * 2.0` and `3.0 / 2.0` would both overflow if the
+    // product or the scaled up `lhs` were computed in full.  `d` is 2^-20 and `e` is 2^-10, which
+    // would be shifted to 0 if the operands were scaled down first, and so would `0.00001`.
+    let scale = 1_i64 << crate::predicate::DEFAULT_REAL_SCALE_BITS;
+    let vars = [
+        3 * scale / 2,
+        2 * scale,
+        3 * scale,
+        scale >> 20,
+        scale >> 10,
+        scale >> 30,
+    ];
+    let constraints = &compiled_contract.predicates[0].constraints;
+    for constraint in &constraints[0..6] {
+        assert_eq!(eval(constraint, &vars), Some(true));
+    }
+}
+
+#[cfg(feature = "experimental-types")]
+#[test]
+fn real_scale_bits() {
+    let code = r#"
+        predicate test {
+            var x: real = 1.5;
+        }
+        "#;
+
+    let compile_with_scale_bits = |real_scale_bits| {
+        compile_contract(
+            &Handler::default(),
+            &flatten(code),
+            &CompileOptions {
+                real_scale_bits,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .to_string()
+    };
+
+    // 1.5 * 2^32
+    let default_scale = compile_with_scale_bits(crate::predicate::DEFAULT_REAL_SCALE_BITS);
+    assert!(default_scale.contains("Stack(Push(6442450944))"));
+
+    // 1.5 * 2^16
+    let smaller_scale = compile_with_scale_bits(16);
+    assert!(smaller_scale.contains("Stack(Push(98304))"));
+    assert!(!smaller_scale.contains("Stack(Push(6442450944))"));
+}
+
+#[test]
+fn real_scale_bits_out_of_range() {
+    let contract = flatten("predicate test { var x: int; }");
+    let compile_with_scale_bits = |real_scale_bits| {
+        let handler = Handler::default();
+        let options = CompileOptions {
+            real_scale_bits,
+            ..Default::default()
+        };
+        let _ = compile_contract(&handler, &contract, &options);
+        handler
+            .consume()
+            .0
+            .iter()
+            .map(|err| err.display_raw())
+            .collect::<String>()
+    };
+
+    assert_eq!(compile_with_scale_bits(MAX_REAL_SCALE_BITS), "");
+    check(
+        &compile_with_scale_bits(MAX_REAL_SCALE_BITS + 1),
+        expect_test::expect![[r#"
+            `real` scale of 63 bits is out of range
+            the number of fractional bits must be at most 62
+        "#]],
+    );
+}
+
+#[test]
+fn real_literal_overflow() {
+    // 10^10 * 2^32 doesn't fit in a word.
+    check(
+        &compile_with_error(&flatten(
+            "predicate test { var x: real; constraint x == 10000000000.0; }",
+        )),
+        expect_test::expect![[r#"
+            integer overflow
+            @46..59: this expression evaluates to a value that does not fit in an `int`
+            `int` values must be in the range -9223372036854775808 to 9223372036854775807
+        "#]],
+    );
+}
+
+#[test]
+fn dup_identical_operands() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                constraint x + x == 4;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Dup)
+                  Alu(Add)
+                  Stack(Push(4))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[cfg(feature = "experimental-types")]
+#[test]
+fn dup_identical_real_operands() {
+    // `real` multiplication and division operate on the two words on top of the stack, so their
+    // operands are shared too.
+    for op in ["*", "/"] {
+        let compiled = compile(&format!(
+            "predicate test {{ var x: real; constraint x {op} x == 1.0; }}"
+        ))
+        .to_string();
+        assert_eq!(compiled.matches("Access(DecisionVar)").count(), 1);
+    }
+}
+
 #[test]
 fn unary_not() {
     check(
@@ -265,8 +680,6 @@ fn select() {
                   Stack(Push(9))
                   Stack(Push(0))
                   Stack(Push(0))
-                  Stack(Pop)
-                  Stack(Push(0))
                   Access(StateLen)
                   Stack(Push(0))
                   Pred(Eq)
@@ -315,6 +728,32 @@ fn select() {
     );
 }
 
+#[test]
+fn select_with_division() {
+    let compile_select = |then_expr: &str| {
+        compile(&format!(
+            "predicate test {{ var c: bool; var a: int; var b: int; var z = c ? {then_expr} : 0; }}"
+        ))
+        .to_string()
+    };
+
+    // A division by a variable may panic, so it must only be evaluated if it's selected.
+    let compiled = compile_select("a / b");
+    assert!(
+        compiled.contains("TotalControlFlow(JumpForwardIf)"),
+        "{compiled}"
+    );
+    assert!(!compiled.contains("Stack(Select)"), "{compiled}");
+
+    // A division by a non-zero constant can't panic, so both branches are evaluated.
+    let compiled = compile_select("a / 2");
+    assert!(compiled.contains("Stack(Select)"), "{compiled}");
+    assert!(
+        !compiled.contains("TotalControlFlow(JumpForwardIf)"),
+        "{compiled}"
+    );
+}
+
 #[test]
 fn select_range() {
     check(
@@ -619,16 +1058,6 @@ fn binary_ops() {
                   Access(DecisionVar)
                   Pred(Gt)
                 constraint 11
-                  Stack(Push(0))
-                  Stack(Push(0))
-                  Stack(Push(1))
-                  Access(DecisionVar)
-                  Stack(Push(1))
-                  Stack(Push(0))
-                  Stack(Push(1))
-                  Access(DecisionVar)
-                  Pred(Gt)
-                constraint 12
                   Access(MutKeys)
                   Stack(Push(0))
                   Pred(EqSet)
@@ -640,16 +1069,16 @@ fn binary_ops() {
 }
 
 #[test]
-fn short_circuit_and() {
+fn const_folded_binary_ops() {
     check(
         &format!(
             "{}",
             compile(
                 r#"
             predicate test {
-                var a: bool;
-                var b: bool;
-                constraint a && b;
+                var x: int;
+                constraint x == 2 + 3 * 4;
+                constraint x > -(6 / 2);
             }
             "#,
             ),
@@ -658,15 +1087,354 @@ fn short_circuit_and() {
             predicate ::test {
                 --- Constraints ---
                 constraint 0
-                  Stack(Push(0))
-                  Stack(Push(6))
                   Stack(Push(0))
                   Stack(Push(0))
                   Stack(Push(1))
                   Access(DecisionVar)
-                  Pred(Not)
-                  TotalControlFlow(JumpForwardIf)
-                  Stack(Pop)
+                  Stack(Push(14))
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(-3))
+                  Pred(Gt)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn duplicate_constraints() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                constraint x > 0;
+                constraint x > 0;
+                constraint x > 0;
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(0))
+                  Pred(Gt)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn pow() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int; var y: int;
+                constraint x ** 0 == y;
+                constraint x ** 1 == y;
+                constraint x ** 3 == y;
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(1))
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Pred(Eq)
+                constraint 2
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Dup)
+                  Alu(Mul)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Alu(Mul)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Pred(Eq)
+                constraint 3
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn pow_non_const_exponent() {
+    check(
+        &compile_with_error(&flatten(
+            "predicate test { var x: int; var n: int; constraint x ** n == 8; }",
+        )),
+        expect_test::expect![[r#"
+            attempt to use a non-constant value as an exponent
+            @57..58: this must be a non-negative integer constant
+        "#]],
+    );
+}
+
+#[test]
+fn pow_large_exponent() {
+    // `x ** 1000` needs 9 squarings and 5 more multiplications by `x`, one per set bit other than
+    // the most significant.
+    let compiled = compile("predicate test { var x: int; constraint x ** 1000 == 0; }").to_string();
+    assert_eq!(compiled.matches("Stack(Dup)").count(), 9);
+    assert_eq!(compiled.matches("Alu(Mul)").count(), 9 + 5);
+    assert_eq!(compiled.matches("Access(DecisionVar)").count(), 6);
+}
+
+#[test]
+fn pow_negative_exponent() {
+    check(
+        &compile_with_error(&flatten(
+            "predicate test { var x: int; constraint x ** -1 == 8; }",
+        )),
+        expect_test::expect![[r#"
+            attempt to raise a value to a negative power
+            @45..47: this exponent is negative
+        "#]],
+    );
+}
+
+#[test]
+fn casts() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var b: bool;
+                constraint (b as int) + 1 == 2;
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Alu(Add)
+                  Stack(Push(2))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[cfg(feature = "experimental-types")]
+#[test]
+fn unsupported_cast() {
+    check(
+        &compile_with_error(&flatten(
+            r#"
+        predicate test {
+            var x: int;
+            var r: real;
+            constraint r == x as real;
+        }
+        "#,
+        )),
+        expect_test::expect![[r#"
+            unsupported cast
+            @103..112: casts from `int` to `real` are not yet supported
+        "#]],
+    );
+}
+
+#[test]
+fn unknown_tuple_field() {
+    // Type checking rejects unknown tuple field names, so rename the field after the fact to
+    // confirm asm gen reports an error rather than panicking.
+    let mut contract = flatten(
+        r#"
+        predicate test {
+            var t: { x: int, y: int };
+            constraint t.y == 42;
+        }
+        "#,
+    );
+
+    let pred_key = contract.preds.keys().next().unwrap();
+    for expr_key in contract.exprs(pred_key).collect::<Vec<_>>() {
+        if let Expr::TupleFieldAccess {
+            field: TupleAccess::Name(ident),
+            ..
+        } = expr_key.get_mut(&mut contract)
+        {
+            ident.name = "z".to_string();
+        }
+    }
+
+    check(
+        &compile_with_error(&contract),
+        expect_test::expect![[r#"
+            unknown tuple field `z`
+            @90..91: tuple has no field named `z`
+        "#]],
+    );
+}
+
+#[test]
+fn nil_immediate() {
+    // Comparisons to `nil` are lowered before asm gen, so replace an immediate with `nil` after
+    // the fact to confirm asm gen reports an error rather than panicking.
+    let mut contract = flatten(
+        r#"
+        predicate test {
+            var x: int;
+            constraint x == 42;
+        }
+        "#,
+    );
+
+    let pred_key = contract.preds.keys().next().unwrap();
+    for expr_key in contract.exprs(pred_key).collect::<Vec<_>>() {
+        if let Expr::Immediate { value, .. } = expr_key.get_mut(&mut contract) {
+            if *value == Immediate::Int(42) {
+                *value = Immediate::Nil;
+            }
+        }
+    }
+
+    check(
+        &compile_with_error(&contract),
+        expect_test::expect![[r#"
+            `nil` is not supported here
+            @78..80: `nil` cannot be encoded as a value
+            `nil` may only be compared to a `state` variable using `==` or `!=`
+        "#]],
+    );
+}
+
+#[test]
+fn named_tuple_compare_order() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var t: { x: int, y: int };
+                constraint t == { y: 1, x: 0 };
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(2))
+                  Access(DecisionVar)
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Stack(Push(2))
+                  Pred(EqRange)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn short_circuit_and() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var a: bool;
+                var b: bool;
+                constraint a && b;
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(6))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Pred(Not)
+                  TotalControlFlow(JumpForwardIf)
+                  Stack(Pop)
                   Stack(Push(1))
                   Stack(Push(0))
                   Stack(Push(1))
@@ -724,6 +1492,159 @@ fn short_circuit_or() {
     );
 }
 
+#[test]
+fn short_circuit_const_folding() {
+    // Each logical op with a constant operand is simplified away before asm gen, so there are no
+    // jumps left.
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var a: bool;
+                var b: bool;
+                var c: bool;
+                constraint true && a;
+                constraint b || false;
+                constraint c == (a || true);
+                constraint !(false && b);
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                constraint 1
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                constraint 2
+                  Stack(Push(2))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Pred(Eq)
+                constraint 3
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn in_range() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                constraint x in 1..10;
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(8))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Pred(Gte)
+                  Pred(Not)
+                  TotalControlFlow(JumpForwardIf)
+                  Stack(Pop)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(10))
+                  Pred(Lte)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn in_array() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                constraint x in [1, 2, 3];
+            }
+            "#,
+            ),
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(1))
+                  Stack(Push(8))
+                  Stack(Push(1))
+                  Stack(Push(8))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Pred(Eq)
+                  TotalControlFlow(JumpForwardIf)
+                  Stack(Pop)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(2))
+                  Pred(Eq)
+                  TotalControlFlow(JumpForwardIf)
+                  Stack(Pop)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(3))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
 #[test]
 fn next_state() {
     let compiled_contract = &compile(
@@ -795,18 +1716,108 @@ fn next_state() {
 
         "#]],
     );
-
-    // Single top-level predicate named `CompiledContract::ROOT_PRED_NAME`
-    assert_eq!(compiled_contract.predicates.len(), 1);
+
+    // Single top-level predicate named `CompiledContract::ROOT_PRED_NAME`
+    assert_eq!(compiled_contract.predicates.len(), 1);
+}
+
+#[test]
+fn b256() {
+    let compiled_contract = &compile(
+        r#"
+        predicate test {
+            var b0 = 0x0000000000000005000000000000000600000000000000070000000000000008;
+            var b1 = 0xF000000000000000500000000000000060000000000000007000000000000000;
+        }
+        "#,
+    );
+
+    check(
+        &format!("{compiled_contract}"),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(5))
+                  Stack(Push(6))
+                  Stack(Push(7))
+                  Stack(Push(8))
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 1
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(-1152921504606846976))
+                  Stack(Push(5764607523034234880))
+                  Stack(Push(6917529027641081856))
+                  Stack(Push(8070450532247928832))
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn b256_not_equal() {
+    let compiled_contract = &compile(
+        r#"
+        predicate test {
+            var a: b256;
+            var b: b256;
+            constraint a != b;
+        }
+        "#,
+    );
+
+    check(
+        &format!("{compiled_contract}"),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(4))
+                  Pred(EqRange)
+                  Pred(Not)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
 }
 
 #[test]
-fn b256() {
+fn short_b256() {
     let compiled_contract = &compile(
         r#"
         predicate test {
-            var b0 = 0x0000000000000005000000000000000600000000000000070000000000000008;
-            var b1 = 0xF000000000000000500000000000000060000000000000007000000000000000;
+            var b0: b256 = 0x01;
+            var b1: b256 = 0b101;
+            var b2: b256 = 0xFFFFFFFFFFFFFFFF;
         }
         "#,
     );
@@ -821,10 +1832,10 @@ fn b256() {
                   Stack(Push(0))
                   Stack(Push(4))
                   Access(DecisionVar)
-                  Stack(Push(5))
-                  Stack(Push(6))
-                  Stack(Push(7))
-                  Stack(Push(8))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
                   Stack(Push(4))
                   Pred(EqRange)
                 constraint 1
@@ -832,13 +1843,24 @@ fn b256() {
                   Stack(Push(0))
                   Stack(Push(4))
                   Access(DecisionVar)
-                  Stack(Push(-1152921504606846976))
-                  Stack(Push(5764607523034234880))
-                  Stack(Push(6917529027641081856))
-                  Stack(Push(8070450532247928832))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(5))
                   Stack(Push(4))
                   Pred(EqRange)
                 constraint 2
+                  Stack(Push(2))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(-1))
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 3
                   Access(MutKeys)
                   Stack(Push(0))
                   Pred(EqSet)
@@ -985,6 +2007,93 @@ predicate Simple {
     );
 }
 
+#[test]
+fn storage_access_dependent_states() {
+    let compiled_contract = &compile(
+        r#"
+storage {
+    supply: int,
+}
+
+predicate Simple {
+    state next_supply = supply + 1;
+    state supply = storage::supply;
+
+    constraint next_supply == 43;
+}
+        "#,
+    );
+
+    // `next_supply` is computed in the same state program as `supply`, from its slot, so the
+    // storage is only read once.
+    assert_eq!(compiled_contract.predicates[0].state_read.len(), 1);
+    assert_eq!(
+        format!("{compiled_contract}")
+            .lines()
+            .filter(|line| line.trim() == "KeyRange")
+            .count(),
+        1
+    );
+
+    check(
+        &format!("{compiled_contract}"),
+        expect_test::expect![[r#"
+            predicate ::Simple {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(2))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Access(State)
+                  Stack(Push(43))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+                state read 0
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(1)))
+                  KeyRange
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  StateMemory(Load)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  Constraint(Alu(Add))
+                  StateMemory(Store)
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(2)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(Load)
+                  Constraint(Stack(Push(1)))
+                  Constraint(Alu(Add))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(Store)
+                  Constraint(TotalControlFlow(Halt))
+            }
+
+        "#]],
+    );
+}
+
 #[test]
 fn storage_access_b256_values() {
     let compiled_contract = &compile(
@@ -1171,6 +2280,38 @@ predicate Simple {
     );
 }
 
+#[test]
+fn storage_access_multiple_b256_reads() {
+    let compiled_contract = &compile(
+        r#"
+storage {
+    addr1: b256,
+    addr2: b256,
+}
+
+predicate Simple {
+    state same = storage::addr1 == storage::addr2;
+    constraint same;
+}
+        "#,
+    );
+
+    // Slot 0 holds the state var itself. Each storage read must then target its own slot so that
+    // the second `b256` doesn't overwrite the first.
+    let asm = format!("{compiled_contract}");
+    let lines = asm.lines().map(str::trim).collect::<Vec<_>>();
+    let mut slot_indices = lines
+        .windows(2)
+        .filter(|window| window[1] == "KeyRange")
+        .map(|window| window[0])
+        .collect::<Vec<_>>();
+    slot_indices.sort();
+    assert_eq!(
+        slot_indices,
+        vec!["Constraint(Stack(Push(1)))", "Constraint(Stack(Push(2)))"]
+    );
+}
+
 #[test]
 fn storage_access_tuples() {
     let compiled_contract = &compile(
@@ -2922,66 +4063,178 @@ predicate Bar {
                 state read 3
                   Constraint(Stack(Push(1)))
                   StateMemory(AllocSlots)
-                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(3)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(69)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(1)))
+                  KeyRangeExtern
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  StateMemory(Load)
+                  Constraint(Stack(Push(2)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(2)))
+                  StateMemory(ValueLen)
+                  StateMemory(Load)
+                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(3)))
+                  StateMemory(ValueLen)
+                  StateMemory(Load)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  Constraint(Alu(Add))
+                  Constraint(Stack(Push(2)))
+                  StateMemory(ValueLen)
+                  Constraint(Alu(Add))
+                  Constraint(Stack(Push(3)))
+                  StateMemory(ValueLen)
+                  Constraint(Alu(Add))
+                  StateMemory(Store)
+                  Constraint(TotalControlFlow(Halt))
+                state read 4
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(-1229782938247303442)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(69)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(1)))
+                  KeyRangeExtern
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  StateMemory(Load)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  Constraint(Alu(Add))
+                  StateMemory(Store)
+                  Constraint(TotalControlFlow(Halt))
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn storage_access_complex_maps() {
+    let compiled_contract = &compile(
+        r#"
+storage {
+    map_in_map: (int => (b256 => int)),
+    map_in_map_in_map: (int => (b256 => (int => b256))),
+}
+
+predicate Simple {
+    state map_in_map_entry = storage::map_in_map[9][0x0000000000000001000000000000000200000000000000030000000000000004];
+    state map_in_map_in_map_entry = storage::map_in_map_in_map[88][0x0000000000000008000000000000000700000000000000060000000000000005][999];
+
+    constraint map_in_map_entry' == 42;
+    constraint map_in_map_in_map_entry' == 0x000000000000000F000000000000000F000000000000000F000000000000000F;
+}
+        "#,
+    );
+
+    check(
+        &format!("{compiled_contract}"),
+        expect_test::expect![[r#"
+            predicate ::Simple {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Stack(Push(1))
+                  Access(State)
+                  Stack(Push(42))
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(2))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Stack(Push(1))
+                  Access(State)
+                  Stack(Push(15))
+                  Stack(Push(15))
+                  Stack(Push(15))
+                  Stack(Push(15))
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+                state read 0
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(1)))
                   StateMemory(AllocSlots)
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(0)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(1)))
-                  Constraint(Stack(Push(69)))
                   Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(9)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(2)))
                   Constraint(Stack(Push(3)))
-                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(4)))
+                  Constraint(Stack(Push(6)))
                   Constraint(Stack(Push(1)))
-                  KeyRangeExtern
                   Constraint(Stack(Push(1)))
-                  Constraint(Stack(Push(0)))
+                  KeyRange
                   Constraint(Stack(Push(1)))
-                  StateMemory(ValueLen)
-                  StateMemory(Load)
-                  Constraint(Stack(Push(2)))
-                  Constraint(Stack(Push(0)))
-                  Constraint(Stack(Push(2)))
-                  StateMemory(ValueLen)
-                  StateMemory(Load)
-                  Constraint(Stack(Push(3)))
                   Constraint(Stack(Push(0)))
-                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(1)))
                   StateMemory(ValueLen)
                   StateMemory(Load)
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(1)))
                   StateMemory(ValueLen)
                   Constraint(Alu(Add))
-                  Constraint(Stack(Push(2)))
-                  StateMemory(ValueLen)
-                  Constraint(Alu(Add))
-                  Constraint(Stack(Push(3)))
-                  StateMemory(ValueLen)
-                  Constraint(Alu(Add))
                   StateMemory(Store)
                   Constraint(TotalControlFlow(Halt))
-                state read 4
+                state read 1
                   Constraint(Stack(Push(1)))
                   StateMemory(AllocSlots)
                   Constraint(Stack(Push(1)))
                   StateMemory(AllocSlots)
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(0)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(-1229782938247303442)))
-                  Constraint(Stack(Push(1)))
-                  Constraint(Stack(Push(69)))
                   Constraint(Stack(Push(1)))
-                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(88)))
+                  Constraint(Stack(Push(8)))
+                  Constraint(Stack(Push(7)))
+                  Constraint(Stack(Push(6)))
+                  Constraint(Stack(Push(5)))
+                  Constraint(Stack(Push(999)))
+                  Constraint(Stack(Push(7)))
                   Constraint(Stack(Push(1)))
                   Constraint(Stack(Push(1)))
-                  KeyRangeExtern
+                  KeyRange
                   Constraint(Stack(Push(1)))
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(1)))
@@ -3000,24 +4253,25 @@ predicate Bar {
 }
 
 #[test]
-fn storage_access_complex_maps() {
+fn storage_access_tuple_keyed_maps() {
     let compiled_contract = &compile(
         r#"
 storage {
-    map_in_map: (int => (b256 => int)),
-    map_in_map_in_map: (int => (b256 => (int => b256))),
+    pairs: ({int, int} => b256),
+    owners: ({b256, bool} => int),
 }
 
 predicate Simple {
-    state map_in_map_entry = storage::map_in_map[9][0x0000000000000001000000000000000200000000000000030000000000000004];
-    state map_in_map_in_map_entry = storage::map_in_map_in_map[88][0x0000000000000008000000000000000700000000000000060000000000000005][999];
+    state pair = storage::pairs[{3, 4}];
+    state owner = storage::owners[{0x0000000000000001000000000000000200000000000000030000000000000004, true}];
 
-    constraint map_in_map_entry' == 42;
-    constraint map_in_map_in_map_entry' == 0x000000000000000F000000000000000F000000000000000F000000000000000F;
+    constraint pair' == 0x000000000000000F000000000000000F000000000000000F000000000000000F;
+    constraint owner' == 42;
 }
         "#,
     );
 
+    // The tuple key is laid out word by word right after the storage index.
     check(
         &format!("{compiled_contract}"),
         expect_test::expect![[r#"
@@ -3026,14 +4280,6 @@ predicate Simple {
                 constraint 0
                   Stack(Push(0))
                   Stack(Push(0))
-                  Stack(Push(1))
-                  Stack(Push(1))
-                  Access(State)
-                  Stack(Push(42))
-                  Pred(Eq)
-                constraint 1
-                  Stack(Push(2))
-                  Stack(Push(0))
                   Stack(Push(4))
                   Stack(Push(1))
                   Access(State)
@@ -3043,6 +4289,14 @@ predicate Simple {
                   Stack(Push(15))
                   Stack(Push(4))
                   Pred(EqRange)
+                constraint 1
+                  Stack(Push(2))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Stack(Push(1))
+                  Access(State)
+                  Stack(Push(42))
+                  Pred(Eq)
                 constraint 2
                   Access(MutKeys)
                   Stack(Push(0))
@@ -3056,12 +4310,9 @@ predicate Simple {
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(0)))
-                  Constraint(Stack(Push(9)))
-                  Constraint(Stack(Push(1)))
-                  Constraint(Stack(Push(2)))
                   Constraint(Stack(Push(3)))
                   Constraint(Stack(Push(4)))
-                  Constraint(Stack(Push(6)))
+                  Constraint(Stack(Push(3)))
                   Constraint(Stack(Push(1)))
                   Constraint(Stack(Push(1)))
                   KeyRange
@@ -3084,13 +4335,12 @@ predicate Simple {
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(0)))
                   Constraint(Stack(Push(1)))
-                  Constraint(Stack(Push(88)))
-                  Constraint(Stack(Push(8)))
-                  Constraint(Stack(Push(7)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(2)))
+                  Constraint(Stack(Push(3)))
+                  Constraint(Stack(Push(4)))
+                  Constraint(Stack(Push(1)))
                   Constraint(Stack(Push(6)))
-                  Constraint(Stack(Push(5)))
-                  Constraint(Stack(Push(999)))
-                  Constraint(Stack(Push(7)))
                   Constraint(Stack(Push(1)))
                   Constraint(Stack(Push(1)))
                   KeyRange
@@ -3370,16 +4620,12 @@ predicate Foo {
                 constraint 0
                   Stack(Push(0))
                   Stack(Push(0))
-                  Stack(Pop)
-                  Stack(Push(0))
                   Access(StateLen)
                   Stack(Push(0))
                   Pred(Eq)
                   Pred(Not)
                 constraint 1
                   Stack(Push(0))
-                  Stack(Push(0))
-                  Stack(Pop)
                   Stack(Push(1))
                   Access(StateLen)
                   Stack(Push(0))
@@ -3388,16 +4634,12 @@ predicate Foo {
                 constraint 2
                   Stack(Push(2))
                   Stack(Push(0))
-                  Stack(Pop)
-                  Stack(Push(0))
                   Access(StateLen)
                   Stack(Push(0))
                   Pred(Eq)
                   Pred(Not)
                 constraint 3
                   Stack(Push(2))
-                  Stack(Push(0))
-                  Stack(Pop)
                   Stack(Push(1))
                   Access(StateLen)
                   Stack(Push(0))
@@ -3405,15 +4647,11 @@ predicate Foo {
                 constraint 4
                   Stack(Push(4))
                   Stack(Push(0))
-                  Stack(Pop)
-                  Stack(Push(0))
                   Access(StateLen)
                   Stack(Push(0))
                   Pred(Eq)
                 constraint 5
                   Stack(Push(4))
-                  Stack(Push(0))
-                  Stack(Pop)
                   Stack(Push(1))
                   Access(StateLen)
                   Stack(Push(0))
@@ -3422,15 +4660,11 @@ predicate Foo {
                 constraint 6
                   Stack(Push(7))
                   Stack(Push(0))
-                  Stack(Pop)
-                  Stack(Push(0))
                   Access(StateLen)
                   Stack(Push(0))
                   Pred(Eq)
                 constraint 7
                   Stack(Push(7))
-                  Stack(Push(0))
-                  Stack(Pop)
                   Stack(Push(1))
                   Access(StateLen)
                   Stack(Push(0))
@@ -3589,6 +4823,75 @@ predicate Foo {
     );
 }
 
+#[test]
+fn size_of_b256_state() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+storage {
+    h: b256,
+}
+
+predicate Foo {
+    state h = storage::h;
+
+    constraint __size_of(h) == 4;
+    constraint __size_of(h') == 4;
+}
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::Foo {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Access(StateLen)
+                  Stack(Push(4))
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(StateLen)
+                  Stack(Push(4))
+                  Pred(Eq)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+                state read 0
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(1)))
+                  StateMemory(AllocSlots)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(1)))
+                  KeyRange
+                  Constraint(Stack(Push(1)))
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  StateMemory(Load)
+                  Constraint(Stack(Push(0)))
+                  Constraint(Stack(Push(1)))
+                  StateMemory(ValueLen)
+                  Constraint(Alu(Add))
+                  StateMemory(Store)
+                  Constraint(TotalControlFlow(Halt))
+            }
+
+        "#]],
+    );
+}
+
 #[test]
 fn nested_match() {
     check(
@@ -3698,3 +5001,169 @@ predicate test {
         "#]],
     );
 }
+
+#[test]
+fn json_round_trip() {
+    let compiled_contract = compile(
+        r#"
+storage {
+    x: int,
+}
+
+predicate Foo {
+    var a: int;
+    state x = storage::x;
+    constraint a == x';
+}
+
+predicate Bar {
+    var b: b256;
+    constraint b == 0x0000000000000001000000000000000200000000000000030000000000000004;
+}
+        "#,
+    );
+
+    let json = compiled_contract.to_json_string().unwrap();
+    let reloaded = CompiledContract::from_json_str(&json).unwrap();
+
+    assert_eq!(reloaded.names, compiled_contract.names);
+    assert_eq!(reloaded.salt, compiled_contract.salt);
+    assert_eq!(
+        reloaded.predicates.len(),
+        compiled_contract.predicates.len()
+    );
+    assert_eq!(reloaded.predicates, compiled_contract.predicates);
+    assert_eq!(format!("{reloaded}"), format!("{compiled_contract}"));
+
+    assert!(CompiledContract::from_json_str("{}").is_err());
+}
+
+#[test]
+fn disassemble() {
+    let compiled_contract = compile(
+        r#"
+predicate test {
+    var x: int;
+    constraint x == 1;
+}
+        "#,
+    );
+
+    check(
+        &compiled_contract.disassemble(),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+    assert_eq!(
+        compiled_contract.disassemble(),
+        format!("{compiled_contract}")
+    );
+}
+
+#[test]
+fn explain_asm() {
+    let contract = flatten(
+        r#"
+predicate test {
+    var x: int;
+    constraint x == 4;
+}
+        "#,
+    );
+    let options = CompileOptions {
+        record_asm_notes: true,
+        ..Default::default()
+    };
+    let compiled_contract = compile_contract(&Handler::default(), &contract, &options).unwrap();
+
+    let explained = compiled_contract.explain_asm();
+    let has_op_with_note = |op: &str, note: &str| {
+        explained
+            .lines()
+            .any(|line| line.trim_start().starts_with(op) && line.ends_with(note))
+    };
+    assert!(has_op_with_note(
+        "Stack(Push(0))",
+        "; decision var `::x` slot"
+    ));
+    assert!(has_op_with_note("Access(DecisionVar)", "; read `::x`"));
+    assert!(has_op_with_note("Stack(Push(4))", "; literal `4`"));
+    assert!(has_op_with_note("Pred(Eq)", "; `==`"));
+
+    // Without the notes, the listing is identical to the plain disassembly.
+    let stripped = explained
+        .lines()
+        .map(|line| line.split(" ; ").next().unwrap().trim_end())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        stripped,
+        compiled_contract.disassemble().lines().collect::<Vec<_>>()
+    );
+
+    // Notes aren't recorded by default.
+    let compiled_contract =
+        compile_contract(&Handler::default(), &contract, &CompileOptions::default()).unwrap();
+    assert!(compiled_contract.asm_notes[0].constraints[0].is_empty());
+    assert_eq!(
+        compiled_contract.explain_asm(),
+        compiled_contract.disassemble()
+    );
+}
+
+#[test]
+fn constraint_spans() {
+    let code = r#"
+storage {
+    x: int,
+}
+
+predicate Foo {
+    var a: int;
+    state x = storage::x;
+    constraint a > 0;
+    constraint a == x';
+}
+"#;
+    let compiled_contract = compile(code);
+
+    assert_eq!(
+        compiled_contract.constraint_spans.len(),
+        compiled_contract.predicates.len()
+    );
+
+    // The two source constraints and the generated `__eq_set` mutable keys constraint.
+    let spans = &compiled_contract.constraint_spans[0];
+    assert_eq!(
+        spans.len(),
+        compiled_contract.predicates[0].constraints.len()
+    );
+    assert_eq!(spans.len(), 3);
+    assert_eq!(&code[spans[0].start..spans[0].end], "constraint a > 0");
+    assert_eq!(&code[spans[1].start..spans[1].end], "constraint a == x'");
+    assert_eq!(spans[2].start..spans[2].end, 0..0);
+    assert_eq!(spans[0].file, spans[1].file);
+
+    // The spans survive a JSON round trip.
+    let reloaded =
+        CompiledContract::from_json_str(&compiled_contract.to_json_string().unwrap()).unwrap();
+    assert_eq!(
+        reloaded.constraint_spans,
+        compiled_contract.constraint_spans
+    );
+}
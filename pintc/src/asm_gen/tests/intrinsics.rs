@@ -1,4 +1,5 @@
-use super::{check, compile};
+use super::{check, compile, flatten};
+use crate::{asm_gen::compile_contract, error::Handler, predicate::CompileOptions};
 
 #[test]
 fn this_address() {
@@ -457,6 +458,98 @@ fn sha256() {
     );
 }
 
+#[test]
+fn recover_address() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var hash: b256;
+                var sig: { b256, b256, int };
+                var addr = __recover_address(hash, sig);
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(2))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(9))
+                  Access(DecisionVar)
+                  Crypto(RecoverSecp256k1)
+                  Stack(Push(5))
+                  Stack(Push(8))
+                  Alu(Mul)
+                  Crypto(Sha256)
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn sha256_string() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var some_b256: b256;
+                constraint __sha256("abc") == some_b256;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(7017280021047803904))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Stack(Push(8))
+                  Alu(Mul)
+                  Crypto(Sha256)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
 #[test]
 fn verify_ed25519() {
     check(
@@ -657,3 +750,205 @@ fn recover_secp256k1() {
         "#]],
     );
 }
+
+#[test]
+fn min_max() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                var y: int;
+                constraint __max(x, y) == 5;
+                constraint __min(x, 3) == y;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Pred(Lt)
+                  Stack(Select)
+                  Stack(Push(5))
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(3))
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Pred(Gt)
+                  Stack(Select)
+                  Stack(Push(1))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Pred(Eq)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn abs() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var x: int;
+                constraint __abs(x) == 5;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(0))
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Alu(Sub)
+                  Stack(Push(1))
+                  Stack(DupFrom)
+                  Stack(Push(0))
+                  Pred(Lt)
+                  Stack(Select)
+                  Stack(Push(5))
+                  Pred(Eq)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn word() {
+    check(
+        &format!(
+            "{}",
+            compile(
+                r#"
+            predicate test {
+                var a: b256;
+                constraint __word(a, 2) == 7;
+                constraint __word(0x0000000000000001000000000000000200000000000000030000000000000004, 1) == 2;
+            }
+            "#,
+            )
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(2))
+                  Alu(Add)
+                  Stack(Push(1))
+                  Access(DecisionVar)
+                  Stack(Push(7))
+                  Pred(Eq)
+                constraint 1
+                  Stack(Push(1))
+                  Stack(Push(2))
+                  Stack(Push(1))
+                  Stack(Select)
+                  Stack(Push(2))
+                  Pred(Eq)
+                constraint 2
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn this_contract_salt() {
+    // Each 8 byte chunk of the salt encodes one word, big-endian.
+    let mut salt = [0; 32];
+    for (i, chunk) in salt.chunks_mut(8).enumerate() {
+        chunk[7] = i as u8 + 1;
+    }
+
+    let contract = flatten(
+        r#"
+predicate test {
+    var this_contract_salt = __this_contract_salt();
+}
+        "#,
+    );
+    let options = CompileOptions {
+        salt,
+        ..Default::default()
+    };
+    check(
+        &format!(
+            "{}",
+            compile_contract(&Handler::default(), &contract, &options).unwrap()
+        ),
+        expect_test::expect![[r#"
+            predicate ::test {
+                --- Constraints ---
+                constraint 0
+                  Stack(Push(0))
+                  Stack(Push(0))
+                  Stack(Push(4))
+                  Access(DecisionVar)
+                  Stack(Push(1))
+                  Stack(Push(2))
+                  Stack(Push(3))
+                  Stack(Push(4))
+                  Stack(Push(4))
+                  Pred(EqRange)
+                constraint 1
+                  Access(MutKeys)
+                  Stack(Push(0))
+                  Pred(EqSet)
+                --- State Reads ---
+            }
+
+        "#]],
+    );
+}
@@ -1,4 +1,9 @@
-use super::{check, compile};
+use super::{check, compile, flatten};
+use crate::{
+    asm_gen::compile_contract_with_entry,
+    error::{Handler, ReportableError},
+    predicate::CompileOptions,
+};
 
 #[test]
 fn check_order() {
@@ -79,3 +84,102 @@ fn check_order() {
         "#]],
     );
 }
+
+#[test]
+fn entry_predicate() {
+    let contract = flatten(
+        r#"
+        predicate Foo {
+            var x: int;
+            constraint x == 1;
+        }
+        predicate Bar {
+            var y: int;
+            constraint y == 2;
+        }
+        "#,
+    );
+
+    let compiled = compile_contract_with_entry(
+        &Handler::default(),
+        &contract,
+        &CompileOptions::default(),
+        Some("Foo"),
+    )
+    .unwrap();
+    assert_eq!(compiled.names, vec!["::Foo".to_string()]);
+    assert_eq!(compiled.predicates.len(), 1);
+    assert_eq!(compiled.constraint_spans.len(), 1);
+
+    // The leading `::` is optional.
+    let compiled = compile_contract_with_entry(
+        &Handler::default(),
+        &contract,
+        &CompileOptions::default(),
+        Some("::Bar"),
+    )
+    .unwrap();
+    assert_eq!(compiled.names, vec!["::Bar".to_string()]);
+}
+
+#[test]
+fn entry_predicate_with_dependencies() {
+    // `A` depends on `B` but not `C`, so only `A` and `B` are compiled, in source order.
+    let contract = flatten(
+        r#"
+        predicate A {
+            predicate BI = B();
+        }
+        predicate B {
+        }
+        predicate C {
+        }
+        "#,
+    );
+
+    let compiled = compile_contract_with_entry(
+        &Handler::default(),
+        &contract,
+        &CompileOptions::default(),
+        Some("A"),
+    )
+    .unwrap();
+    assert_eq!(compiled.names, vec!["::A".to_string(), "::B".to_string()]);
+
+    // Compiling `A` on its own must produce the same code as compiling the whole contract.
+    let full = compile_contract_with_entry(
+        &Handler::default(),
+        &contract,
+        &CompileOptions::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        compiled.predicates[0].constraints,
+        full.predicates[0].constraints
+    );
+}
+
+#[test]
+fn unknown_entry_predicate() {
+    let contract = flatten(
+        r#"
+        predicate Foo {
+        }
+        "#,
+    );
+
+    let handler = Handler::default();
+    assert!(compile_contract_with_entry(
+        &handler,
+        &contract,
+        &CompileOptions::default(),
+        Some("Baz")
+    )
+    .is_err());
+    let errors = handler.consume().0;
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .display_raw()
+        .starts_with("cannot find predicate `Baz` in this contract"));
+}
@@ -0,0 +1,91 @@
+use crate::asm_gen::{peephole::remove_redundant_ops, AsmNotes};
+use state_asm::{
+    Access, Alu, Constraint as ConstraintOp, Op as StateOp, Stack, StateMemory,
+    TotalControlFlow::JumpForwardIf,
+};
+
+fn push(val: i64) -> ConstraintOp {
+    Stack::Push(val).into()
+}
+
+fn pop() -> ConstraintOp {
+    Stack::Pop.into()
+}
+
+fn jump() -> ConstraintOp {
+    ConstraintOp::TotalControlFlow(JumpForwardIf)
+}
+
+#[test]
+fn cancelling_pairs() {
+    let mut ops = vec![
+        push(1),
+        push(2),
+        push(3),
+        pop(),
+        Stack::Dup.into(),
+        pop(),
+        pop(),
+        push(4),
+        Alu::Add.into(),
+    ];
+    let mut notes = AsmNotes::from([(2, "three".to_string()), (7, "four".to_string())]);
+    remove_redundant_ops(&mut ops, &mut notes);
+    assert_eq!(ops, vec![push(1), push(4), Alu::Add.into()]);
+    assert_eq!(notes, AsmNotes::from([(1, "four".to_string())]));
+
+    // Nothing to remove.
+    let mut ops = vec![push(1), Stack::Dup.into(), Alu::Add.into(), pop()];
+    remove_redundant_ops(&mut ops, &mut AsmNotes::new());
+    assert_eq!(
+        ops,
+        vec![push(1), Stack::Dup.into(), Alu::Add.into(), pop()]
+    );
+
+    // State programs too.
+    let mut ops = vec![
+        StateOp::Constraint(push(1)),
+        StateOp::Constraint(pop()),
+        StateMemory::AllocSlots.into(),
+    ];
+    remove_redundant_ops(&mut ops, &mut AsmNotes::new());
+    assert_eq!(ops, vec![StateMemory::AllocSlots.into()]);
+}
+
+#[test]
+fn jumps_over_removed_ops() {
+    // The jump lands on `push(7)`, over a pair which is removed, so its distance shrinks.
+    let mut ops = vec![push(4), push(1), jump(), push(5), pop(), push(6), push(7)];
+    remove_redundant_ops(&mut ops, &mut AsmNotes::new());
+    assert_eq!(ops, vec![push(2), push(1), jump(), push(6), push(7)]);
+
+    // A jump to the start of a pair lands after it once it's removed.
+    let mut ops = vec![push(1), push(1), jump(), push(5), pop(), push(6)];
+    remove_redundant_ops(&mut ops, &mut AsmNotes::new());
+    assert_eq!(ops, vec![push(1), push(1), jump(), push(6)]);
+}
+
+#[test]
+fn jump_targets_are_kept() {
+    // The jump lands on the `pop()`, which then doesn't cancel the `push(5)`.
+    let mut ops = vec![push(2), push(1), jump(), push(5), pop(), push(6)];
+    remove_redundant_ops(&mut ops, &mut AsmNotes::new());
+    assert_eq!(ops, vec![push(2), push(1), jump(), push(5), pop(), push(6)]);
+
+    // The distance of this jump isn't known, so nothing after it is removed, though the pair
+    // before it is.
+    let mut ops = vec![
+        push(0),
+        pop(),
+        push(2),
+        Access::ThisPathway.into(),
+        jump(),
+        push(5),
+        pop(),
+    ];
+    remove_redundant_ops(&mut ops, &mut AsmNotes::new());
+    assert_eq!(
+        ops,
+        vec![push(2), Access::ThisPathway.into(), jump(), push(5), pop()]
+    );
+}
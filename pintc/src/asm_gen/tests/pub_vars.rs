@@ -1,4 +1,5 @@
-use super::{check, compile};
+use super::{check, compile, flatten_with_options};
+use crate::{asm_gen::compile_contract, error::Handler, predicate::CompileOptions};
 
 #[test]
 fn local_pub_var() {
@@ -164,6 +165,56 @@ predicate Bar {
     );
 }
 
+#[test]
+fn const_instance_addresses() {
+    // Even without optimizations the addresses should be folded into immediates.
+    let contract = flatten_with_options(
+        r#"
+interface Foo { predicate Bar { pub var x: int; } }
+
+const i_addr: b256 = 0x0000000000000000000000000000000000000000000000000000000000000001;
+const use_v2 = true;
+const v1_addr: b256 = 0x2222222222222222222222222222222222222222222222222222222222222222;
+const v2_addr: b256 = 0x3333333333333333333333333333333333333333333333333333333333333333;
+
+predicate Baz {
+    interface FooInstance = Foo(i_addr);
+    predicate BarInstance = FooInstance::Bar(use_v2 ? v2_addr : v1_addr);
+    var x = BarInstance::x;
+}
+        "#,
+        &CompileOptions {
+            skip_optimize: true,
+            ..Default::default()
+        },
+    );
+    let compiled = compile_contract(&Handler::default(), &contract, &CompileOptions::default())
+        .unwrap()
+        .to_string();
+    assert!(!compiled.contains("Select"), "{compiled}");
+
+    // The address constraint compares the pathway's addresses to the two folded `b256`s.
+    let ops: Vec<_> = compiled.lines().map(str::trim).collect();
+    let pred_at = ops
+        .iter()
+        .position(|op| *op == "Access(PredicateAt)")
+        .unwrap();
+    check(
+        &ops[pred_at + 1..pred_at + 11].join("\n"),
+        expect_test::expect![[r#"
+            Stack(Push(0))
+            Stack(Push(0))
+            Stack(Push(0))
+            Stack(Push(1))
+            Stack(Push(3689348814741910323))
+            Stack(Push(3689348814741910323))
+            Stack(Push(3689348814741910323))
+            Stack(Push(3689348814741910323))
+            Stack(Push(8))
+            Pred(EqRange)"#]],
+    );
+}
+
 #[test]
 fn sibling_predicates() {
     check(
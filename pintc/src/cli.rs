@@ -1,13 +1,43 @@
-use clap::Parser;
+use crate::{
+    parser::DEFAULT_MAX_NESTING_DEPTH,
+    predicate::{DEFAULT_REAL_SCALE_BITS, MAX_REAL_SCALE_BITS},
+};
+use clap::{Parser, ValueEnum};
+
+/// The format in which errors and warnings are reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human-readable reports with source snippets.
+    #[default]
+    Human,
+    /// One JSON object per line with `file`, `start`, `end`, `severity`, `message` and `code`.
+    Json,
+}
 
 #[derive(Debug, Default, Parser)]
 pub struct Args {
+    /// The root source file of the contract, or `-` to read the source from stdin.
     #[arg(value_parser)]
     pub filepath: String,
 
+    /// The path of the compiled contract. Defaults to the input path with a `.json` extension.
+    /// When reading from stdin nothing is written unless this is given.
     #[arg(long = "output", short = 'o')]
     pub output: Option<String>,
 
+    /// Only type check the input, reporting any errors or warnings, without flattening it or
+    /// producing any output files.
+    #[arg(long = "check")]
+    pub check: bool,
+
+    /// Enable a feature for `@cfg(feature = "..")` attributes. May be given multiple times.
+    #[arg(long = "cfg", value_name = "FEATURE")]
+    pub cfg: Vec<String>,
+
+    /// The maximum depth of nested expressions, e.g., operators, field accesses and array elements.
+    #[arg(long = "max-nesting-depth", default_value_t = DEFAULT_MAX_NESTING_DEPTH)]
+    pub max_nesting_depth: usize,
+
     #[arg(long = "print-parsed")]
     pub print_parsed: bool,
 
@@ -17,9 +47,74 @@ pub struct Args {
     #[arg(long = "print-optimized")]
     pub print_optimized: bool,
 
+    /// Print the compiled predicates' IR, after flattening and optimization, to stdout as JSON.
+    #[arg(long = "dump-ir")]
+    pub dump_ir: bool,
+
     #[arg(long = "print-asm")]
     pub print_asm: bool,
 
+    /// Print the assembly like `--print-asm`, but with each op followed by a note describing the
+    /// source construct that produced it, where known.
+    #[arg(long = "explain-asm")]
+    pub explain_asm: bool,
+
+    /// Write a human-readable assembly listing next to the compiled contract, using an `.asm`
+    /// extension.
+    #[arg(long = "asm-text")]
+    pub asm_text: bool,
+
+    /// Print the contract's JSON ABI to stdout.
+    #[arg(long = "abi")]
+    pub print_abi: bool,
+
+    /// Print how long each compilation phase (parse, type check, flatten, optimize and asm gen)
+    /// takes to stderr.
+    #[arg(long = "time")]
+    pub time: bool,
+
+    /// Disable colored output. Colors are also disabled when the `NO_COLOR` environment variable
+    /// is set to a non-empty value.
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+
+    /// The format of errors and warnings printed to stderr.
+    #[arg(long = "error-format", value_enum, default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
+
+    /// Treat warnings as errors, failing the compilation if any are reported.
+    #[arg(long = "deny-warnings")]
+    pub deny_warnings: bool,
+
+    /// The maximum number of errors to print. Any further errors are summarised in a single line,
+    /// except with `--error-format json` where they are left out.
+    #[arg(long = "max-errors", value_name = "N")]
+    pub max_errors: Option<usize>,
+
+    /// The number of fractional bits used to encode `real` values as fixed-point integers. More
+    /// bits give more precision but less range.
+    #[arg(
+        long = "real-scale-bits",
+        default_value_t = DEFAULT_REAL_SCALE_BITS,
+        value_parser = clap::value_parser!(u32).range(0..=MAX_REAL_SCALE_BITS as i64)
+    )]
+    pub real_scale_bits: u32,
+
+    /// Inline initialised variables which are used only once. This removes decision variables
+    /// from the compiled predicates.
+    #[arg(long = "inline-vars")]
+    pub inline_vars: bool,
+
+    /// The salt of the contract as 32 bytes of hex, optionally prefixed with `0x`. The salt
+    /// contributes to the contract's address. Defaults to all zeroes.
+    #[arg(long = "salt", value_name = "HEX", value_parser = pint_manifest::parse_salt)]
+    pub salt: Option<[u8; 32]>,
+
+    /// Compile only the named predicate and the predicates whose addresses it depends on. The
+    /// leading `::` of the name is optional.
+    #[arg(long = "entry", value_name = "PREDICATE")]
+    pub entry: Option<String>,
+
     #[arg(long = "skip-optimize", hide = true)]
     pub skip_optimize: bool,
 }
@@ -4,7 +4,7 @@ mod lex_error;
 mod parse_error;
 
 use crate::span::{Span, Spanned};
-use ariadne::{FnCache, Label, Report, ReportKind, Source};
+use ariadne::{Config, FnCache, Label, Report, ReportKind, Source};
 use std::fmt::{Display, Formatter, Result, Write};
 use thiserror::Error;
 use yansi::{Color, Paint, Style};
@@ -68,7 +68,8 @@ where
     /// A helpful "note" about the error
     fn note(&self) -> Option<String>;
 
-    /// A unique error code
+    /// A unique error code.  These are reported by `--error-format json` and must not change once
+    /// published.
     fn code(&self) -> Option<String>;
 
     /// Additional information to help the user address the diagnostic
@@ -89,6 +90,7 @@ where
         let error_file: &str = &format!("{}", self.span().context().display());
         let mut report_builder = Report::build(ReportKind::Error, error_file, self.span().start())
             .with_message(format!("{}", self.bold()))
+            .with_config(Config::default().with_color(yansi::is_enabled()))
             .with_labels(
                 self.labels()
                     .iter()
@@ -199,9 +201,11 @@ impl ReportableError for Error {
     fn code(&self) -> Option<String> {
         use Error::*;
         match self {
-            Lex { .. } => None,
-            Parse { error } => error.code().map(|code| format!("P{code}")),
-            Compile { error } => error.code().map(|code| format!("C{code}")),
+            Lex { error, .. } => match error {
+                LexError::InvalidToken => Some("invalid_token".to_string()),
+            },
+            Parse { error } => error.code(),
+            Compile { error } => error.code(),
             MacroBodyWrapper { child, .. } => child.code(),
         }
     }
@@ -217,6 +221,337 @@ impl ReportableError for Error {
     }
 }
 
+/// Longer-form explanations for error codes, keyed by the codes returned from
+/// [`ReportableError::code`].  Each explanation describes when the error occurs and shows a
+/// minimal example which triggers it.
+static EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "symbol_not_found",
+        r#"A name was used which does not refer to any declaration in scope.
+
+Every variable, constant, type, union and macro must be declared before it can be referenced,
+either in the current module or via a `use` statement.
+
+Example:
+
+    predicate test {
+        var x: int;
+        constraint x == y; // `y` was never declared
+    }
+"#,
+    ),
+    (
+        "storage_symbol_not_found",
+        r#"A storage access refers to a variable which is not declared in the `storage` block.
+
+Example:
+
+    storage {
+        x: int,
+    }
+
+    predicate test {
+        state y = storage::y; // `y` is not a storage variable
+    }
+"#,
+    ),
+    (
+        "missing_storage_block",
+        r#"A storage access was made in a contract which has no `storage` block.
+
+Storage variables must be declared in a single `storage { .. }` block at the top level of the
+contract before they can be accessed from a predicate.
+
+Example:
+
+    predicate test {
+        state x = storage::x; // there is no `storage` block
+    }
+"#,
+    ),
+    (
+        "invalid_next_state_access",
+        r#"The next state operator `'` was applied to something other than a state variable.
+
+Only state variables have a next state value. Decision variables, constants and other expressions
+have a single value.
+
+Example:
+
+    predicate test {
+        var x: int;
+        constraint x' == 1; // `x` is not a state variable
+    }
+"#,
+    ),
+    (
+        "non_const_array_length",
+        r#"An array type was declared with a length which is not a compile time constant.
+
+Array lengths must be known at compile time, so they may only use literals, constants and
+arithmetic over those.
+
+Example:
+
+    predicate test {
+        var n: int;
+        var a: int[n]; // `n` is a decision variable
+    }
+"#,
+    ),
+    (
+        "array_index_out_of_bounds",
+        r#"An array was indexed with a constant which is outside of its bounds.
+
+Array indices start at 0 and must be strictly less than the length of the array.
+
+Example:
+
+    predicate test {
+        var a: int[3];
+        constraint a[3] == 0; // valid indices are 0, 1 and 2
+    }
+"#,
+    ),
+    (
+        "var_has_storage_type",
+        r#"A decision variable was declared with a type which is only valid in storage.
+
+Storage maps and storage vectors may only be used as the types of storage variables.
+
+Example:
+
+    predicate test {
+        var m: (int => int); // maps are storage-only types
+    }
+"#,
+    ),
+    (
+        "type_not_allowed_in_storage",
+        r#"A storage variable was declared with a type which cannot be stored.
+
+Storage maps must have keys of type `bool`, `int` or `b256`, or tuples of those.
+
+Example:
+
+    storage {
+        m: (int[2] => int), // arrays are not allowed as map keys
+    }
+"#,
+    ),
+    (
+        "compare_to_nil_error",
+        r#"`nil` was used with an operator other than `==` or `!=`, or compared against something
+which is not a state variable.
+
+`nil` represents a state variable which has no value, so it may only be tested for (in)equality.
+
+Example:
+
+    storage {
+        x: int,
+    }
+
+    predicate test {
+        state x = storage::x;
+        constraint x > nil; // only `==` and `!=` are allowed with `nil`
+    }
+"#,
+    ),
+    (
+        "integer_overflow",
+        r#"A constant integer expression overflowed while being evaluated at compile time.
+
+Integers are 64 bit signed values and arithmetic on them must stay within that range.
+
+Example:
+
+    const big = 0x7FFFFFFFFFFFFFFF + 1;
+"#,
+    ),
+    (
+        "unsatisfiable_constraint",
+        r#"A constraint was found to always be `false` during optimization, so no solution could ever
+satisfy the predicate.
+
+Example:
+
+    predicate test {
+        constraint 1 == 2;
+    }
+"#,
+    ),
+    (
+        "dependency_cycle",
+        r#"Two or more predicates depend on each other's addresses, forming a cycle.
+
+A predicate's address is derived from its compiled bytecode, so a predicate cannot reference
+another predicate which, directly or indirectly, references it back.
+
+Example:
+
+    predicate A {
+        predicate BI = B();
+    }
+
+    predicate B {
+        predicate AI = A();
+    }
+"#,
+    ),
+];
+
+/// Error and warning codes, as returned by [`ReportableError::code`], which don't yet have an
+/// entry in [`EXPLANATIONS`].  Every code must appear in exactly one of the two lists.
+static UNEXPLAINED: &[&str] = &[
+    "internal",
+    "file_io",
+    "dual_modulity",
+    "no_file_found_for_path",
+    "cyclic_import",
+    "macro_decl_clash",
+    "macro_not_found",
+    "macro_call_mismatch",
+    "macro_multiple_packs",
+    "macro_unknown_pack",
+    "macro_non_unique_param_counts",
+    "macro_undefined_param",
+    "macro_recursion",
+    "macro_unrecognized_splice_var",
+    "macro_splice_var_not_array",
+    "macro_splice_array_unknown_size",
+    "macro_call_was_not_expression",
+    "duplicate_generator_index",
+    "invalid_generator_index_bound",
+    "non_int_generator_range",
+    "non_bool_generator_condition",
+    "non_bool_generator_body",
+    "missing_interface",
+    "missing_predicate",
+    "unknown_predicate",
+    "real_scale_bits_out_of_range",
+    "self_referencial_predicate",
+    "missing_interface_instance",
+    "missing_predicate_instance",
+    "address_expression_type_error",
+    "invalid_const_array_length",
+    "non_const_array_index",
+    "invalid_const_array_index",
+    "cannot_index_into_value",
+    "unknown_type",
+    "undefined_type",
+    "non_bool_conditional",
+    "mismatched_if_branch_types",
+    "constraint_expression_type_error",
+    "index_expr_non_indexable",
+    "array_access_with_wrong_type",
+    "invalid_array_range_type",
+    "storage_map_access_with_wrong_type",
+    "mismatched_array_comparison_sizes",
+    "mismatched_array_comparison_element_types",
+    "tuple_access_non_tuple",
+    "invalid_tuple_accessor",
+    "empty_array_expression",
+    "range_array_too_long",
+    "non_homogeneous_array_element",
+    "operator_type_error",
+    "operator_invalid_type",
+    "init_type_error",
+    "state_var_init_type_error",
+    "expr_recursion",
+    "bad_cast_to",
+    "bad_cast_from",
+    "range_types_mismatch",
+    "range_types_non_numeric",
+    "in_expr_types_mismatch",
+    "in_expr_types_array_mismatch",
+    "unexpected_intrinsic_arg_count",
+    "mismatched_intrinsic_arg_type",
+    "intrinsic_arg_must_be_state_var",
+    "intrinsic_arg_must_be_storage_access",
+    "recursive_new_type",
+    "in_range_invalid",
+    "address_of_self",
+    "predicate_name_not_found",
+    "match_expr_not_union",
+    "match_variant_unknown",
+    "match_branch_type_mismatch",
+    "match_branch_reused",
+    "match_branch_missing",
+    "unknown_union",
+    "unknown_union_variant",
+    "superfluous_union_expr_value",
+    "missing_union_expr_value",
+    "union_variant_type_mismatch",
+    "invalid_storage_access",
+    "non_const_exponent",
+    "negative_exponent",
+    "shift_out_of_range",
+    "division_by_zero",
+    "unknown_tuple_field",
+    "invalid_word_index",
+    "nil_not_supported_here",
+    "unsupported_cast",
+    "invalid_token",
+    "lex",
+    "unterminated_block_comment",
+    "invalid_escape",
+    "expression_too_deep",
+    "expected_found",
+    "keyword_as_ident",
+    "untyped_variable",
+    "empty_array_expr",
+    "empty_index_access",
+    "invalid_integer_tuple_index",
+    "invalid_tuple_index",
+    "empty_tuple_expr",
+    "empty_tuple_type",
+    "name_clash",
+    "unsupported_leading_plus",
+    "unexpected_leading_comma",
+    "self_with_empty_prefix",
+    "self_not_at_the_end",
+    "binary_literal_length",
+    "hex_literal_length",
+    "int_literal_too_large",
+    "too_many_storage_blocks",
+    "storage_directive_must_be_top_level",
+    "storage_access_must_be_top_level",
+    "path_too_short",
+    "bad_splice",
+    "bad_storage_intrinsic",
+    "missing_intrinsic",
+    "type_not_supported",
+    "literal_not_supported",
+    "invalid_cfg_condition",
+    "match_wildcard_binding",
+    "unreachable_match_branch",
+    "match_unneeded_else",
+    "always_true_constraint",
+    "unused_var",
+    "chained_comparison",
+    "unreferenced_pub_var",
+];
+
+/// The result of looking up an error code with [`explain`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Explanation {
+    /// A longer-form explanation of the code.
+    Found(&'static str),
+
+    /// The code is valid but has no longer-form explanation yet.
+    Missing,
+}
+
+/// Returns a longer-form explanation of the error with the given `code`, as returned by
+/// [`ReportableError::code`], or `None` if `code` is not a known error code.
+pub fn explain(code: &str) -> Option<Explanation> {
+    EXPLANATIONS
+        .iter()
+        .find_map(|(name, explanation)| (*name == code).then_some(Explanation::Found(*explanation)))
+        .or_else(|| UNEXPLAINED.contains(&code).then_some(Explanation::Missing))
+}
+
 impl Spanned for Error {
     fn span(&self) -> &Span {
         use Error::*;
@@ -229,11 +564,47 @@ impl Spanned for Error {
     }
 }
 
-/// Print a list of [`Error`] using the `ariadne` crate
-pub fn print_errors(errs: &Errors) {
-    for err in &errs.0 {
+/// Print a list of [`Error`] using the `ariadne` crate.  If `max_errors` is provided then at most
+/// that many errors are printed, followed by a summary of how many were left out.
+pub fn print_errors(errs: &Errors, max_errors: Option<usize>) {
+    for err in errs.0.iter().take(max_errors.unwrap_or(usize::MAX)) {
         err.print();
     }
+    print_omitted_errors_summary(errs, max_errors);
+}
+
+/// Print a list of [`Error`] to stderr as JSON lines, one object per error, for consumption by
+/// editors and other tools.  If `max_errors` is provided then at most that many errors are
+/// printed.  No summary of the omitted errors is printed so that every line of output is valid
+/// JSON.
+pub fn print_errors_json(errs: &Errors, max_errors: Option<usize>) {
+    for err in errs.0.iter().take(max_errors.unwrap_or(usize::MAX)) {
+        let span = err.span();
+        eprintln!(
+            "{}",
+            serde_json::json!({
+                "file": format!("{}", span.context().display()),
+                "start": span.start(),
+                "end": span.end(),
+                "severity": "error",
+                "message": err.to_string(),
+                "code": err.code(),
+            })
+        );
+    }
+}
+
+/// Print a summary line for the errors which were left out due to `max_errors`, if any.
+fn print_omitted_errors_summary(errs: &Errors, max_errors: Option<usize>) {
+    let omitted = errs
+        .0
+        .len()
+        .saturating_sub(max_errors.unwrap_or(usize::MAX));
+    if omitted == 1 {
+        eprintln!("... and 1 more error");
+    } else if omitted > 1 {
+        eprintln!("... and {omitted} more errors");
+    }
 }
 
 /// A simple wrapper around `anyhow::bail!` that prints a different message based on a the number
@@ -255,3 +626,63 @@ macro_rules! pintc_bail {
         }
     };
 }
+
+/// Implements `code_str()` for a diagnostic enum, mapping each variant to its code, and collects
+/// every code into an `ALL_CODES` const so that they can be checked against [`EXPLANATIONS`].
+macro_rules! diagnostic_codes {
+    ($ty: ident { $($variant: ident => $code: literal,)* }) => {
+        impl $ty {
+            /// Every code returned by `code()`.
+            #[cfg(test)]
+            pub(crate) const ALL_CODES: &'static [&'static str] = &[$($code),*];
+
+            fn code_str(&self) -> &'static str {
+                match self {
+                    $($ty::$variant { .. } => $code,)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use diagnostic_codes;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_code_is_explained_or_unexplained() {
+        let codes = CompileError::ALL_CODES
+            .iter()
+            .chain(ParseError::ALL_CODES)
+            .chain(crate::warning::Warning::ALL_CODES)
+            .copied()
+            .collect::<Vec<_>>();
+        assert!(codes.len() > 100);
+
+        for code in &codes {
+            let explained = EXPLANATIONS.iter().any(|(name, _)| name == code);
+            let unexplained = UNEXPLAINED.contains(code);
+            assert!(
+                explained != unexplained,
+                "`{code}` must be in exactly one of `EXPLANATIONS` or `UNEXPLAINED`"
+            );
+        }
+
+        // And neither list may contain a stale code.
+        for code in EXPLANATIONS.iter().map(|(name, _)| name).chain(UNEXPLAINED) {
+            assert!(codes.contains(code), "`{code}` is not a known error code");
+        }
+    }
+
+    #[test]
+    fn explain_codes() {
+        assert!(matches!(
+            explain("symbol_not_found"),
+            Some(Explanation::Found(text)) if !text.trim().is_empty()
+        ));
+        assert_eq!(explain("internal"), Some(Explanation::Missing));
+        assert_eq!(explain("not_a_real_code"), None);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    error::{ErrorLabel, ReportableError},
+    error::{diagnostic_codes, ErrorLabel, ReportableError},
+    predicate::MAX_REAL_SCALE_BITS,
     span::{empty_span, Span, Spanned},
 };
 use std::path::PathBuf;
@@ -30,6 +31,8 @@ pub enum CompileError {
         path_union: String,
         span: Span,
     },
+    #[error("cyclic module import detected")]
+    CyclicImport { cycle: Vec<String>, span: Span },
     #[error("macro {name} is declared multiple times")]
     MacroDeclClash {
         name: String,
@@ -115,7 +118,7 @@ pub enum CompileError {
     },
     #[error("cannot find storage variable `{name}`")]
     StorageSymbolNotFound { name: String, span: Span },
-    #[error("cannot find storage variable `{name}`")]
+    #[error("storage access without a declared storage block")]
     MissingStorageBlock { name: String, span: Span },
     #[error("`next state` access must be bound to a state variable")]
     InvalidNextStateAccess { span: Span },
@@ -133,6 +136,14 @@ pub enum CompileError {
         interface_name: Option<String>,
         span: Span,
     },
+    #[error("cannot find predicate `{name}` in this contract")]
+    UnknownPredicate {
+        name: String,
+        available: Vec<String>,
+        span: Span,
+    },
+    #[error("`real` scale of {bits} bits is out of range")]
+    RealScaleBitsOutOfRange { bits: u32, span: Span },
     #[error("self referential predicate `{pred_name}`")]
     SelfReferencialPredicate { pred_name: String, span: Span },
     #[error("cannot find interface instance `{name}`")]
@@ -164,7 +175,13 @@ pub enum CompileError {
         span: Span,
     },
     #[error("branches of a select expression must have the same type")]
-    SelectBranchesTypeMismatch { large_err: Box<LargeTypeError> },
+    MismatchedIfBranchTypes {
+        then_ty: String,
+        then_span: Span,
+        else_ty: String,
+        else_span: Span,
+        span: Span,
+    },
     #[error("constraint expression type error")]
     ConstraintExpressionTypeError { large_err: Box<LargeTypeError> },
     #[error("indexed expression invalid")]
@@ -201,6 +218,13 @@ pub enum CompileError {
         rhs_size: i64,
         span: Span,
     },
+    #[error("comparison between arrays of different element types")]
+    MismatchedArrayComparisonElementTypes {
+        op: String,
+        lhs_el_ty: String,
+        rhs_el_ty: String,
+        span: Span,
+    },
     #[error("attempt to access tuple field from a non-tuple value")]
     TupleAccessNonTuple { non_tuple_type: String, span: Span },
     #[error("invalid tuple accessor")]
@@ -211,6 +235,8 @@ pub enum CompileError {
     },
     #[error("illegal empty array value")]
     EmptyArrayExpression { span: Span },
+    #[error("range array is too long")]
+    RangeArrayTooLong { max_len: i64, span: Span },
     #[error("array element type mismatch")]
     NonHomogeneousArrayElement {
         expected_ty: String,
@@ -356,6 +382,30 @@ pub enum CompileError {
     },
     #[error("invalid position for accessing storage")]
     InvalidStorageAccess { span: Span },
+    #[error("attempt to use a non-constant value as an exponent")]
+    NonConstExponent { span: Span },
+    #[error("attempt to raise a value to a negative power")]
+    NegativeExponent { span: Span },
+    #[error("integer overflow")]
+    IntegerOverflow { span: Span },
+    #[error("shift amount out of range")]
+    ShiftOutOfRange { span: Span },
+    #[error("attempt to divide by zero")]
+    DivisionByZero { span: Span },
+    #[error("constraint is always `false`")]
+    UnsatisfiableConstraint { span: Span },
+    #[error("unknown tuple field `{field}`")]
+    UnknownTupleField { field: String, span: Span },
+    #[error("invalid `b256` word index")]
+    InvalidWordIndex { span: Span },
+    #[error("`nil` is not supported here")]
+    NilNotSupportedHere { span: Span },
+    #[error("unsupported cast")]
+    UnsupportedCast {
+        from_ty: String,
+        to_ty: String,
+        span: Span,
+    },
 }
 
 // This is here purely at the suggestion of Clippy, who pointed out that these error variants are
@@ -366,13 +416,6 @@ pub enum CompileError {
 
 #[derive(Debug)]
 pub enum LargeTypeError {
-    SelectBranchesTypeMismatch {
-        then_type: String,
-        then_span: Span,
-        else_type: String,
-        else_span: Span,
-        span: Span,
-    },
     OperatorTypeError {
         op: &'static str,
         expected_ty: String,
@@ -407,6 +450,111 @@ pub enum LargeTypeError {
     },
 }
 
+diagnostic_codes! {
+    CompileError {
+        Internal => "internal",
+        FileIO => "file_io",
+        DualModulity => "dual_modulity",
+        NoFileFoundForPath => "no_file_found_for_path",
+        CyclicImport => "cyclic_import",
+        MacroDeclClash => "macro_decl_clash",
+        MacroNotFound => "macro_not_found",
+        MacroCallMismatch => "macro_call_mismatch",
+        MacroMultiplePacks => "macro_multiple_packs",
+        MacroUnknownPack => "macro_unknown_pack",
+        MacroNonUniqueParamCounts => "macro_non_unique_param_counts",
+        MacroUndefinedParam => "macro_undefined_param",
+        MacroRecursion => "macro_recursion",
+        MacroUnrecognizedSpliceVar => "macro_unrecognized_splice_var",
+        MacroSpliceVarNotArray => "macro_splice_var_not_array",
+        MacroSpliceArrayUnknownSize => "macro_splice_array_unknown_size",
+        MacroCallWasNotExpression => "macro_call_was_not_expression",
+        DuplicateGeneratorIndex => "duplicate_generator_index",
+        InvalidGeneratorIndexBound => "invalid_generator_index_bound",
+        NonIntGeneratorRange => "non_int_generator_range",
+        NonBoolGeneratorCondition => "non_bool_generator_condition",
+        NonBoolGeneratorBody => "non_bool_generator_body",
+        SymbolNotFound => "symbol_not_found",
+        StorageSymbolNotFound => "storage_symbol_not_found",
+        MissingStorageBlock => "missing_storage_block",
+        InvalidNextStateAccess => "invalid_next_state_access",
+        MissingInterface => "missing_interface",
+        MissingPredicate => "missing_predicate",
+        UnknownPredicate => "unknown_predicate",
+        RealScaleBitsOutOfRange => "real_scale_bits_out_of_range",
+        SelfReferencialPredicate => "self_referencial_predicate",
+        MissingInterfaceInstance => "missing_interface_instance",
+        MissingPredicateInstance => "missing_predicate_instance",
+        AddressExpressionTypeError => "address_expression_type_error",
+        NonConstArrayLength => "non_const_array_length",
+        InvalidConstArrayLength => "invalid_const_array_length",
+        NonConstArrayIndex => "non_const_array_index",
+        InvalidConstArrayIndex => "invalid_const_array_index",
+        ArrayIndexOutOfBounds => "array_index_out_of_bounds",
+        CannotIndexIntoValue => "cannot_index_into_value",
+        UnknownType => "unknown_type",
+        UndefinedType => "undefined_type",
+        NonBoolConditional => "non_bool_conditional",
+        MismatchedIfBranchTypes => "mismatched_if_branch_types",
+        ConstraintExpressionTypeError => "constraint_expression_type_error",
+        IndexExprNonIndexable => "index_expr_non_indexable",
+        ArrayAccessWithWrongType => "array_access_with_wrong_type",
+        InvalidArrayRangeType => "invalid_array_range_type",
+        VarHasStorageType => "var_has_storage_type",
+        TypeNotAllowedInStorage => "type_not_allowed_in_storage",
+        StorageMapAccessWithWrongType => "storage_map_access_with_wrong_type",
+        MismatchedArrayComparisonSizes => "mismatched_array_comparison_sizes",
+        MismatchedArrayComparisonElementTypes => "mismatched_array_comparison_element_types",
+        TupleAccessNonTuple => "tuple_access_non_tuple",
+        InvalidTupleAccessor => "invalid_tuple_accessor",
+        EmptyArrayExpression => "empty_array_expression",
+        RangeArrayTooLong => "range_array_too_long",
+        NonHomogeneousArrayElement => "non_homogeneous_array_element",
+        OperatorTypeError => "operator_type_error",
+        OperatorInvalidType => "operator_invalid_type",
+        InitTypeError => "init_type_error",
+        StateVarInitTypeError => "state_var_init_type_error",
+        ExprRecursion => "expr_recursion",
+        BadCastTo => "bad_cast_to",
+        BadCastFrom => "bad_cast_from",
+        RangeTypesMismatch => "range_types_mismatch",
+        RangeTypesNonNumeric => "range_types_non_numeric",
+        InExprTypesMismatch => "in_expr_types_mismatch",
+        InExprTypesArrayMismatch => "in_expr_types_array_mismatch",
+        UnexpectedIntrinsicArgCount => "unexpected_intrinsic_arg_count",
+        MismatchedIntrinsicArgType => "mismatched_intrinsic_arg_type",
+        IntrinsicArgMustBeStateVar => "intrinsic_arg_must_be_state_var",
+        IntrinsicArgMustBeStorageAccess => "intrinsic_arg_must_be_storage_access",
+        CompareToNilError => "compare_to_nil_error",
+        RecursiveNewType => "recursive_new_type",
+        InRangeInvalid => "in_range_invalid",
+        DependencyCycle => "dependency_cycle",
+        AddressOfSelf => "address_of_self",
+        PredicateNameNotFound => "predicate_name_not_found",
+        MatchExprNotUnion => "match_expr_not_union",
+        MatchVariantUnknown => "match_variant_unknown",
+        MatchBranchTypeMismatch => "match_branch_type_mismatch",
+        MatchBranchReused => "match_branch_reused",
+        MatchBranchMissing => "match_branch_missing",
+        UnknownUnion => "unknown_union",
+        UnknownUnionVariant => "unknown_union_variant",
+        SuperfluousUnionExprValue => "superfluous_union_expr_value",
+        MissingUnionExprValue => "missing_union_expr_value",
+        UnionVariantTypeMismatch => "union_variant_type_mismatch",
+        InvalidStorageAccess => "invalid_storage_access",
+        NonConstExponent => "non_const_exponent",
+        NegativeExponent => "negative_exponent",
+        IntegerOverflow => "integer_overflow",
+        ShiftOutOfRange => "shift_out_of_range",
+        DivisionByZero => "division_by_zero",
+        UnsatisfiableConstraint => "unsatisfiable_constraint",
+        UnknownTupleField => "unknown_tuple_field",
+        InvalidWordIndex => "invalid_word_index",
+        NilNotSupportedHere => "nil_not_supported_here",
+        UnsupportedCast => "unsupported_cast",
+    }
+}
+
 impl ReportableError for CompileError {
     fn labels(&self) -> Vec<ErrorLabel> {
         use CompileError::*;
@@ -429,6 +577,14 @@ impl ReportableError for CompileError {
                 }]
             }
 
+            CyclicImport { cycle, span } => {
+                vec![ErrorLabel {
+                    message: format!("this import completes the cycle {}", cycle.join(" -> ")),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
             MacroDeclClash {
                 name,
                 span,
@@ -631,6 +787,25 @@ impl ReportableError for CompileError {
                 }]
             }
 
+            MismatchedIfBranchTypes {
+                then_ty,
+                then_span,
+                else_ty,
+                else_span,
+                ..
+            } => vec![
+                ErrorLabel {
+                    message: format!("'then' branch has the type `{then_ty}`"),
+                    span: then_span.clone(),
+                    color: Color::Red,
+                },
+                ErrorLabel {
+                    message: format!("'else' branch has the type `{else_ty}`"),
+                    span: else_span.clone(),
+                    color: Color::Red,
+                },
+            ],
+
             SymbolNotFound { span, .. } => {
                 vec![ErrorLabel {
                     message: "not found in this scope".to_string(),
@@ -740,6 +915,91 @@ impl ReportableError for CompileError {
                 }]
             }
 
+            UnknownTupleField { field, span } => {
+                vec![ErrorLabel {
+                    message: format!("tuple has no field named `{field}`"),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            InvalidWordIndex { span } => {
+                vec![ErrorLabel {
+                    message: "this must be an integer constant from 0 to 3".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            NilNotSupportedHere { span } => {
+                vec![ErrorLabel {
+                    message: "`nil` cannot be encoded as a value".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            UnsupportedCast {
+                from_ty,
+                to_ty,
+                span,
+            } => {
+                vec![ErrorLabel {
+                    message: format!("casts from `{from_ty}` to `{to_ty}` are not yet supported"),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            NonConstExponent { span } => {
+                vec![ErrorLabel {
+                    message: "this must be a non-negative integer constant".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            NegativeExponent { span } => {
+                vec![ErrorLabel {
+                    message: "this exponent is negative".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            IntegerOverflow { span } => {
+                vec![ErrorLabel {
+                    message: "this expression evaluates to a value that does not fit in an `int`"
+                        .to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            ShiftOutOfRange { span } => {
+                vec![ErrorLabel {
+                    message: "this shift amount is negative or too large".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            DivisionByZero { span } => {
+                vec![ErrorLabel {
+                    message: "this expression divides by zero".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
+            UnsatisfiableConstraint { span } => {
+                vec![ErrorLabel {
+                    message: "this constraint always evaluates to `false`".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+
             ArrayIndexOutOfBounds { span } => {
                 vec![ErrorLabel {
                     message: "array index is out of bounds".to_string(),
@@ -843,6 +1103,12 @@ impl ReportableError for CompileError {
                 color: Color::Red,
             }],
 
+            MismatchedArrayComparisonElementTypes { span, .. } => vec![ErrorLabel {
+                message: "cannot compare arrays of different element types".to_string(),
+                span: span.clone(),
+                color: Color::Red,
+            }],
+
             TupleAccessNonTuple {
                 non_tuple_type,
                 span,
@@ -866,6 +1132,12 @@ impl ReportableError for CompileError {
                 color: Color::Red,
             }],
 
+            RangeArrayTooLong { max_len, span } => vec![ErrorLabel {
+                message: format!("this range has more than {max_len} elements"),
+                span: span.clone(),
+                color: Color::Red,
+            }],
+
             NonHomogeneousArrayElement { ty, span, .. } => {
                 vec![ErrorLabel {
                     message: format!("array element has type `{ty}`"),
@@ -890,31 +1162,11 @@ impl ReportableError for CompileError {
                 },
             ],
 
-            SelectBranchesTypeMismatch { large_err }
-            | OperatorTypeError { large_err, .. }
+            OperatorTypeError { large_err, .. }
             | StateVarInitTypeError { large_err, .. }
             | ConstraintExpressionTypeError { large_err, .. }
             | AddressExpressionTypeError { large_err, .. }
             | InitTypeError { large_err, .. } => match large_err.as_ref() {
-                LargeTypeError::SelectBranchesTypeMismatch {
-                    then_type,
-                    then_span,
-                    else_type,
-                    else_span,
-                    ..
-                } => vec![
-                    ErrorLabel {
-                        message: format!("'then' branch has the type `{then_type}`"),
-                        span: then_span.clone(),
-                        color: Color::Red,
-                    },
-                    ErrorLabel {
-                        message: format!("'else' branch has the type `{else_type}`"),
-                        span: else_span.clone(),
-                        color: Color::Red,
-                    },
-                ],
-
                 LargeTypeError::OperatorTypeError {
                     op,
                     found_ty,
@@ -1111,7 +1363,7 @@ impl ReportableError for CompileError {
 
             InRangeInvalid { found_ty, span } => vec![ErrorLabel {
                 message: format!(
-                    "`in` operator range must be either an enumeration union or an array, \
+                    "`in` operator range must be either a numeric range or an array, \
                     found `{found_ty}`"
                 ),
                 span: span.clone(),
@@ -1238,7 +1490,7 @@ impl ReportableError for CompileError {
                 }
             }
 
-            FileIO { .. } => Vec::new(),
+            FileIO { .. } | UnknownPredicate { .. } | RealScaleBitsOutOfRange { .. } => Vec::new(),
         }
     }
 
@@ -1263,6 +1515,33 @@ impl ReportableError for CompileError {
                 "one of the modules `{path_mod}` or `{path_union}` must exist",
             )),
 
+            CyclicImport { .. } => Some(
+                "modules must not import each other, either directly or transitively".to_string(),
+            ),
+
+            IntegerOverflow { .. } => Some(format!(
+                "`int` values must be in the range {} to {}",
+                i64::MIN,
+                i64::MAX
+            )),
+
+            ShiftOutOfRange { .. } => {
+                Some("the shift amount must be in the range 0 to 63".to_string())
+            }
+
+            UnsatisfiableConstraint { .. } => Some(
+                "a predicate containing an unsatisfiable constraint can never be solved"
+                    .to_string(),
+            ),
+
+            InvalidWordIndex { .. } => {
+                Some("a `b256` is made up of four 64-bit words, indexed from 0 to 3".to_string())
+            }
+
+            NilNotSupportedHere { .. } => Some(
+                "`nil` may only be compared to a `state` variable using `==` or `!=`".to_string(),
+            ),
+
             MacroDeclClash { name, .. } => Some(format!(
                 "it is valid to have multiple macros named `{name}` \
                 but they must have differing parameter lists"
@@ -1299,8 +1578,12 @@ impl ReportableError for CompileError {
                 "`{gen_kind}` index `{name}` must be declared only once in this scope"
             )),
 
+            MissingStorageBlock { name, .. } => Some(format!(
+                "storage variable `{name}` must be declared in a `storage {{ .. }}` block"
+            )),
+
             InvalidGeneratorIndexBound { gen_kind, .. } => Some(format!(
-                "`{gen_kind}` index bound must be an integer literal"
+                "`{gen_kind}` index bound must be a constant integer expression"
             )),
 
             MismatchedArrayComparisonSizes {
@@ -1313,6 +1596,17 @@ impl ReportableError for CompileError {
                     elements while the right-hand side argument has {rhs_size} elements"
             )),
 
+            MismatchedArrayComparisonElementTypes {
+                op,
+                lhs_el_ty,
+                rhs_el_ty,
+                ..
+            } => Some(format!(
+                "the left-hand side argument of the `{op}` operator has elements of type \
+                    `{lhs_el_ty}` while the right-hand side argument has elements of type \
+                    `{rhs_el_ty}`"
+            )),
+
             InvalidTupleAccessor { tuple_type, .. } => {
                 Some(format!("tuple has type `{tuple_type}`"))
             }
@@ -1356,11 +1650,11 @@ impl ReportableError for CompileError {
 
             Internal { .. }
             | FileIO { .. }
+            | UnknownPredicate { .. }
             | MacroNotFound { .. }
             | MacroUndefinedParam { .. }
             | SymbolNotFound { .. }
             | StorageSymbolNotFound { .. }
-            | MissingStorageBlock { .. }
             | InvalidNextStateAccess { .. }
             | MissingInterface { .. }
             | MissingPredicate { .. }
@@ -1373,6 +1667,11 @@ impl ReportableError for CompileError {
             | NonConstArrayIndex { .. }
             | InvalidConstArrayIndex { .. }
             | ArrayIndexOutOfBounds { .. }
+            | NonConstExponent { .. }
+            | NegativeExponent { .. }
+            | DivisionByZero { .. }
+            | UnknownTupleField { .. }
+            | UnsupportedCast { .. }
             | CannotIndexIntoValue { .. }
             | MacroMultiplePacks { .. }
             | MacroUnknownPack { .. }
@@ -1382,7 +1681,7 @@ impl ReportableError for CompileError {
             | UnknownType { .. }
             | UndefinedType { .. }
             | NonBoolConditional { .. }
-            | SelectBranchesTypeMismatch { .. }
+            | MismatchedIfBranchTypes { .. }
             | ConstraintExpressionTypeError { .. }
             | OperatorTypeError { .. }
             | OperatorInvalidType { .. }
@@ -1391,6 +1690,7 @@ impl ReportableError for CompileError {
             | IndexExprNonIndexable { .. }
             | TupleAccessNonTuple { .. }
             | EmptyArrayExpression { .. }
+            | RangeArrayTooLong { .. }
             | ExprRecursion { .. }
             | BadCastTo { .. }
             | BadCastFrom { .. }
@@ -1425,7 +1725,7 @@ impl ReportableError for CompileError {
     }
 
     fn code(&self) -> Option<String> {
-        None
+        Some(self.code_str().to_string())
     }
 
     fn help(&self) -> Option<String> {
@@ -1450,6 +1750,17 @@ impl ReportableError for CompileError {
                 pretty_join_strings(actual_variants),
             )),
 
+            RealScaleBitsOutOfRange { .. } => Some(format!(
+                "the number of fractional bits must be at most {MAX_REAL_SCALE_BITS}"
+            )),
+
+            UnknownPredicate { available, .. } if !available.is_empty() => Some(format!(
+                "the predicate{} in this contract {} {}",
+                if available.len() > 1 { "s" } else { "" },
+                if available.len() > 1 { "are" } else { "is" },
+                pretty_join_strings(available),
+            )),
+
             MacroCallMismatch {
                 name, suggestion, ..
             } => suggestion.clone().or(Some(format!(
@@ -1572,6 +1883,7 @@ impl Spanned for CompileError {
             | Internal { span, .. }
             | DualModulity { span, .. }
             | NoFileFoundForPath { span, .. }
+            | CyclicImport { span, .. }
             | MacroDeclClash { span, .. }
             | MacroNotFound { span, .. }
             | MacroCallMismatch { span, .. }
@@ -1600,6 +1912,8 @@ impl Spanned for CompileError {
             | MissingStorageBlock { span, .. }
             | MissingInterface { span, .. }
             | MissingPredicate { span, .. }
+            | UnknownPredicate { span, .. }
+            | RealScaleBitsOutOfRange { span, .. }
             | SelfReferencialPredicate { span, .. }
             | MissingInterfaceInstance { span, .. }
             | MissingPredicateInstance { span, .. }
@@ -1608,10 +1922,21 @@ impl Spanned for CompileError {
             | NonConstArrayLength { span }
             | InvalidConstArrayIndex { span }
             | ArrayIndexOutOfBounds { span }
+            | NonConstExponent { span }
+            | NegativeExponent { span }
+            | IntegerOverflow { span }
+            | ShiftOutOfRange { span }
+            | DivisionByZero { span }
+            | UnsatisfiableConstraint { span }
+            | UnknownTupleField { span, .. }
+            | InvalidWordIndex { span }
+            | NilNotSupportedHere { span }
+            | UnsupportedCast { span, .. }
             | CannotIndexIntoValue { span, .. }
             | UnknownType { span }
             | UndefinedType { span }
             | NonBoolConditional { span, .. }
+            | MismatchedIfBranchTypes { span, .. }
             | IndexExprNonIndexable { span, .. }
             | ArrayAccessWithWrongType { span, .. }
             | InvalidArrayRangeType { span, .. }
@@ -1619,9 +1944,11 @@ impl Spanned for CompileError {
             | TypeNotAllowedInStorage { span, .. }
             | StorageMapAccessWithWrongType { span, .. }
             | MismatchedArrayComparisonSizes { span, .. }
+            | MismatchedArrayComparisonElementTypes { span, .. }
             | TupleAccessNonTuple { span, .. }
             | InvalidTupleAccessor { span, .. }
             | EmptyArrayExpression { span }
+            | RangeArrayTooLong { span, .. }
             | NonHomogeneousArrayElement { span, .. }
             | ExprRecursion {
                 dependant_span: span,
@@ -1657,14 +1984,12 @@ impl Spanned for CompileError {
 
             DependencyCycle { spans } => &spans[0],
 
-            SelectBranchesTypeMismatch { large_err }
-            | OperatorTypeError { large_err, .. }
+            OperatorTypeError { large_err, .. }
             | StateVarInitTypeError { large_err, .. }
             | ConstraintExpressionTypeError { large_err, .. }
             | AddressExpressionTypeError { large_err, .. }
             | InitTypeError { large_err, .. } => match large_err.as_ref() {
-                LargeTypeError::SelectBranchesTypeMismatch { span, .. }
-                | LargeTypeError::OperatorTypeError { span, .. }
+                LargeTypeError::OperatorTypeError { span, .. }
                 | LargeTypeError::StateVarInitTypeError { span, .. }
                 | LargeTypeError::ConstraintExpressionTypeError { span, .. }
                 | LargeTypeError::AddressExpressionTypeError { span, .. }
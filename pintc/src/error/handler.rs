@@ -53,6 +53,16 @@ impl Handler {
         !self.inner.borrow().warnings.is_empty()
     }
 
+    /// The number of errors emitted so far.
+    pub fn error_count(&self) -> usize {
+        self.inner.borrow().errors.len()
+    }
+
+    /// The number of warnings emitted so far.
+    pub fn warning_count(&self) -> usize {
+        self.inner.borrow().warnings.len()
+    }
+
     pub fn clear(&self) {
         self.clear_errors();
         self.clear_warnings();
@@ -88,6 +98,11 @@ impl Handler {
         (inner.errors, inner.warnings)
     }
 
+    /// Consume the handler, returning only its errors.
+    pub fn into_errors(self) -> Vec<Error> {
+        self.consume().0
+    }
+
     pub fn append(&self, other: Handler) {
         let (errors, warnings) = other.consume();
         for warn in warnings {
@@ -104,3 +119,27 @@ impl Handler {
 pub struct ErrorEmitted {
     _priv: (),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::CompileError, span::empty_span};
+
+    #[test]
+    fn error_count() {
+        let handler = Handler::default();
+        assert_eq!(handler.error_count(), 0);
+
+        for _ in 0..3 {
+            let _ = handler.emit_err(Error::Compile {
+                error: CompileError::Internal {
+                    msg: "test error",
+                    span: empty_span(),
+                },
+            });
+        }
+        assert_eq!(handler.error_count(), 3);
+        assert_eq!(handler.warning_count(), 0);
+        assert_eq!(handler.into_errors().len(), 3);
+    }
+}
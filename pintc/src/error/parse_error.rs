@@ -1,5 +1,5 @@
 use crate::{
-    error::{ErrorLabel, ReportableError},
+    error::{diagnostic_codes, ErrorLabel, ReportableError},
     lexer::{self, Token},
     span::{Span, Spanned},
 };
@@ -17,6 +17,12 @@ pub enum ParseError {
     InvalidToken,
     #[error("invalid token")]
     Lex { span: Span },
+    #[error("unterminated block comment")]
+    UnterminatedBlockComment { span: Span },
+    #[error("unknown character escape")]
+    InvalidEscape { span: Span },
+    #[error("expression is nested too deeply")]
+    ExpressionTooDeep { max_depth: usize, span: Span },
 
     #[error("{}", format_expected_found_error(&mut expected.clone(), found))]
     ExpectedFound {
@@ -48,6 +54,8 @@ pub enum ParseError {
     },
     #[error("leading `+` is not supported")]
     UnsupportedLeadingPlus { span: Span },
+    #[error("leading `,` is not allowed in an argument list")]
+    UnexpectedLeadingComma { span: Span },
     #[error("`self` import can only appear in an import list with a non-empty prefix")]
     SelfWithEmptyPrefix { span: Span },
     #[error("`self` is only allowed at the end of a use path")]
@@ -79,6 +87,54 @@ pub enum ParseError {
     TypeNotSupported { ty: String, span: Span },
     #[error("Unsupported literal")]
     LiteralNotSupported { kind: String, span: Span },
+    #[error("invalid `@cfg` condition")]
+    InvalidCfgCondition { span: Span },
+    #[error("the wildcard `_` cannot bind a value")]
+    MatchWildcardBinding { span: Span },
+    #[error("unreachable `match` branch")]
+    UnreachableMatchBranch {
+        span: Span,          // Actual error location
+        wildcard_span: Span, // Span of the wildcard branch which precedes it
+    },
+}
+
+diagnostic_codes! {
+    ParseError {
+        InvalidToken => "invalid_token",
+        Lex => "lex",
+        UnterminatedBlockComment => "unterminated_block_comment",
+        InvalidEscape => "invalid_escape",
+        ExpressionTooDeep => "expression_too_deep",
+        ExpectedFound => "expected_found",
+        KeywordAsIdent => "keyword_as_ident",
+        UntypedVariable => "untyped_variable",
+        EmptyArrayExpr => "empty_array_expr",
+        EmptyIndexAccess => "empty_index_access",
+        InvalidIntegerTupleIndex => "invalid_integer_tuple_index",
+        InvalidTupleIndex => "invalid_tuple_index",
+        EmptyTupleExpr => "empty_tuple_expr",
+        EmptyTupleType => "empty_tuple_type",
+        NameClash => "name_clash",
+        UnsupportedLeadingPlus => "unsupported_leading_plus",
+        UnexpectedLeadingComma => "unexpected_leading_comma",
+        SelfWithEmptyPrefix => "self_with_empty_prefix",
+        SelfNotAtTheEnd => "self_not_at_the_end",
+        BinaryLiteralLength => "binary_literal_length",
+        HexLiteralLength => "hex_literal_length",
+        IntLiteralTooLarge => "int_literal_too_large",
+        TooManyStorageBlocks => "too_many_storage_blocks",
+        StorageDirectiveMustBeTopLevel => "storage_directive_must_be_top_level",
+        StorageAccessMustBeTopLevel => "storage_access_must_be_top_level",
+        PathTooShort => "path_too_short",
+        BadSplice => "bad_splice",
+        BadStorageIntrinsic => "bad_storage_intrinsic",
+        MissingIntrinsic => "missing_intrinsic",
+        TypeNotSupported => "type_not_supported",
+        LiteralNotSupported => "literal_not_supported",
+        InvalidCfgCondition => "invalid_cfg_condition",
+        MatchWildcardBinding => "match_wildcard_binding",
+        UnreachableMatchBranch => "unreachable_match_branch",
+    }
 }
 
 impl ReportableError for ParseError {
@@ -93,6 +149,27 @@ impl ReportableError for ParseError {
                     color: Color::Red,
                 }]
             }
+            UnterminatedBlockComment { span } => {
+                vec![ErrorLabel {
+                    message: "block comment is never closed".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+            InvalidEscape { span } => {
+                vec![ErrorLabel {
+                    message: "unknown character escape".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+            ExpressionTooDeep { max_depth, span } => {
+                vec![ErrorLabel {
+                    message: format!("nesting exceeds the maximum depth of {max_depth}"),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
             ExpectedFound { span, expected, .. } => {
                 vec![ErrorLabel {
                     message: format_expected_tokens_message(&mut expected.clone()),
@@ -181,6 +258,13 @@ impl ReportableError for ParseError {
                     color: Color::Red,
                 }]
             }
+            UnexpectedLeadingComma { span } => {
+                vec![ErrorLabel {
+                    message: "unexpected `,`".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
             SelfWithEmptyPrefix { span } => {
                 vec![ErrorLabel {
                     message: "can only appear in an import list with a non-empty prefix"
@@ -235,6 +319,30 @@ impl ReportableError for ParseError {
                     },
                 ]
             }
+            MatchWildcardBinding { span } => {
+                vec![ErrorLabel {
+                    message: "unexpected binding for the wildcard `_`".to_string(),
+                    span: span.clone(),
+                    color: Color::Red,
+                }]
+            }
+            UnreachableMatchBranch {
+                span,
+                wildcard_span,
+            } => {
+                vec![
+                    ErrorLabel {
+                        message: "this wildcard branch matches every value".to_string(),
+                        span: wildcard_span.clone(),
+                        color: Color::Blue,
+                    },
+                    ErrorLabel {
+                        message: "this branch is never reached".to_string(),
+                        span: span.clone(),
+                        color: Color::Red,
+                    },
+                ]
+            }
             StorageDirectiveMustBeTopLevel { span } => {
                 vec![ErrorLabel {
                     message: "a `storage` block can only appear in the top level module"
@@ -286,6 +394,11 @@ impl ReportableError for ParseError {
                 span: span.clone(),
                 color: Color::Red,
             }],
+            InvalidCfgCondition { span } => vec![ErrorLabel {
+                message: "unable to evaluate this condition".to_string(),
+                span: span.clone(),
+                color: Color::Red,
+            }],
         }
     }
 
@@ -296,25 +409,41 @@ impl ReportableError for ParseError {
                 "`{sym}` must be declared or imported only once in this scope"
             )),
             BinaryLiteralLength { .. } => {
-                Some("number of digits must be either 256 or between 1 and 64".to_string())
+                Some("number of digits must be between 1 and 256".to_string())
             }
             HexLiteralLength { .. } => {
-                Some("number of digits must be either 64 or between 1 and 16".to_string())
+                Some("number of digits must be between 1 and 64".to_string())
             }
             IntLiteralTooLarge { .. } => {
                 Some("value exceeds limit of `9,223,372,036,854,775,807`".to_string())
             }
+            UnterminatedBlockComment { .. } => Some(
+                "every `/*` in a block comment, including nested ones, must be closed by a `*/`"
+                    .to_string(),
+            ),
+            InvalidEscape { .. } => Some(
+                "valid escapes are `\\n`, `\\t`, `\\\"`, `\\\\` and `\\xDD`, where `DD` is two \
+                    hexadecimal digits"
+                    .to_string(),
+            ),
             PathTooShort { .. } => Some(
                 "a path to a predicate interface must contain a path to an interface \
                     instance followed by the name of the predicate, separated by a `::`"
                     .to_string(),
             ),
+            InvalidCfgCondition { .. } => Some(
+                "a `@cfg` condition is either `feature = \"<name>\"` or `not(<condition>)`"
+                    .to_string(),
+            ),
+            UnreachableMatchBranch { .. } => {
+                Some("a wildcard `_` branch must be the last branch of a `match`".to_string())
+            }
             _ => None,
         }
     }
 
     fn code(&self) -> Option<String> {
-        None
+        Some(self.code_str().to_string())
     }
 
     fn help(&self) -> Option<String> {
@@ -324,6 +453,10 @@ impl ReportableError for ParseError {
                 "consider giving `{name}` an explicit type or an initializer"
             )),
             UnsupportedLeadingPlus { .. } => Some("try removing the `+`".to_string()),
+            UnexpectedLeadingComma { .. } => Some("try removing the `,`".to_string()),
+            ExpressionTooDeep { .. } => Some(
+                "consider splitting the expression up using intermediate variables".to_string(),
+            ),
             _ => None,
         }
     }
@@ -389,6 +522,7 @@ impl Spanned for ParseError {
             | EmptyTupleType { span, .. }
             | NameClash { span, .. }
             | UnsupportedLeadingPlus { span, .. }
+            | UnexpectedLeadingComma { span, .. }
             | SelfWithEmptyPrefix { span, .. }
             | SelfNotAtTheEnd { span, .. }
             | BinaryLiteralLength { span, .. }
@@ -403,6 +537,12 @@ impl Spanned for ParseError {
             | MissingIntrinsic { span, .. }
             | TypeNotSupported { span, .. }
             | LiteralNotSupported { span, .. }
+            | InvalidCfgCondition { span }
+            | MatchWildcardBinding { span }
+            | UnreachableMatchBranch { span, .. }
+            | UnterminatedBlockComment { span }
+            | InvalidEscape { span }
+            | ExpressionTooDeep { span, .. }
             | Lex { span } => span,
 
             InvalidToken => unreachable!("The `InvalidToken` error is always wrapped in `Lex`."),
@@ -1,5 +1,5 @@
 use crate::{
-    predicate::{CallKey, ExprKey, UnionKey},
+    predicate::{CallKey, Contract, ExprKey, UnionKey},
     span::{empty_span, Span, Spanned},
     types::{PrimitiveKind, Type},
 };
@@ -82,6 +82,15 @@ pub enum Expr {
         index: ExprKey,
         span: Span,
     },
+    ArraySlice {
+        array: ExprKey,
+        range: ExprKey,
+        span: Span,
+    },
+    RangeArray {
+        range: ExprKey,
+        span: Span,
+    },
     TupleFieldAccess {
         tuple: ExprKey,
         field: TupleAccess,
@@ -155,6 +164,9 @@ pub enum Immediate {
     Nil,
     Real(f64),
     Int(i64),
+    /// An integer produced by compile-time evaluation which has overflowed `i64`.  These are only
+    /// valid as intermediate results and are never folded into the contract.
+    OverflowedInt(i128),
     Bool(bool),
     String(String),
     B256([u64; 4]),
@@ -169,6 +181,89 @@ pub enum Immediate {
 }
 
 impl Immediate {
+    /// Wrap `val` as an `Int` if it fits in an `i64`, otherwise as an `OverflowedInt`.
+    pub fn from_i128(val: i128) -> Self {
+        i64::try_from(val).map_or(Immediate::OverflowedInt(val), Immediate::Int)
+    }
+
+    /// Replace every union decl key found in `keys` with its new key.
+    pub(crate) fn replace_union_keys(&mut self, keys: &FxHashMap<UnionKey, UnionKey>) {
+        match self {
+            Immediate::Array(elements) => elements
+                .iter_mut()
+                .for_each(|element| element.replace_union_keys(keys)),
+
+            Immediate::Tuple(fields) => fields
+                .iter_mut()
+                .for_each(|(_, field)| field.replace_union_keys(keys)),
+
+            Immediate::UnionVariant { value, decl, .. } => {
+                if let Some(new_decl) = keys.get(decl) {
+                    *decl = *new_decl;
+                }
+
+                if let Some(value) = value {
+                    value.replace_union_keys(keys);
+                }
+            }
+
+            Immediate::Error
+            | Immediate::Nil
+            | Immediate::Real(_)
+            | Immediate::Int(_)
+            | Immediate::OverflowedInt(_)
+            | Immediate::Bool(_)
+            | Immediate::String(_)
+            | Immediate::B256(_) => {}
+        }
+    }
+
+    /// Like `==` but ignoring the spans of any tuple field names.
+    pub(crate) fn struct_eq(&self, other: &Immediate) -> bool {
+        match (self, other) {
+            (Immediate::Array(lhs), Immediate::Array(rhs)) => {
+                lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.struct_eq(rhs))
+            }
+
+            (Immediate::Tuple(lhs), Immediate::Tuple(rhs)) => {
+                lhs.len() == rhs.len()
+                    && lhs
+                        .iter()
+                        .zip(rhs)
+                        .all(|((lhs_name, lhs_value), (rhs_name, rhs_value))| {
+                            Ident::opt_struct_eq(lhs_name, rhs_name)
+                                && lhs_value.struct_eq(rhs_value)
+                        })
+            }
+
+            (
+                Immediate::UnionVariant {
+                    tag_num: lhs_tag_num,
+                    value_size: lhs_value_size,
+                    value: lhs_value,
+                    decl: lhs_decl,
+                },
+                Immediate::UnionVariant {
+                    tag_num: rhs_tag_num,
+                    value_size: rhs_value_size,
+                    value: rhs_value,
+                    decl: rhs_decl,
+                },
+            ) => {
+                lhs_tag_num == rhs_tag_num
+                    && lhs_value_size == rhs_value_size
+                    && lhs_decl == rhs_decl
+                    && match (lhs_value, rhs_value) {
+                        (Some(lhs), Some(rhs)) => lhs.struct_eq(rhs),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+
+            _ => self == other,
+        }
+    }
+
     pub fn get_ty(&self, opt_span: Option<&Span>) -> Type {
         let span = opt_span.cloned().unwrap_or_else(empty_span);
 
@@ -204,7 +299,7 @@ impl Immediate {
                 kind: match self {
                     Immediate::Nil => PrimitiveKind::Nil,
                     Immediate::Real(_) => PrimitiveKind::Real,
-                    Immediate::Int(_) => PrimitiveKind::Int,
+                    Immediate::Int(_) | Immediate::OverflowedInt(_) => PrimitiveKind::Int,
                     Immediate::Bool(_) => PrimitiveKind::Bool,
                     Immediate::String(_) => PrimitiveKind::String,
                     Immediate::B256(_) => PrimitiveKind::B256,
@@ -238,6 +333,7 @@ pub enum BinaryOp {
     Mul,
     Div,
     Mod,
+    Pow,
 
     // Comparison
     Equal,
@@ -247,6 +343,13 @@ pub enum BinaryOp {
     GreaterThanOrEqual,
     GreaterThan,
 
+    // Bitwise
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
+
     // Logical
     LogicalAnd,
     LogicalOr,
@@ -260,12 +363,18 @@ impl BinaryOp {
             BinaryOp::Mul => "*",
             BinaryOp::Div => "/",
             BinaryOp::Mod => "%",
+            BinaryOp::Pow => "**",
             BinaryOp::Equal => "==",
             BinaryOp::NotEqual => "!=",
             BinaryOp::LessThanOrEqual => "<=",
             BinaryOp::LessThan => "<",
             BinaryOp::GreaterThanOrEqual => ">=",
             BinaryOp::GreaterThan => ">",
+            BinaryOp::BitwiseAnd => "&",
+            BinaryOp::BitwiseOr => "|",
+            BinaryOp::BitwiseXor => "^",
+            BinaryOp::ShiftLeft => "<<",
+            BinaryOp::ShiftRight => ">>",
             BinaryOp::LogicalAnd => "&&",
             BinaryOp::LogicalOr => "||",
         }
@@ -296,6 +405,8 @@ impl Spanned for Expr {
             | Expr::Select { span, .. }
             | Expr::Match { span, .. }
             | Expr::Index { span, .. }
+            | Expr::ArraySlice { span, .. }
+            | Expr::RangeArray { span, .. }
             | Expr::TupleFieldAccess { span, .. }
             | Expr::Cast { span, .. }
             | Expr::In { span, .. }
@@ -379,6 +490,11 @@ impl Expr {
                 replace(expr);
                 replace(index);
             }
+            Expr::ArraySlice { array, range, .. } => {
+                replace(array);
+                replace(range);
+            }
+            Expr::RangeArray { range, .. } => replace(range),
             Expr::TupleFieldAccess { tuple, .. } => replace(tuple),
             Expr::Cast { value, .. } => replace(value),
             Expr::In {
@@ -429,4 +545,362 @@ impl Expr {
             }
         });
     }
+
+    /// Compare two expressions structurally, recursing into their sub-expressions and ignoring
+    /// all spans.  E.g., two `x + 1` expressions from different places in the source are equal,
+    /// whereas `x + 1` and `x + 2` are not.  Types are compared with `Type::eq()`.
+    pub fn struct_eq(&self, other: &Expr, contract: &Contract) -> bool {
+        let key_eq = |lhs: &ExprKey, rhs: &ExprKey| {
+            lhs == rhs
+                || match (lhs.try_get(contract), rhs.try_get(contract)) {
+                    (Some(lhs), Some(rhs)) => lhs.struct_eq(rhs, contract),
+                    _ => false,
+                }
+        };
+        let keys_eq = |lhs: &[ExprKey], rhs: &[ExprKey]| {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| key_eq(lhs, rhs))
+        };
+
+        match (self, other) {
+            (Expr::Error(_), Expr::Error(_)) => true,
+
+            (Expr::Immediate { value: lhs, .. }, Expr::Immediate { value: rhs, .. }) => {
+                lhs.struct_eq(rhs)
+            }
+
+            (
+                Expr::Array {
+                    elements: lhs_elements,
+                    range_expr: lhs_range_expr,
+                    ..
+                },
+                Expr::Array {
+                    elements: rhs_elements,
+                    range_expr: rhs_range_expr,
+                    ..
+                },
+            ) => keys_eq(lhs_elements, rhs_elements) && key_eq(lhs_range_expr, rhs_range_expr),
+
+            (Expr::Tuple { fields: lhs, .. }, Expr::Tuple { fields: rhs, .. }) => {
+                lhs.len() == rhs.len()
+                    && lhs
+                        .iter()
+                        .zip(rhs)
+                        .all(|((lhs_name, lhs_field), (rhs_name, rhs_field))| {
+                            Ident::opt_struct_eq(lhs_name, rhs_name) && key_eq(lhs_field, rhs_field)
+                        })
+            }
+
+            (
+                Expr::UnionVariant {
+                    path: lhs_path,
+                    value: lhs_value,
+                    ..
+                },
+                Expr::UnionVariant {
+                    path: rhs_path,
+                    value: rhs_value,
+                    ..
+                },
+            ) => {
+                lhs_path == rhs_path
+                    && match (lhs_value, rhs_value) {
+                        (Some(lhs), Some(rhs)) => key_eq(lhs, rhs),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+
+            (Expr::Path(lhs, _), Expr::Path(rhs, _)) => lhs == rhs,
+
+            (
+                Expr::StorageAccess {
+                    name: lhs_name,
+                    mutable: lhs_mutable,
+                    ..
+                },
+                Expr::StorageAccess {
+                    name: rhs_name,
+                    mutable: rhs_mutable,
+                    ..
+                },
+            ) => lhs_name == rhs_name && lhs_mutable == rhs_mutable,
+
+            (
+                Expr::ExternalStorageAccess {
+                    interface_instance: lhs_instance,
+                    name: lhs_name,
+                    ..
+                },
+                Expr::ExternalStorageAccess {
+                    interface_instance: rhs_instance,
+                    name: rhs_name,
+                    ..
+                },
+            ) => lhs_instance == rhs_instance && lhs_name == rhs_name,
+
+            (
+                Expr::UnaryOp {
+                    op: lhs_op,
+                    expr: lhs_expr,
+                    ..
+                },
+                Expr::UnaryOp {
+                    op: rhs_op,
+                    expr: rhs_expr,
+                    ..
+                },
+            ) => lhs_op == rhs_op && key_eq(lhs_expr, rhs_expr),
+
+            (
+                Expr::BinaryOp {
+                    op: lhs_op,
+                    lhs: lhs_lhs,
+                    rhs: lhs_rhs,
+                    ..
+                },
+                Expr::BinaryOp {
+                    op: rhs_op,
+                    lhs: rhs_lhs,
+                    rhs: rhs_rhs,
+                    ..
+                },
+            ) => lhs_op == rhs_op && key_eq(lhs_lhs, rhs_lhs) && key_eq(lhs_rhs, rhs_rhs),
+
+            // Macro call arguments are kept with the call itself, so calls are only equal if they
+            // are the same call.
+            (
+                Expr::MacroCall {
+                    call: lhs_call,
+                    path: lhs_path,
+                    ..
+                },
+                Expr::MacroCall {
+                    call: rhs_call,
+                    path: rhs_path,
+                    ..
+                },
+            ) => lhs_call == rhs_call && lhs_path == rhs_path,
+
+            (
+                Expr::IntrinsicCall {
+                    kind: (lhs_kind, _),
+                    args: lhs_args,
+                    ..
+                },
+                Expr::IntrinsicCall {
+                    kind: (rhs_kind, _),
+                    args: rhs_args,
+                    ..
+                },
+            ) => lhs_kind == rhs_kind && keys_eq(lhs_args, rhs_args),
+
+            (
+                Expr::Select {
+                    condition: lhs_condition,
+                    then_expr: lhs_then,
+                    else_expr: lhs_else,
+                    ..
+                },
+                Expr::Select {
+                    condition: rhs_condition,
+                    then_expr: rhs_then,
+                    else_expr: rhs_else,
+                    ..
+                },
+            ) => {
+                key_eq(lhs_condition, rhs_condition)
+                    && key_eq(lhs_then, rhs_then)
+                    && key_eq(lhs_else, rhs_else)
+            }
+
+            (
+                Expr::Match {
+                    match_expr: lhs_match_expr,
+                    match_branches: lhs_branches,
+                    else_branch: lhs_else,
+                    ..
+                },
+                Expr::Match {
+                    match_expr: rhs_match_expr,
+                    match_branches: rhs_branches,
+                    else_branch: rhs_else,
+                    ..
+                },
+            ) => {
+                key_eq(lhs_match_expr, rhs_match_expr)
+                    && lhs_branches.len() == rhs_branches.len()
+                    && lhs_branches.iter().zip(rhs_branches).all(|(lhs, rhs)| {
+                        lhs.name == rhs.name
+                            && Ident::opt_struct_eq(&lhs.binding, &rhs.binding)
+                            && keys_eq(&lhs.constraints, &rhs.constraints)
+                            && key_eq(&lhs.expr, &rhs.expr)
+                    })
+                    && match (lhs_else, rhs_else) {
+                        (Some(lhs), Some(rhs)) => {
+                            keys_eq(&lhs.constraints, &rhs.constraints)
+                                && key_eq(&lhs.expr, &rhs.expr)
+                        }
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+
+            (
+                Expr::Index {
+                    expr: lhs_expr,
+                    index: lhs_index,
+                    ..
+                },
+                Expr::Index {
+                    expr: rhs_expr,
+                    index: rhs_index,
+                    ..
+                },
+            ) => key_eq(lhs_expr, rhs_expr) && key_eq(lhs_index, rhs_index),
+
+            (
+                Expr::ArraySlice {
+                    array: lhs_array,
+                    range: lhs_range,
+                    ..
+                },
+                Expr::ArraySlice {
+                    array: rhs_array,
+                    range: rhs_range,
+                    ..
+                },
+            ) => key_eq(lhs_array, rhs_array) && key_eq(lhs_range, rhs_range),
+
+            (Expr::RangeArray { range: lhs, .. }, Expr::RangeArray { range: rhs, .. }) => {
+                key_eq(lhs, rhs)
+            }
+
+            (
+                Expr::TupleFieldAccess {
+                    tuple: lhs_tuple,
+                    field: lhs_field,
+                    ..
+                },
+                Expr::TupleFieldAccess {
+                    tuple: rhs_tuple,
+                    field: rhs_field,
+                    ..
+                },
+            ) => {
+                key_eq(lhs_tuple, rhs_tuple)
+                    && match (lhs_field, rhs_field) {
+                        (TupleAccess::Error, TupleAccess::Error) => true,
+                        (TupleAccess::Index(lhs), TupleAccess::Index(rhs)) => lhs == rhs,
+                        (TupleAccess::Name(lhs), TupleAccess::Name(rhs)) => lhs.struct_eq(rhs),
+                        _ => false,
+                    }
+            }
+
+            (
+                Expr::Cast {
+                    value: lhs_value,
+                    ty: lhs_ty,
+                    ..
+                },
+                Expr::Cast {
+                    value: rhs_value,
+                    ty: rhs_ty,
+                    ..
+                },
+            ) => lhs_ty.eq(contract, rhs_ty) && key_eq(lhs_value, rhs_value),
+
+            (
+                Expr::In {
+                    value: lhs_value,
+                    collection: lhs_collection,
+                    ..
+                },
+                Expr::In {
+                    value: rhs_value,
+                    collection: rhs_collection,
+                    ..
+                },
+            ) => key_eq(lhs_value, rhs_value) && key_eq(lhs_collection, rhs_collection),
+
+            (
+                Expr::Range {
+                    lb: lhs_lb,
+                    ub: lhs_ub,
+                    ..
+                },
+                Expr::Range {
+                    lb: rhs_lb,
+                    ub: rhs_ub,
+                    ..
+                },
+            ) => key_eq(lhs_lb, rhs_lb) && key_eq(lhs_ub, rhs_ub),
+
+            (
+                Expr::Generator {
+                    kind: lhs_kind,
+                    gen_ranges: lhs_ranges,
+                    conditions: lhs_conditions,
+                    body: lhs_body,
+                    ..
+                },
+                Expr::Generator {
+                    kind: rhs_kind,
+                    gen_ranges: rhs_ranges,
+                    conditions: rhs_conditions,
+                    body: rhs_body,
+                    ..
+                },
+            ) => {
+                lhs_kind == rhs_kind
+                    && lhs_ranges.len() == rhs_ranges.len()
+                    && lhs_ranges.iter().zip(rhs_ranges).all(
+                        |((lhs_name, lhs_range), (rhs_name, rhs_range))| {
+                            lhs_name.struct_eq(rhs_name) && key_eq(lhs_range, rhs_range)
+                        },
+                    )
+                    && keys_eq(lhs_conditions, rhs_conditions)
+                    && key_eq(lhs_body, rhs_body)
+            }
+
+            (
+                Expr::UnionTag {
+                    union_expr: lhs, ..
+                },
+                Expr::UnionTag {
+                    union_expr: rhs, ..
+                },
+            ) => key_eq(lhs, rhs),
+
+            (
+                Expr::UnionValue {
+                    union_expr: lhs_union_expr,
+                    variant_ty: lhs_ty,
+                    ..
+                },
+                Expr::UnionValue {
+                    union_expr: rhs_union_expr,
+                    variant_ty: rhs_ty,
+                    ..
+                },
+            ) => lhs_ty.eq(contract, rhs_ty) && key_eq(lhs_union_expr, rhs_union_expr),
+
+            _ => false,
+        }
+    }
+}
+
+impl Ident {
+    /// Compare two identifiers ignoring their spans.
+    pub(crate) fn struct_eq(&self, other: &Ident) -> bool {
+        self.name == other.name && self.hygienic == other.hygienic
+    }
+
+    fn opt_struct_eq(lhs: &Option<Ident>, rhs: &Option<Ident>) -> bool {
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => lhs.struct_eq(rhs),
+            (None, None) => true,
+            _ => false,
+        }
+    }
 }
@@ -120,6 +120,19 @@ impl DisplayWithContract for &super::Expr {
                 )
             }
 
+            super::Expr::ArraySlice { array, range, .. } => {
+                write!(
+                    f,
+                    "{}[{}]",
+                    contract.with_ctrct(array),
+                    contract.with_ctrct(range)
+                )
+            }
+
+            super::Expr::RangeArray { range, .. } => {
+                write!(f, "[{}]", contract.with_ctrct(range))
+            }
+
             super::Expr::MacroCall { path, .. } => {
                 write!(f, "{path}(...)",)
             }
@@ -235,6 +248,7 @@ impl DisplayWithContract for super::Immediate {
             super::Immediate::Nil => write!(f, "nil"),
             super::Immediate::Real(n) => write!(f, "{n:e}"),
             super::Immediate::Int(n) => write!(f, "{n}"),
+            super::Immediate::OverflowedInt(n) => write!(f, "{n}"),
             super::Immediate::Bool(b) => write!(f, "{b}"),
             super::Immediate::String(s) => write!(f, "{s:?}"),
             super::Immediate::B256(val) => {
@@ -298,6 +312,9 @@ impl Display for super::BinaryOp {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
             expr::BinaryOp::Add => write!(f, "+"),
+            expr::BinaryOp::BitwiseAnd => write!(f, "&"),
+            expr::BinaryOp::BitwiseOr => write!(f, "|"),
+            expr::BinaryOp::BitwiseXor => write!(f, "^"),
             expr::BinaryOp::Div => write!(f, "/"),
             expr::BinaryOp::Equal => write!(f, "=="),
             expr::BinaryOp::GreaterThanOrEqual => write!(f, ">="),
@@ -309,6 +326,9 @@ impl Display for super::BinaryOp {
             expr::BinaryOp::Mod => write!(f, "%"),
             expr::BinaryOp::Mul => write!(f, "*"),
             expr::BinaryOp::NotEqual => write!(f, "!="),
+            expr::BinaryOp::Pow => write!(f, "**"),
+            expr::BinaryOp::ShiftLeft => write!(f, "<<"),
+            expr::BinaryOp::ShiftRight => write!(f, ">>"),
             expr::BinaryOp::Sub => write!(f, "-"),
         }
     }
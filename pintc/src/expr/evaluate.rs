@@ -4,7 +4,7 @@ use crate::{
         BinaryOp as BinOp, Expr, Immediate as Imm, MatchBranch, MatchElse, TupleAccess, UnaryOp,
     },
     predicate::{Contract, ExprKey},
-    span::{empty_span, Spanned},
+    span::{empty_span, Span, Spanned},
     types::Type,
 };
 use fxhash::FxHashMap;
@@ -124,12 +124,16 @@ impl Evaluator {
                     })
                 }),
 
-            Expr::UnaryOp { op, expr, .. } => {
+            Expr::UnaryOp { op, expr, span } => {
                 let expr = self.evaluate_key(expr, handler, contract)?;
 
                 match (expr, op) {
                     (Imm::Real(expr), UnaryOp::Neg) => Ok(Imm::Real(-expr)),
-                    (Imm::Int(expr), UnaryOp::Neg) => Ok(Imm::Int(-expr)),
+                    (Imm::Int(expr), UnaryOp::Neg) => Ok(Imm::from_i128(-(expr as i128))),
+                    (Imm::OverflowedInt(expr), UnaryOp::Neg) => expr
+                        .checked_neg()
+                        .map(Imm::from_i128)
+                        .ok_or_else(|| int_overflow(handler, span)),
                     (Imm::Bool(expr), UnaryOp::Not) => Ok(Imm::Bool(!expr)),
                     _ => Err(handler.emit_err(Error::Compile {
                         error: CompileError::Internal {
@@ -140,7 +144,7 @@ impl Evaluator {
                 }
             }
 
-            Expr::BinaryOp { op, lhs, rhs, .. } => {
+            Expr::BinaryOp { op, lhs, rhs, span } => {
                 let lhs = self.evaluate_key(lhs, handler, contract)?;
                 let rhs = self.evaluate_key(rhs, handler, contract)?;
 
@@ -151,6 +155,7 @@ impl Evaluator {
                         BinOp::Sub => Ok(Imm::Real(lhs - rhs)),
                         BinOp::Mul => Ok(Imm::Real(lhs * rhs)),
                         BinOp::Div => Ok(Imm::Real(lhs / rhs)),
+                        BinOp::Pow => Ok(Imm::Real(lhs.powf(rhs))),
 
                         // Comparison
                         BinOp::Equal => Ok(Imm::Bool(lhs == rhs)),
@@ -170,11 +175,44 @@ impl Evaluator {
 
                     (Imm::Int(lhs), Imm::Int(rhs)) => match op {
                         // Arithmetic
-                        BinOp::Add => Ok(Imm::Int(lhs + rhs)),
-                        BinOp::Sub => Ok(Imm::Int(lhs - rhs)),
-                        BinOp::Mul => Ok(Imm::Int(lhs * rhs)),
-                        BinOp::Div => Ok(Imm::Int(lhs / rhs)),
-                        BinOp::Mod => Ok(Imm::Int(lhs % rhs)),
+                        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod => {
+                            int_arith(handler, op, lhs as i128, rhs as i128, span)
+                        }
+                        BinOp::Pow if rhs < 0 => Err(handler.emit_err(Error::Compile {
+                            error: CompileError::NegativeExponent { span: span.clone() },
+                        })),
+                        BinOp::Pow => u32::try_from(rhs)
+                            .ok()
+                            .and_then(|rhs| lhs.checked_pow(rhs))
+                            .map(Imm::Int)
+                            .ok_or_else(|| {
+                                handler.emit_err(Error::Compile {
+                                    error: CompileError::IntegerOverflow { span: span.clone() },
+                                })
+                            }),
+
+                        // Bitwise
+                        BinOp::BitwiseAnd => Ok(Imm::Int(lhs & rhs)),
+                        BinOp::BitwiseOr => Ok(Imm::Int(lhs | rhs)),
+                        BinOp::BitwiseXor => Ok(Imm::Int(lhs ^ rhs)),
+                        BinOp::ShiftLeft => u32::try_from(rhs)
+                            .ok()
+                            .and_then(|rhs| lhs.checked_shl(rhs))
+                            .map(Imm::Int)
+                            .ok_or_else(|| {
+                                handler.emit_err(Error::Compile {
+                                    error: CompileError::ShiftOutOfRange { span: span.clone() },
+                                })
+                            }),
+                        BinOp::ShiftRight => u32::try_from(rhs)
+                            .ok()
+                            .and_then(|rhs| lhs.checked_shr(rhs))
+                            .map(Imm::Int)
+                            .ok_or_else(|| {
+                                handler.emit_err(Error::Compile {
+                                    error: CompileError::ShiftOutOfRange { span: span.clone() },
+                                })
+                            }),
 
                         // Comparison
                         BinOp::Equal => Ok(Imm::Bool(lhs == rhs)),
@@ -192,6 +230,35 @@ impl Evaluator {
                         })),
                     },
 
+                    // At least one side has already overflowed `i64`, so only arithmetic and
+                    // comparisons are supported, using `i128`.
+                    (
+                        lhs @ (Imm::Int(_) | Imm::OverflowedInt(_)),
+                        rhs @ (Imm::Int(_) | Imm::OverflowedInt(_)),
+                    ) => {
+                        let as_i128 = |imm| match imm {
+                            Imm::Int(val) => val as i128,
+                            Imm::OverflowedInt(val) => val,
+                            _ => unreachable!("matched int immediates"),
+                        };
+                        let (lhs, rhs) = (as_i128(lhs), as_i128(rhs));
+
+                        match op {
+                            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod => {
+                                int_arith(handler, op, lhs, rhs, span)
+                            }
+
+                            BinOp::Equal => Ok(Imm::Bool(lhs == rhs)),
+                            BinOp::NotEqual => Ok(Imm::Bool(lhs != rhs)),
+                            BinOp::LessThan => Ok(Imm::Bool(lhs < rhs)),
+                            BinOp::LessThanOrEqual => Ok(Imm::Bool(lhs <= rhs)),
+                            BinOp::GreaterThan => Ok(Imm::Bool(lhs > rhs)),
+                            BinOp::GreaterThanOrEqual => Ok(Imm::Bool(lhs >= rhs)),
+
+                            _ => Err(int_overflow(handler, span)),
+                        }
+                    }
+
                     (Imm::Bool(lhs), Imm::Bool(rhs)) => match op {
                         // Comparison
                         BinOp::Equal => Ok(Imm::Bool(lhs == rhs)),
@@ -274,6 +341,69 @@ impl Evaluator {
                 }
             }
 
+            Expr::ArraySlice { array, range, span } => {
+                let Imm::Array(elements) = self.evaluate_key(array, handler, contract)? else {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::CannotIndexIntoValue {
+                            span: array.get(contract).span().clone(),
+                            index_span: span.clone(),
+                        },
+                    }));
+                };
+                let Some(Expr::Range { lb, ub, .. }) = range.try_get(contract) else {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::Internal {
+                            msg: "array slice must have a range",
+                            span: span.clone(),
+                        },
+                    }));
+                };
+
+                match (
+                    self.evaluate_key(lb, handler, contract)?,
+                    self.evaluate_key(ub, handler, contract)?,
+                ) {
+                    (Imm::Int(lb), Imm::Int(ub))
+                        if 0 <= lb && lb < ub && ub as usize <= elements.len() =>
+                    {
+                        Ok(Imm::Array(elements[lb as usize..ub as usize].to_vec()))
+                    }
+
+                    (Imm::Int(_), Imm::Int(_)) => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::ArrayIndexOutOfBounds { span: span.clone() },
+                    })),
+
+                    _ => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::InvalidConstArrayIndex { span: span.clone() },
+                    })),
+                }
+            }
+
+            Expr::RangeArray { range, span } => {
+                let Some(Expr::Range { lb, ub, .. }) = range.try_get(contract) else {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::Internal {
+                            msg: "range array must have a range",
+                            span: span.clone(),
+                        },
+                    }));
+                };
+
+                match (
+                    self.evaluate_key(lb, handler, contract)?,
+                    self.evaluate_key(ub, handler, contract)?,
+                ) {
+                    (Imm::Int(lb), Imm::Int(ub)) => {
+                        range_array_len(handler, lb, ub, span)?;
+                        Ok(Imm::Array((lb..ub).map(Imm::Int).collect()))
+                    }
+
+                    _ => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::InvalidConstArrayLength { span: span.clone() },
+                    })),
+                }
+            }
+
             Expr::TupleFieldAccess { tuple, field, span } => {
                 // If the expr is a tuple...
                 let tup = self.evaluate_key(tuple, handler, contract)?;
@@ -377,6 +507,16 @@ impl Evaluator {
                         }
                     }
 
+                    Imm::OverflowedInt(i) => {
+                        if ty.is_int() {
+                            Ok(imm)
+                        } else if ty.is_real() {
+                            Ok(Imm::Real(i as f64))
+                        } else {
+                            cast_error(imm)
+                        }
+                    }
+
                     Imm::Bool(b) => {
                         if ty.is_bool() {
                             Ok(imm)
@@ -698,6 +838,17 @@ impl ExprKey {
 
                 Expr::Index { expr, index, span }
             }
+            Expr::ArraySlice { array, range, span } => {
+                let array = array.plug_in(contract, values_map);
+                let range = range.plug_in(contract, values_map);
+
+                Expr::ArraySlice { array, range, span }
+            }
+            Expr::RangeArray { range, span } => {
+                let range = range.plug_in(contract, values_map);
+
+                Expr::RangeArray { range, span }
+            }
             Expr::TupleFieldAccess { tuple, field, span } => {
                 let tuple = tuple.plug_in(contract, values_map);
 
@@ -822,3 +973,63 @@ impl ExprKey {
             .insert(plugged, self.get_ty(contract).clone())
     }
 }
+
+/// Perform integer arithmetic for `op` using checked `i128` operations, producing an `Int` if the
+/// result fits in an `i64` and an `OverflowedInt` otherwise.  Fails on division by zero or if even
+/// `i128` overflows.
+fn int_arith(
+    handler: &Handler,
+    op: &BinOp,
+    lhs: i128,
+    rhs: i128,
+    span: &Span,
+) -> Result<Imm, ErrorEmitted> {
+    if rhs == 0 && matches!(op, BinOp::Div | BinOp::Mod) {
+        return Err(handler.emit_err(Error::Compile {
+            error: CompileError::DivisionByZero { span: span.clone() },
+        }));
+    }
+
+    match op {
+        BinOp::Add => lhs.checked_add(rhs),
+        BinOp::Sub => lhs.checked_sub(rhs),
+        BinOp::Mul => lhs.checked_mul(rhs),
+        BinOp::Div => lhs.checked_div(rhs),
+        BinOp::Mod => lhs.checked_rem(rhs),
+        _ => None,
+    }
+    .map(Imm::from_i128)
+    .ok_or_else(|| int_overflow(handler, span))
+}
+
+/// The maximum number of elements in a range array such as `[0..n]`, which is expanded into an
+/// array of every integer in its range.
+const MAX_RANGE_ARRAY_LEN: i64 = 1 << 16;
+
+/// Returns the number of elements in the range array `[lb..ub]`, spanning `span`.  Fails if the
+/// range is empty or has more than `MAX_RANGE_ARRAY_LEN` elements.
+pub(crate) fn range_array_len(
+    handler: &Handler,
+    lb: i64,
+    ub: i64,
+    span: &Span,
+) -> Result<i64, ErrorEmitted> {
+    match ub.checked_sub(lb) {
+        Some(len) if len <= 0 => Err(handler.emit_err(Error::Compile {
+            error: CompileError::EmptyArrayExpression { span: span.clone() },
+        })),
+        Some(len) if len <= MAX_RANGE_ARRAY_LEN => Ok(len),
+        _ => Err(handler.emit_err(Error::Compile {
+            error: CompileError::RangeArrayTooLong {
+                max_len: MAX_RANGE_ARRAY_LEN,
+                span: span.clone(),
+            },
+        })),
+    }
+}
+
+fn int_overflow(handler: &Handler, span: &Span) -> ErrorEmitted {
+    handler.emit_err(Error::Compile {
+        error: CompileError::IntegerOverflow { span: span.clone() },
+    })
+}
@@ -1,11 +1,11 @@
-use crate::types::{any, b256, error, int, r#bool, string, tuple, vector, Type};
+use crate::types::{any, array, b256, error, int, r#bool, string, tuple, vector, Type};
 use std::fmt::{Display, Formatter, Result};
 
 ///////////////////
 // IntrinsicKind //
 ///////////////////
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum IntrinsicKind {
     External(ExternalIntrinsic),
     Internal(InternalIntrinsic),
@@ -44,15 +44,31 @@ impl IntrinsicKind {
 // ExternalIntrinsic //
 ///////////////////////
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ExternalIntrinsic {
+    // Returns the absolute value of an integer.
+    Abs,
+
     // Returns the address of a predicate in the same contract
     AddressOf,
 
+    // Returns the number of elements in an array whose size is known at compile time.
+    Length,
+
+    // Returns the larger of two integers.
+    Max,
+
+    // Returns the smaller of two integers.
+    Min,
+
     // Returns the address of the predicate at a given pathway. Returns both the contract
     // address and the predicate address.
     PredicateAt,
 
+    // Recovers the address, i.e. the SHA 256 hash of the public key, from a secp256k1
+    // signature.
+    RecoverAddress,
+
     // Recovers the public key from a secp256k1 signature.
     RecoverSECP256k1,
 
@@ -68,6 +84,9 @@ pub enum ExternalIntrinsic {
     // Returns the content hash of the contract that this predicate belongs to.
     ThisContractAddress,
 
+    // Returns the salt of the contract that this predicate belongs to.
+    ThisContractSalt,
+
     // Returns the "pathway" of this predicate. The pathway of a predicate is the index of
     // the solution data currently being used to check the predicate.
     ThisPathway,
@@ -77,21 +96,31 @@ pub enum ExternalIntrinsic {
 
     // Validates an Ed25519 signature against a public key.
     VerifyEd25519,
+
+    // Returns one of the four 64-bit words of a `b256`, selected by a constant index.
+    Word,
 }
 
 impl Display for ExternalIntrinsic {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
+            Self::Abs => write!(f, "__abs"),
             Self::AddressOf => write!(f, "__address_of"),
+            Self::Length => write!(f, "__length"),
+            Self::Max => write!(f, "__max"),
+            Self::Min => write!(f, "__min"),
             Self::PredicateAt => write!(f, "__predicate_at"),
+            Self::RecoverAddress => write!(f, "__recover_address"),
             Self::RecoverSECP256k1 => write!(f, "__recover_secp256k1"),
             Self::Sha256 => write!(f, "__sha256"),
             Self::SizeOf => write!(f, "__size_of"),
             Self::ThisAddress => write!(f, "__this_address"),
             Self::ThisContractAddress => write!(f, "__this_contract_address"),
+            Self::ThisContractSalt => write!(f, "__this_contract_salt"),
             Self::ThisPathway => write!(f, "__this_pathway"),
             Self::VecLen => write!(f, "__vec_len"),
             Self::VerifyEd25519 => write!(f, "__verify_ed25519"),
+            Self::Word => write!(f, "__word"),
         }
     }
 }
@@ -99,13 +128,23 @@ impl Display for ExternalIntrinsic {
 impl ExternalIntrinsic {
     pub fn args(&self) -> Vec<Type> {
         match self {
+            Self::Abs => vec![
+                int(), // value
+            ],
             Self::AddressOf => vec![
                 string(), // path to a predicate in the contract
             ],
+            Self::Length => vec![
+                array(any()), // array to find the length of
+            ],
+            Self::Max | Self::Min => vec![
+                int(), // lhs
+                int(), // rhs
+            ],
             Self::PredicateAt => vec![
                 int(), // pathway
             ],
-            Self::RecoverSECP256k1 => vec![
+            Self::RecoverAddress | Self::RecoverSECP256k1 => vec![
                 b256(),                             // data hash
                 tuple(vec![b256(), b256(), int()]), // signature
             ],
@@ -117,6 +156,7 @@ impl ExternalIntrinsic {
             ],
             Self::ThisAddress => vec![],
             Self::ThisContractAddress => vec![],
+            Self::ThisContractSalt => vec![],
             Self::ThisPathway => vec![],
             Self::VecLen => vec![
                 vector(any()), // storage vector to find the length of
@@ -126,21 +166,32 @@ impl ExternalIntrinsic {
                 tuple(vec![b256(), b256()]), // signature
                 b256(),                      // public key
             ],
+            Self::Word => vec![
+                b256(), // value to take a word from
+                int(),  // constant index of the word, from 0 to 3
+            ],
         }
     }
 
     pub fn ty(&self) -> Type {
         match self {
+            Self::Abs => int(),
             Self::AddressOf => b256(),
+            Self::Length => int(),
+            Self::Max => int(),
+            Self::Min => int(),
             Self::PredicateAt => tuple(vec![b256(), b256()]),
+            Self::RecoverAddress => b256(),
             Self::RecoverSECP256k1 => tuple(vec![b256(), int()]),
             Self::Sha256 => b256(),
             Self::SizeOf => int(),
             Self::ThisAddress => b256(),
             Self::ThisContractAddress => b256(),
+            Self::ThisContractSalt => b256(),
             Self::ThisPathway => int(),
             Self::VecLen => int(),
             Self::VerifyEd25519 => r#bool(),
+            Self::Word => int(),
         }
     }
 }
@@ -149,7 +200,7 @@ impl ExternalIntrinsic {
 // InternalIntrinsic //
 ///////////////////////
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum InternalIntrinsic {
     // Determines if two sets are equal
     EqSet,
@@ -1,6 +1,11 @@
-use crate::{error::ParseError, span::Span};
+use crate::{
+    error::ParseError,
+    parser::ParseOptions,
+    span::{self, Span},
+};
+use fxhash::FxHashSet;
 use logos::Logos;
-use std::{fmt, ops::Range, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, fmt, ops::Range, rc::Rc};
 
 #[cfg(test)]
 mod tests;
@@ -43,6 +48,14 @@ pub enum Token {
     DoubleAmpersand,
     #[token("||")]
     DoublePipe,
+    #[token("&")]
+    Ampersand,
+    #[token("^")]
+    Caret,
+    #[token("<<")]
+    LtLt,
+    #[token(">>")]
+    GtGt,
     #[token("'")]
     SingleQuote,
     #[token("?")]
@@ -54,6 +67,8 @@ pub enum Token {
     Comma,
     #[token("*")]
     Star,
+    #[token("**")]
+    StarStar,
     #[token("{")]
     BraceOpen,
     #[token("}")]
@@ -167,20 +182,34 @@ pub enum Token {
     RealLiteral(String),
     #[regex(r"(0x([0-9A-Fa-f](_[0-9A-Fa-f])*)+|0b([0-1](_[0-1])*)+|([0-9](_[0-9])*)+)", |lex| lex.slice().to_string())]
     IntLiteral(String),
+    // Digit separators must sit between two digits; doubled or trailing `_` is a lex error rather
+    // than an int followed by an identifier.
+    #[regex(r"[0-9][0-9_]*(__[0-9_]*|_)", |_| Err::<(), _>(ParseError::InvalidToken))]
+    BadIntLiteral,
+    // Any escape is accepted here so that unknown ones can be reported precisely while decoding.
     #[regex(
-        r#""([^"\\]|\\(x[0-9a-fA-F]{2}|[nt"]|\\|\n))*""#,
+        r#""([^"\\]|\\(.|\n))*""#,
         |lex| {
             StringLiteralChar::lexer(lex.slice())
-                .map(|c| c.map(char::from))
+                .spanned()
+                .map(|(c, range)| c.map(char::from).map_err(|_| invalid_escape(lex, range.start)))
                 .collect::<Result<String, _>>()
-                .unwrap()
         }
     )]
     StringLiteral(String),
 
     #[regex(r"//[^\n\r]*", logos::skip)]
+    #[token("/*", skip_block_comment)]
     Comment,
 
+    // Exactly three slashes, so that `////...` separator lines remain ordinary comments.  The
+    // token holds the comment text without the `///` and a single following space, if any.
+    #[regex(r"///([^/\n\r][^\n\r]*)?", |lex| {
+        let text = &lex.slice()[3..];
+        text.strip_prefix(' ').unwrap_or(text).to_string()
+    })]
+    DocComment(String),
+
     // If we want to use '#' elsewhere one day we'll need to put these above it.
     #[token("###expr###")]
     TestMarkerExpr,
@@ -256,11 +285,16 @@ impl fmt::Display for Token {
             Token::NotEq => write!(f, "!="),
             Token::DoubleAmpersand => write!(f, "&&"),
             Token::DoublePipe => write!(f, "||"),
+            Token::Ampersand => write!(f, "&"),
+            Token::Caret => write!(f, "^"),
+            Token::LtLt => write!(f, "<<"),
+            Token::GtGt => write!(f, ">>"),
             Token::SingleQuote => write!(f, "'"),
             Token::QuestionMark => write!(f, "?"),
             Token::Semi => write!(f, ";"),
             Token::Comma => write!(f, ","),
             Token::Star => write!(f, "*"),
+            Token::StarStar => write!(f, "**"),
             Token::BraceOpen => write!(f, "{{"),
             Token::BraceClose => write!(f, "}}"),
             Token::ParenOpen => write!(f, "("),
@@ -339,8 +373,10 @@ impl fmt::Display for Token {
             Token::IntrinsicName(ident) => write!(f, "{ident}"),
             Token::RealLiteral(ident) => write!(f, "{ident}"),
             Token::IntLiteral(ident) => write!(f, "{ident}"),
+            Token::BadIntLiteral => write!(f, "bad int literal"),
             Token::StringLiteral(contents) => write!(f, "{contents}"),
             Token::Comment => write!(f, "comment"),
+            Token::DocComment(_) => write!(f, "doc comment"),
             Token::TestMarkerExpr => write!(f, "### expr test ###"),
             Token::TestMarkerRange => write!(f, "### range test ###"),
             Token::TestMarkerType => write!(f, "### type test ###"),
@@ -352,11 +388,63 @@ impl fmt::Display for Token {
     }
 }
 
+/// Skips over a block comment whose opening `/*` has just been lexed. Block comments may be
+/// nested, so the comment only ends once every `/*` has been matched by a `*/`. If the end of the
+/// input is reached first, the rest of the input is consumed and an error is returned.
+fn skip_block_comment(lex: &mut logos::Lexer<Token>) -> logos::FilterResult<(), ParseError> {
+    let remainder = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut idx = 0;
+    while idx < remainder.len() {
+        match &remainder[idx..] {
+            [b'/', b'*', ..] => {
+                depth += 1;
+                idx += 2;
+            }
+            [b'*', b'/', ..] => {
+                depth -= 1;
+                idx += 2;
+                if depth == 0 {
+                    lex.bump(idx);
+                    return logos::FilterResult::Skip;
+                }
+            }
+            _ => idx += 1,
+        }
+    }
+
+    lex.bump(remainder.len());
+    logos::FilterResult::Error(ParseError::UnterminatedBlockComment {
+        span: span::empty_span(),
+    })
+}
+
+/// The error for an unknown escape sequence at `offset` within the string literal being lexed. The
+/// span covers the `\` and the character following it. Like other errors produced by callbacks,
+/// the span's file path is filled in by `Lexer`.
+fn invalid_escape(lex: &logos::Lexer<Token>, offset: usize) -> ParseError {
+    let start = lex.span().start + offset;
+    let len: usize = lex.slice()[offset..]
+        .chars()
+        .take(2)
+        .map(char::len_utf8)
+        .sum();
+    ParseError::InvalidEscape {
+        span: Span::new(span::empty_span().context(), start..start + len),
+    }
+}
+
 pub(super) struct Lexer<'a> {
     token_stream: TokenSource<'a>,
     filepath: Rc<std::path::Path>,
     mod_path: &'a [String],
     state: LexerState,
+    // The features enabled for `@cfg(feature = "..")` attributes.
+    cfg: Option<&'a FxHashSet<String>>,
+    // The lines of the `///` doc comment block lexed since the last token.
+    doc_lines: Vec<String>,
+    // Every doc comment block lexed so far, keyed by the start of the token which follows it.
+    doc_comments: Rc<RefCell<BTreeMap<usize, String>>>,
 }
 
 impl<'sc> Lexer<'sc> {
@@ -370,6 +458,9 @@ impl<'sc> Lexer<'sc> {
             filepath: filepath.clone(),
             mod_path,
             state: LexerState::default(),
+            cfg: None,
+            doc_lines: Vec::new(),
+            doc_comments: Rc::default(),
         }
     }
 
@@ -383,9 +474,134 @@ impl<'sc> Lexer<'sc> {
             filepath: filepath.clone(),
             mod_path,
             state: LexerState::default(),
+            cfg: None,
+            doc_lines: Vec::new(),
+            doc_comments: Rc::default(),
+        }
+    }
+
+    /// Returns the doc comments collected by this lexer.  Each block of consecutive `///` doc
+    /// comment lines is joined with newlines and keyed by the start of the token which follows
+    /// it.  Blocks are added as the tokens are lexed, so a block is available to the parser once
+    /// the token following it has been lexed.
+    pub(super) fn doc_comments(&self) -> Rc<RefCell<BTreeMap<usize, String>>> {
+        self.doc_comments.clone()
+    }
+
+    /// Enable the features in `cfg` for any `@cfg(feature = "..")` attributes.
+    pub(super) fn with_cfg(mut self, cfg: &'sc FxHashSet<String>) -> Self {
+        self.cfg = Some(cfg);
+        self
+    }
+
+    /// Apply the lexing related `options`, i.e., the enabled features.
+    pub(super) fn with_options(self, options: &'sc ParseOptions) -> Self {
+        self.with_cfg(&options.cfg)
+    }
+
+    /// Evaluate a `@cfg(..)` attribute whose `@cfg` token has just been lexed, consuming all of
+    /// its tokens.
+    fn eval_cfg_attr(&mut self, cfg_span: &Range<usize>) -> Result<bool, ParseError> {
+        self.expect_cfg_token(cfg_span, Token::ParenOpen)?;
+        let enabled = self.eval_cfg_condition(cfg_span)?;
+        self.expect_cfg_token(cfg_span, Token::ParenClose)?;
+        Ok(enabled)
+    }
+
+    /// Evaluate either `feature = "name"` or `not(<condition>)`.
+    fn eval_cfg_condition(&mut self, cfg_span: &Range<usize>) -> Result<bool, ParseError> {
+        match self.token_stream.next() {
+            Some(Ok(Token::Ident((id, _)))) if id == "feature" => {
+                self.expect_cfg_token(cfg_span, Token::Eq)?;
+                match self.token_stream.next() {
+                    Some(Ok(Token::StringLiteral(feature))) => {
+                        Ok(self.cfg.map_or(false, |cfg| cfg.contains(&feature)))
+                    }
+                    _ => Err(self.invalid_cfg_condition(cfg_span)),
+                }
+            }
+
+            Some(Ok(Token::Ident((id, _)))) if id == "not" => {
+                self.expect_cfg_token(cfg_span, Token::ParenOpen)?;
+                let enabled = self.eval_cfg_condition(cfg_span)?;
+                self.expect_cfg_token(cfg_span, Token::ParenClose)?;
+                Ok(!enabled)
+            }
+
+            _ => Err(self.invalid_cfg_condition(cfg_span)),
         }
     }
 
+    fn expect_cfg_token(
+        &mut self,
+        cfg_span: &Range<usize>,
+        expected: Token,
+    ) -> Result<(), ParseError> {
+        match self.token_stream.next() {
+            Some(Ok(tok)) if tok == expected => Ok(()),
+            _ => Err(self.invalid_cfg_condition(cfg_span)),
+        }
+    }
+
+    fn invalid_cfg_condition(&self, cfg_span: &Range<usize>) -> ParseError {
+        ParseError::InvalidCfgCondition {
+            span: Span::new(
+                self.filepath.clone(),
+                cfg_span.start..self.token_stream.span().end,
+            ),
+        }
+    }
+
+    /// Skip over the declaration following a disabled `@cfg(..)` attribute.  Most declarations end
+    /// with a `;` but those which start with a block keyword may instead end with their closing
+    /// `}`, and an `if` may be followed by any number of `else` blocks.
+    fn skip_cfg_decl(&mut self) -> Result<(), ParseError> {
+        let mut first_tok = self.token_stream.next();
+
+        // Any further attributes are part of this declaration.
+        while let Some(Ok(Token::MacroName(name))) = &first_tok {
+            if name != "@cfg" {
+                break;
+            }
+            let cfg_span = self.token_stream.span();
+            self.eval_cfg_attr(&cfg_span)?;
+            first_tok = self.token_stream.next();
+        }
+
+        let is_block_decl = matches!(
+            first_tok,
+            Some(Ok(Token::Predicate
+                | Token::Interface
+                | Token::Storage
+                | Token::Macro
+                | Token::If
+                | Token::Match))
+        );
+
+        let mut depth = 0_usize;
+        let mut next_tok = first_tok;
+        while let Some(tok) = next_tok {
+            match tok {
+                Ok(Token::ParenOpen | Token::BracketOpen | Token::BraceOpen) => depth += 1,
+                Ok(Token::ParenClose | Token::BracketClose) => depth = depth.saturating_sub(1),
+                Ok(Token::BraceClose) => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 && is_block_decl {
+                        let mut peek_stream = self.token_stream.clone();
+                        if !matches!(peek_stream.next(), Some(Ok(Token::Else))) {
+                            return Ok(());
+                        }
+                    }
+                }
+                Ok(Token::Semi) if depth == 0 => return Ok(()),
+                _ => {}
+            }
+            next_tok = self.token_stream.next();
+        }
+
+        Ok(())
+    }
+
     fn gather_macro_body(
         &mut self,
         obrace_tok: Token,
@@ -449,7 +665,8 @@ impl<'sc> Lexer<'sc> {
                     count_since_double_colon = 0;
                 }
 
-                Some(Ok(tok @ Token::MacroName(_))) => {
+                // `@cfg` attributes are not macro calls so they're left alone.
+                Some(Ok(Token::MacroName(name))) if name != "@cfg" => {
                     // If we see a macro name in a macro body then we
                     // a) inject an empty tag used by recursion checking, and
                     // b) inject an absolute path for macros which don't have one.
@@ -469,7 +686,7 @@ impl<'sc> Lexer<'sc> {
                         }
                     }
 
-                    push_tok!(tok);
+                    push_tok!(Token::MacroName(name));
                     push_tok!(Token::MacroTag(None));
                 }
 
@@ -679,7 +896,38 @@ impl<'a> Iterator for Lexer<'a> {
     type Item = Result<(usize, Token, usize), ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.token_stream.next().map(|res| {
+        // Doc comments are collected rather than passed on to the parser.  Declarations disabled by
+        // a `@cfg(..)` attribute are skipped entirely, along with their doc comments.
+        let next = loop {
+            match self.token_stream.next() {
+                Some(Ok(Token::DocComment(line))) => self.doc_lines.push(line),
+                Some(Ok(Token::MacroName(name)))
+                    if name == "@cfg" && self.state == LexerState::Normal =>
+                {
+                    let cfg_span = self.token_stream.span();
+                    match self.eval_cfg_attr(&cfg_span) {
+                        Ok(true) => continue,
+                        Ok(false) => {
+                            self.doc_lines.clear();
+                            if let Err(err) = self.skip_cfg_decl() {
+                                return Some(Err(err));
+                            }
+                        }
+                        Err(err) => return Some(Err(err)),
+                    }
+                }
+                next => break next,
+            }
+        };
+
+        let doc_lines = std::mem::take(&mut self.doc_lines);
+        if next.is_some() && !doc_lines.is_empty() {
+            self.doc_comments
+                .borrow_mut()
+                .insert(self.token_stream.span().start, doc_lines.join("\n"));
+        }
+
+        next.map(|res| {
             let span = self.token_stream.span();
             res.and_then(|tok| match tok {
                 // The following states track a macro declaration going from `macro` to `@name` to
@@ -744,6 +992,18 @@ impl<'a> Iterator for Lexer<'a> {
                 // Preserve some errors.
                 ParseError::BadSplice(_) => err,
 
+                // Point at the unknown escape rather than the entire string literal.
+                ParseError::InvalidEscape { span } => ParseError::InvalidEscape {
+                    span: Span::new(self.filepath.clone(), span.start()..span.end()),
+                },
+
+                // Point at the opening `/*` rather than the entire rest of the file.
+                ParseError::UnterminatedBlockComment { .. } => {
+                    ParseError::UnterminatedBlockComment {
+                        span: Span::new(self.filepath.clone(), span.start..span.start + 2),
+                    }
+                }
+
                 // Generally just return a tokenisation failure with a proper span.
                 _ => ParseError::Lex {
                     span: Span::new(self.filepath.clone(), span.start..span.end),
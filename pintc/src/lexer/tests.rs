@@ -99,6 +99,10 @@ fn reals() {
         lex_one_success("1.23e1_000"),
         Token::RealLiteral("1.23e1_000".to_owned())
     );
+    assert_eq!(
+        lex_one_success("3.141_592"),
+        Token::RealLiteral("3.141_592".to_owned())
+    );
     check(
         &format!("{:?}", lex(".34", Rc::from(Path::new("test")))),
         expect_test::expect![[r#"([(Dot, "test":0..1), (IntLiteral("34"), "test":1..3)], [])"#]],
@@ -161,6 +165,17 @@ fn ints() {
         lex_one_success("0b1_0_1_0"),
         Token::IntLiteral("0b1_0_1_0".to_owned())
     );
+    assert_eq!(
+        lex_one_success("0xDEAD_BEEF"),
+        Token::IntLiteral("0xDEAD_BEEF".to_owned())
+    );
+
+    // Doubled and trailing separators are rejected.
+    for src in ["1__000", "1_000__000", "1_", "1_000_"] {
+        let (toks, errs) = lex(src, Rc::from(Path::new("test")));
+        assert!(toks.is_empty(), "`{src}` should not produce tokens.");
+        assert_eq!(errs.len(), 1, "`{src}` should produce a single error.");
+    }
 }
 
 #[test]
@@ -212,6 +227,49 @@ fn strings() {
         lex_one_success("\"aha\\x0a\\x0d\\x09\""),
         Token::StringLiteral("aha\n\r\t".to_string())
     );
+    assert_eq!(
+        lex_one_success(r#""new\nline""#),
+        Token::StringLiteral("new\nline".to_string())
+    );
+    assert_eq!(
+        lex_one_success(r#""tab\tbed""#),
+        Token::StringLiteral("tab\tbed".to_string())
+    );
+}
+
+#[test]
+fn invalid_string_escapes() {
+    let filepath: Rc<Path> = Rc::from(Path::new("test"));
+    let lex_string = |src: &str| {
+        crate::lexer::Lexer::new(src, &filepath, &[])
+            .map(|res| res.map(|(_, tok, _)| tok))
+            .collect::<Vec<_>>()
+    };
+
+    // Unknown escapes are reported at the escape itself.
+    assert_eq!(
+        lex_string(r#""bad \q escape""#),
+        vec![Err(ParseError::InvalidEscape {
+            span: Span::new(Rc::clone(&filepath), 5..7),
+        })]
+    );
+    assert_eq!(
+        lex_string(r#""\xZZ""#),
+        vec![Err(ParseError::InvalidEscape {
+            span: Span::new(Rc::clone(&filepath), 1..3),
+        })]
+    );
+
+    // Lexing continues after the string literal.
+    assert_eq!(
+        lex_string(r#""\0" ;"#),
+        vec![
+            Err(ParseError::InvalidEscape {
+                span: Span::new(Rc::clone(&filepath), 1..3),
+            }),
+            Ok(Token::Semi),
+        ]
+    );
 }
 
 #[test]
@@ -220,6 +278,16 @@ fn variables() {
     assert_eq!(lex_one_success("const"), Token::Const);
 }
 
+#[test]
+fn idents() {
+    // `_` is an ordinary identifier; it is only a wildcard as a `match` branch pattern.
+    assert_eq!(lex_one_success("_"), Token::Ident(("_".to_owned(), false)));
+    assert_eq!(
+        lex_one_success("_x"),
+        Token::Ident(("_x".to_owned(), false))
+    );
+}
+
 #[test]
 fn r#types() {
     assert_eq!(lex_one_success("type"), Token::Type);
@@ -233,6 +301,7 @@ fn operators() {
     assert_eq!(lex_one_success("+"), Token::Plus);
     assert_eq!(lex_one_success("-"), Token::Minus);
     assert_eq!(lex_one_success("*"), Token::Star);
+    assert_eq!(lex_one_success("**"), Token::StarStar);
     assert_eq!(lex_one_success("/"), Token::Div);
     assert_eq!(lex_one_success("%"), Token::Mod);
     assert_eq!(lex_one_success(">"), Token::Gt);
@@ -243,6 +312,27 @@ fn operators() {
     assert_eq!(lex_one_success("!="), Token::NotEq);
     assert_eq!(lex_one_success("&&"), Token::DoubleAmpersand);
     assert_eq!(lex_one_success("||"), Token::DoublePipe);
+    assert_eq!(lex_one_success("&"), Token::Ampersand);
+    assert_eq!(lex_one_success("^"), Token::Caret);
+    assert_eq!(lex_one_success("<<"), Token::LtLt);
+    assert_eq!(lex_one_success(">>"), Token::GtGt);
+}
+
+#[test]
+fn bitwise_and_logical_operators() {
+    let (toks, errs) = lex(
+        "a & b && c | d || e ^ f << 1 >> 2",
+        Rc::from(Path::new("test")),
+    );
+    assert!(errs.is_empty());
+    let toks = toks.into_iter().map(|(tok, _)| tok).collect::<Vec<_>>();
+    assert_eq!(toks[1], Token::Ampersand);
+    assert_eq!(toks[3], Token::DoubleAmpersand);
+    assert_eq!(toks[5], Token::Pipe);
+    assert_eq!(toks[7], Token::DoublePipe);
+    assert_eq!(toks[9], Token::Caret);
+    assert_eq!(toks[11], Token::LtLt);
+    assert_eq!(toks[13], Token::GtGt);
 }
 
 #[test]
@@ -341,6 +431,48 @@ constraint mid < low_val @ 2;
     assert!(matches!(tokens[18].0, Semi));
 }
 
+#[test]
+fn block_comments() {
+    use Token::*;
+
+    let filepath: Rc<Path> = Rc::from(Path::new("test"));
+    let lex_tokens = |src: &str| {
+        let (tokens, errors) = lex(src, Rc::clone(&filepath));
+        assert!(errors.is_empty());
+        tokens.into_iter().map(|(tok, _)| tok).collect::<Vec<_>>()
+    };
+    let a = || Ident(("a".to_owned(), false));
+    let b = || Ident(("b".to_owned(), false));
+
+    // Simple block comments, on one line or spanning several.
+    assert_eq!(lex_tokens("a /* comment */ b"), vec![a(), b()]);
+    assert_eq!(lex_tokens("a /* multi\n * line\n */ b"), vec![a(), b()]);
+    assert_eq!(lex_tokens("a/**/b"), vec![a(), b()]);
+
+    // Nested block comments only end when the outermost comment is closed.
+    assert_eq!(
+        lex_tokens("a /* outer /* inner */ still-comment */ b"),
+        vec![a(), b()]
+    );
+    assert_eq!(lex_tokens("a /*/**/*/ b"), vec![a(), b()]);
+
+    // Line comments are unaffected, even when they contain `/*`.
+    assert_eq!(lex_tokens("a // b /* c\nb"), vec![a(), b()]);
+    assert_eq!(lex_tokens("a /* // */ b"), vec![a(), b()]);
+
+    // An unterminated block comment is reported at its opening `/*`.
+    let results =
+        crate::lexer::Lexer::new("a /* outer /* inner */ b", &filepath, &[]).collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0], Ok((0, a(), 1)));
+    assert_eq!(
+        results[1],
+        Err(ParseError::UnterminatedBlockComment {
+            span: Span::new(Rc::clone(&filepath), 2..4),
+        })
+    );
+}
+
 #[test]
 fn macros_success() {
     use Token::*;
@@ -729,3 +861,99 @@ fn macros_call_badly_formed() {
     assert!(matches!(toks.next().unwrap().unwrap(), (_, Ident(_), _)));
     assert!(toks.next().is_none());
 }
+
+#[test]
+fn doc_comments() {
+    use Token::*;
+
+    let a = || Ident(("a".to_owned(), false));
+    let b = || Ident(("b".to_owned(), false));
+
+    // Exactly three slashes make a doc comment, with a single leading space dropped.
+    assert_eq!(
+        lex_one_success("/// Some docs."),
+        DocComment("Some docs.".to_owned())
+    );
+    assert_eq!(lex_one_success("///x"), DocComment("x".to_owned()));
+    assert_eq!(
+        lex_one_success("///   indented"),
+        DocComment("  indented".to_owned())
+    );
+    assert_eq!(lex_one_success("///"), DocComment(String::new()));
+
+    // Two slashes or more than three are ordinary comments.
+    let (tokens, errors) = lex("// a\n//// b\n/////////", Rc::from(Path::new("test")));
+    assert!(errors.is_empty());
+    assert!(tokens.is_empty());
+
+    // The parser's lexer skips doc comments entirely.
+    let filepath: Rc<Path> = Rc::from(Path::new("test"));
+    let tokens = crate::lexer::Lexer::new("a\n/// docs\nb", &filepath, &[])
+        .map(|res| res.map(|(_, tok, _)| tok))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens, vec![a(), b()]);
+
+    // Consecutive doc comment lines are joined and keyed by the start of the following token.
+    let src = "a\n/// one\n///two\nb /// three\n\n/// four\n";
+    let lexer = crate::lexer::Lexer::new(src, &filepath, &[]);
+    let doc_comments = lexer.doc_comments();
+    assert_eq!(lexer.count(), 2);
+    let doc_comments = doc_comments.borrow();
+    assert_eq!(doc_comments.len(), 1);
+    assert_eq!(doc_comments[&src.find('b').unwrap()], "one\ntwo");
+
+    // The doc comments of a declaration disabled by a `@cfg(..)` attribute are dropped with it.
+    let lexer = crate::lexer::Lexer::new("/// gone\n@cfg(feature = \"off\") a;\nb", &filepath, &[]);
+    let disabled_doc_comments = lexer.doc_comments();
+    assert_eq!(lexer.count(), 1);
+    assert!(disabled_doc_comments.borrow().is_empty());
+}
+
+#[test]
+fn cfg_attributes() {
+    use Token::*;
+
+    let filepath: Rc<Path> = Rc::from(Path::new("test"));
+    let cfg = FxHashSet::from_iter(["on".to_owned()]);
+    let lex_with_cfg = |src: &str| {
+        Lexer::new(src, &filepath, &[])
+            .with_cfg(&cfg)
+            .map(|res| res.map(|(_, tok, _)| tok))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let a = || Ident(("a".to_owned(), false));
+    let b = || Ident(("b".to_owned(), false));
+
+    // Enabled attributes are dropped, disabled ones drop the following declaration too.
+    assert_eq!(
+        lex_with_cfg(r#"@cfg(feature = "on") a; @cfg(feature = "off") b; a"#).unwrap(),
+        vec![a(), Semi, a()]
+    );
+    assert_eq!(
+        lex_with_cfg(r#"@cfg(not(feature = "on")) a; @cfg(not(feature = "off")) b;"#).unwrap(),
+        vec![b(), Semi]
+    );
+
+    // Block declarations end at their closing brace, including any `else` blocks.
+    assert_eq!(
+        lex_with_cfg(r#"@cfg(feature = "off") predicate a { b; } a"#).unwrap(),
+        vec![a()]
+    );
+    assert_eq!(
+        lex_with_cfg(r#"@cfg(feature = "off") if a { a; } else if b { b; } else { a; } b"#)
+            .unwrap(),
+        vec![b()]
+    );
+    assert_eq!(
+        lex_with_cfg(r#"@cfg(feature = "off") const a = { 1, 2 }; b"#).unwrap(),
+        vec![b()]
+    );
+
+    // Anything else is an error.
+    assert!(matches!(
+        lex_with_cfg(r#"@cfg(on) a;"#),
+        Err(ParseError::InvalidCfgCondition { .. })
+    ));
+}
@@ -12,3 +12,27 @@ pub mod predicate;
 mod span;
 mod types;
 mod util;
+
+use asm_gen::CompiledContract;
+use error::{ErrorEmitted, Handler};
+use predicate::CompileOptions;
+use std::path::Path;
+
+/// Parse, type check, flatten, optimize and generate assembly for the contract in `src`, without
+/// reading it from a file.  The source is given the name `<string>` in any diagnostics.  Any
+/// modules it uses are still found relative to the current directory.
+///
+/// ```
+/// use pintc::{compile_str, error::Handler};
+///
+/// let handler = Handler::default();
+/// let compiled = compile_str("predicate test { constraint true; }", &handler).unwrap();
+/// assert_eq!(compiled.predicates.len(), 1);
+/// ```
+pub fn compile_str(src: &str, handler: &Handler) -> Result<CompiledContract, ErrorEmitted> {
+    let deps = Default::default();
+    let parsed = parser::parse_project_from_str(handler, &deps, Path::new("<string>"), src)?;
+    let options = CompileOptions::default();
+    let contract = handler.scope(|handler| parsed.compile(handler, &options))?;
+    handler.scope(|handler| asm_gen::compile_contract(handler, &contract, &options))
+}
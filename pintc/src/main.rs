@@ -1,20 +1,60 @@
 use clap::Parser;
 use pintc::{
-    asm_gen::compile_contract, cli::Args, error, parser, predicate::CompileOptions, warning,
+    asm_gen::compile_contract_with_entry,
+    cli::{Args, ErrorFormat},
+    error,
+    parser::{self, ParseOptions},
+    predicate::CompileOptions,
+    warning,
 };
 use std::{
-    fs::{create_dir_all, File},
+    fs::{self, create_dir_all, File},
+    io::Read,
     path::{Path, PathBuf},
+    time::Instant,
 };
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    let filepath = Path::new(&args.filepath);
+    let from_stdin = args.filepath == "-";
+    let filepath = if from_stdin {
+        Path::new("<stdin>")
+    } else {
+        Path::new(&args.filepath)
+    };
+
+    // See https://no-color.org/
+    if args.no_color || std::env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty()) {
+        yansi::disable();
+    }
+
+    let options = CompileOptions {
+        skip_optimize: args.skip_optimize,
+        print_flat: args.print_flat,
+        real_scale_bits: args.real_scale_bits,
+        inline_vars: args.inline_vars,
+        salt: args.salt.unwrap_or_default(),
+        record_asm_notes: args.explain_asm,
+        time_phases: args.time,
+    };
 
     // Lex + Parse
     let handler = error::Handler::default();
     let deps = Default::default(); // Allow for passing lib deps by CLI?
-    let parsed = match parser::parse_project(&handler, &deps, filepath) {
+    let start = Instant::now();
+    let parse_options = ParseOptions {
+        cfg: args.cfg.iter().cloned().collect(),
+        max_nesting_depth: args.max_nesting_depth,
+    };
+    let parse_result = if from_stdin {
+        let mut src = String::new();
+        std::io::stdin().read_to_string(&mut src)?;
+        parser::parse_project_from_str_with_options(&handler, &deps, filepath, &src, &parse_options)
+    } else {
+        parser::parse_project_with_options(&handler, &deps, filepath, &parse_options)
+    };
+    options.report_time("parse", start);
+    let parsed = match parse_result {
         Ok(parsed) => {
             if args.print_parsed {
                 println!("{parsed}");
@@ -25,44 +65,76 @@ fn main() -> anyhow::Result<()> {
             let (errors, warnings) = handler.consume();
             let errors_len = errors.len();
             if !cfg!(test) {
-                error::print_errors(&error::Errors(errors));
-                warning::print_warnings(&warning::Warnings(warnings));
+                print_diagnostics(args.error_format, args.max_errors, errors, warnings);
             }
             pintc::pintc_bail!(errors_len, filepath)
         }
     };
 
+    // Type check only, if requested
+    if args.check {
+        let start = Instant::now();
+        let check_result = parsed.type_check_only(&handler);
+        options.report_time("type check", start);
+        if check_result.is_err() {
+            let (errors, warnings) = handler.consume();
+            let errors_len = errors.len();
+            if !cfg!(test) {
+                print_diagnostics(args.error_format, args.max_errors, errors, warnings);
+            }
+            pintc::pintc_bail!(errors_len, filepath)
+        }
+
+        deny_warnings(&args, &handler, filepath)?;
+
+        if handler.has_warnings() && !cfg!(test) {
+            print_diagnostics(
+                args.error_format,
+                args.max_errors,
+                Vec::new(),
+                handler.consume().1,
+            );
+        }
+        return Ok(());
+    }
+
     // Type check, flatten and optimize
-    let contract = match handler.scope(|handler| {
-        parsed.compile(
-            handler,
-            CompileOptions {
-                skip_optimize: args.skip_optimize,
-                print_flat: args.print_flat,
-            },
-        )
-    }) {
+    let compile_result = handler.scope(|handler| parsed.compile(handler, &options));
+    let contract = match compile_result {
         Ok(optimized) => {
             if args.print_optimized && !args.skip_optimize {
                 println!("{optimized}");
             }
+            if args.dump_ir {
+                println!("{}", serde_json::to_string_pretty(&optimized.to_ir_json())?);
+            }
             optimized
         }
         Err(_) => {
             let (errors, warnings) = handler.consume();
             let errors_len = errors.len();
             if !cfg!(test) {
-                error::print_errors(&error::Errors(errors));
-                warning::print_warnings(&warning::Warnings(warnings));
+                print_diagnostics(args.error_format, args.max_errors, errors, warnings);
             }
             pintc::pintc_bail!(errors_len, filepath)
         }
     };
 
-    match handler.scope(|handler| compile_contract(handler, &contract)) {
+    // Treat any warnings as errors, if requested, before producing any output.
+    deny_warnings(&args, &handler, filepath)?;
+
+    let start = Instant::now();
+    let asm_gen_result = handler.scope(|handler| {
+        compile_contract_with_entry(handler, &contract, &options, args.entry.as_deref())
+    });
+    options.report_time("asm gen", start);
+    match asm_gen_result {
         Ok(compiled_contract) => {
             if args.print_asm {
-                println!("{compiled_contract}");
+                println!("{}", compiled_contract.disassemble());
+            }
+            if args.explain_asm {
+                println!("{}", compiled_contract.explain_asm());
             }
 
             // Determine output directory
@@ -76,52 +148,78 @@ fn main() -> anyhow::Result<()> {
                 }
             }
 
-            // Produce `json` ABI
-            let mut filepath_stem = filepath
+            // Produce `json` ABI. When reading from stdin there is no input file to name it after,
+            // so it is named after the output file instead.
+            let abi_stem_path = if from_stdin {
+                output_file_path.as_path()
+            } else {
+                filepath
+            };
+            let mut filepath_stem = abi_stem_path
                 .file_stem()
                 .expect("Failed to get file stem")
                 .to_os_string();
             filepath_stem.push("-abi");
-            let mut json_abi_path = PathBuf::from(filepath);
+            let mut json_abi_path = PathBuf::from(abi_stem_path);
             json_abi_path.set_file_name(filepath_stem);
             json_abi_path.set_extension("json");
-            json_abi_path = output_directory_path.join(json_abi_path);
+            if !from_stdin {
+                json_abi_path = output_directory_path.join(json_abi_path);
+            }
 
-            // Compute the JSON ABI
-            let abi = match handler.scope(|handler| contract.abi(handler)) {
+            // Compute the JSON ABI, keeping only the predicates which were compiled if an entry
+            // predicate was given.
+            let mut abi = match handler.scope(|handler| contract.abi(handler)) {
                 Ok(abi) => abi,
                 Err(_) => {
                     let (errors, warnings) = handler.consume();
                     let errors_len = errors.len();
                     if !cfg!(test) {
-                        error::print_errors(&error::Errors(errors));
-                        warning::print_warnings(&warning::Warnings(warnings));
+                        print_diagnostics(args.error_format, args.max_errors, errors, warnings);
                     }
                     pintc::pintc_bail!(errors_len, filepath)
                 }
             };
+            abi.predicates
+                .retain(|pred| compiled_contract.names.contains(&pred.name));
+
+            if args.print_abi {
+                println!("{}", serde_json::to_string_pretty(&abi)?);
+            }
 
-            // Write ABI and contract
-            serde_json::to_writer_pretty(File::create(json_abi_path)?, &abi)?;
-            serde_json::to_writer(
-                File::create(output_file_path)?,
-                &essential_types::contract::Contract {
-                    predicates: compiled_contract.predicates,
-                    salt: compiled_contract.salt,
-                },
-            )?;
+            // Write ABI and contract, unless reading from stdin without an output path.
+            if !from_stdin || args.output.is_some() {
+                serde_json::to_writer_pretty(File::create(json_abi_path)?, &abi)?;
+                if args.asm_text {
+                    fs::write(
+                        output_file_path.with_extension("asm"),
+                        compiled_contract.disassemble(),
+                    )?;
+                }
+                serde_json::to_writer(
+                    File::create(output_file_path)?,
+                    &essential_types::contract::Contract {
+                        predicates: compiled_contract.predicates,
+                        salt: compiled_contract.salt,
+                    },
+                )?;
+            }
 
             // Report any warnings
             if handler.has_warnings() && !cfg!(test) {
-                warning::print_warnings(&warning::Warnings(handler.consume().1));
+                print_diagnostics(
+                    args.error_format,
+                    args.max_errors,
+                    Vec::new(),
+                    handler.consume().1,
+                );
             }
         }
         Err(_) => {
             let (errors, warnings) = handler.consume();
             let errors_len = errors.len();
             if !cfg!(test) {
-                error::print_errors(&error::Errors(errors));
-                warning::print_warnings(&warning::Warnings(warnings));
+                print_diagnostics(args.error_format, args.max_errors, errors, warnings);
             }
             pintc::pintc_bail!(errors_len, filepath)
         }
@@ -129,3 +227,41 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// If `--deny-warnings` was given and there are any warnings then print them and fail.
+fn deny_warnings(args: &Args, handler: &error::Handler, filepath: &Path) -> anyhow::Result<()> {
+    if !args.deny_warnings || !handler.has_warnings() {
+        return Ok(());
+    }
+
+    let warnings = handler.consume().1;
+    let warnings_len = warnings.len();
+    if !cfg!(test) {
+        print_diagnostics(args.error_format, args.max_errors, Vec::new(), warnings);
+    }
+    anyhow::bail!(
+        "could not compile `{}` due to {} denied warning{}",
+        filepath.display(),
+        warnings_len,
+        if warnings_len == 1 { "" } else { "s" }
+    )
+}
+
+/// Print `errors` followed by `warnings` to stderr in the requested `format`.
+fn print_diagnostics(
+    format: ErrorFormat,
+    max_errors: Option<usize>,
+    errors: Vec<error::Error>,
+    warnings: Vec<warning::Warning>,
+) {
+    match format {
+        ErrorFormat::Human => {
+            error::print_errors(&error::Errors(errors), max_errors);
+            warning::print_warnings(&warning::Warnings(warnings));
+        }
+        ErrorFormat::Json => {
+            error::print_errors_json(&error::Errors(errors), max_errors);
+            warning::print_warnings_json(&warning::Warnings(warnings));
+        }
+    }
+}
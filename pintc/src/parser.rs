@@ -7,6 +7,7 @@ use crate::{
     span::{empty_span, Span},
     types::*,
 };
+use fxhash::FxHashSet;
 
 use std::{
     collections::BTreeMap,
@@ -23,30 +24,104 @@ mod use_path;
 pub(crate) use use_path::{UsePath, UseTree};
 
 mod context;
-pub(crate) use context::ParserContext;
+pub(crate) use context::{ParsedMatchBranch, ParserContext};
 
 #[cfg(test)]
 mod tests;
 
+/// The default maximum depth of nested expressions, e.g., operators, field accesses and array
+/// elements.  Deeper nesting is rejected early since later recursive passes over the expressions
+/// could otherwise overflow the stack.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 256;
+
+/// Options which control how a project is parsed.
+pub struct ParseOptions {
+    /// The features enabled for `@cfg` attributes.  Any declaration preceded by a
+    /// `@cfg(feature = "<name>")` attribute is only parsed if `<name>` is in `cfg`.  The condition
+    /// may be negated with `@cfg(not(feature = "<name>"))`.
+    pub cfg: FxHashSet<String>,
+    /// The maximum depth of nested expressions.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            cfg: FxHashSet::default(),
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+        }
+    }
+}
+
 pub fn parse_project(
     handler: &Handler,
     deps: &Dependencies,
     root_src_path: &Path,
 ) -> Result<Contract, ErrorEmitted> {
-    ProjectParser::new(handler, deps, PathBuf::from(root_src_path))
+    parse_project_with_options(handler, deps, root_src_path, &ParseOptions::default())
+}
+
+/// Like `parse_project()` but with custom `ParseOptions`.
+pub fn parse_project_with_options(
+    handler: &Handler,
+    deps: &Dependencies,
+    root_src_path: &Path,
+    options: &ParseOptions,
+) -> Result<Contract, ErrorEmitted> {
+    ProjectParser::new(handler, deps, PathBuf::from(root_src_path), None, options)
         .parse_project()
         .finalize()
 }
 
+/// Like `parse_project()` but the source of the root module is `root_src` rather than the contents
+/// of the file at `root_src_path`.  The path is still used in spans and as the base for finding any
+/// other modules.
+pub fn parse_project_from_str(
+    handler: &Handler,
+    deps: &Dependencies,
+    root_src_path: &Path,
+    root_src: &str,
+) -> Result<Contract, ErrorEmitted> {
+    parse_project_from_str_with_options(
+        handler,
+        deps,
+        root_src_path,
+        root_src,
+        &ParseOptions::default(),
+    )
+}
+
+/// Like `parse_project_from_str()` but with custom `ParseOptions`.
+pub fn parse_project_from_str_with_options(
+    handler: &Handler,
+    deps: &Dependencies,
+    root_src_path: &Path,
+    root_src: &str,
+    options: &ParseOptions,
+) -> Result<Contract, ErrorEmitted> {
+    ProjectParser::new(
+        handler,
+        deps,
+        PathBuf::from(root_src_path),
+        Some(root_src),
+        options,
+    )
+    .parse_project()
+    .finalize()
+}
+
 struct ProjectParser<'a> {
     contract: Contract,
     macros: Vec<MacroDecl>,
     macro_calls: BTreeMap<PredKey, slotmap::SecondaryMap<CallKey, (ExprKey, MacroCall)>>,
     proj_root_path: PathBuf,
     root_src_path: PathBuf,
+    root_src: Option<&'a str>,
     visited_paths: Vec<PathBuf>,
+    mod_imports: BTreeMap<Vec<String>, BTreeMap<Vec<String>, Span>>,
     handler: &'a Handler,
     deps: &'a Dependencies<'a>,
+    options: &'a ParseOptions,
     unique_idx: u64,
 }
 
@@ -82,7 +157,13 @@ enum FoundPath {
 }
 
 impl<'a> ProjectParser<'a> {
-    fn new(handler: &'a Handler, deps: &'a Dependencies<'a>, root_src_path: PathBuf) -> Self {
+    fn new(
+        handler: &'a Handler,
+        deps: &'a Dependencies<'a>,
+        root_src_path: PathBuf,
+        root_src: Option<&'a str>,
+        options: &'a ParseOptions,
+    ) -> Self {
         let proj_root_path = root_src_path
             .parent()
             .map_or_else(|| PathBuf::from("/"), PathBuf::from);
@@ -96,9 +177,12 @@ impl<'a> ProjectParser<'a> {
             macro_calls,
             proj_root_path,
             root_src_path,
+            root_src,
             visited_paths: vec![],
+            mod_imports: BTreeMap::default(),
             handler,
             deps,
+            options,
             unique_idx: 0,
         }
     }
@@ -228,6 +312,13 @@ impl<'a> ProjectParser<'a> {
     }
 
     fn finalize(self) -> Result<Contract, ErrorEmitted> {
+        // Reject expressions too deep for the later recursive passes.
+        self.contract
+            .check_expr_depths(self.handler, self.options.max_nesting_depth);
+
+        // Modules which import each other can't be resolved.
+        self.check_import_cycles();
+
         // Check all predicate symbols against top level symbols for name clashes.
         self.contract.preds.values().for_each(|pred| {
             let _ = self
@@ -261,6 +352,7 @@ macro_rules! parse_with {
      $local_scope: expr,
      $macro_ctx: expr,
      $current_pred_key: expr,
+     $doc_comments: expr,
      $handler: expr,
      ) => {{
         let span_from = |start, end| Span {
@@ -285,6 +377,7 @@ macro_rules! parse_with {
             current_pred_key: $current_pred_key,
             macros: &mut $self.macros,
             macro_calls: &mut $self.macro_calls,
+            doc_comments: $doc_comments,
             span_from: &span_from,
             use_paths: &mut Vec::new(),
             next_paths: &mut next_paths,
@@ -302,6 +395,8 @@ macro_rules! parse_with {
             })
             .unwrap_or_default();
 
+        context.contract.check_min_int_literals(&local_handler);
+
         if let Some((macro_name, macro_span)) = $macro_ctx {
             for err in local_handler.consume().0 {
                 $handler.emit_err(Error::MacroBodyWrapper {
@@ -320,16 +415,21 @@ macro_rules! parse_with {
 
 impl<'a> ProjectParser<'a> {
     fn parse_module(&mut self, src_path: &Rc<Path>, mod_path: &[String]) -> ((), Vec<NextModPath>) {
-        let src_str = fs::read_to_string(src_path).unwrap_or_else(|io_err| {
-            self.handler.emit_err(Error::Compile {
-                error: CompileError::FileIO {
-                    error: io_err,
-                    file: src_path.to_path_buf(),
-                    span: empty_span(),
-                },
-            });
-            String::new()
-        });
+        let src_str = match self.root_src {
+            Some(root_src) if src_path.as_ref() == self.root_src_path.as_path() => {
+                root_src.to_string()
+            }
+            _ => fs::read_to_string(src_path).unwrap_or_else(|io_err| {
+                self.handler.emit_err(Error::Compile {
+                    error: CompileError::FileIO {
+                        error: io_err,
+                        file: src_path.to_path_buf(),
+                        span: empty_span(),
+                    },
+                });
+                String::new()
+            }),
+        };
 
         let mut mod_prefix = mod_path
             .iter()
@@ -338,15 +438,18 @@ impl<'a> ProjectParser<'a> {
             .concat();
         mod_prefix.push_str("");
 
+        let lexer = lexer::Lexer::new(&src_str, src_path, mod_path).with_options(self.options);
+        let doc_comments = lexer.doc_comments();
         parse_with!(
             self,
-            lexer::Lexer::new(&src_str, src_path, mod_path),
+            lexer,
             pint_parser::PintParser::new(),
             src_path,
             mod_path,
             None,                           // local_scope
             Option::<(String, Span)>::None, // macro_ctx
             None,                           // Always use root pred when we explore a new module.
+            &doc_comments,
             self.handler,
         )
     }
@@ -364,32 +467,34 @@ impl<'a> ProjectParser<'a> {
         if macro_call.is_at_decl {
             parse_with!(
                 self,
-                lexer::Lexer::from_tokens(tokens, src_path, mod_path),
+                lexer::Lexer::from_tokens(tokens, src_path, mod_path).with_options(self.options),
                 pint_parser::MacroBodyAtDeclParser::new(),
                 src_path,
                 mod_path,
                 Some(&local_scope),
                 Some((macro_call.name.clone(), macro_call.span.clone())),
                 Some(current_pred),
+                &Default::default(), // Doc comments aren't supported in macro bodies.
                 self.handler,
             )
         } else {
             parse_with!(
                 self,
-                lexer::Lexer::from_tokens(tokens, src_path, mod_path),
+                lexer::Lexer::from_tokens(tokens, src_path, mod_path).with_options(self.options),
                 pint_parser::MacroBodyAtExprParser::new(),
                 src_path,
                 mod_path,
                 Some(&local_scope),
                 Some((macro_call.name.clone(), macro_call.span.clone())),
                 Some(current_pred),
+                &Default::default(), // Doc comments aren't supported in macro bodies.
                 self.handler,
             )
         }
     }
 
     fn analyse_and_add_paths(
-        &self,
+        &mut self,
         mod_path: &[String],
         next_paths: &[NextModPath],
         pending_paths: &mut Vec<(PathBuf, Vec<String>)>,
@@ -413,6 +518,7 @@ impl<'a> ProjectParser<'a> {
             if let Some((_found, next_path)) =
                 self.find_next_path(*is_abs, mod_path_strs, mod_path, span)
             {
+                self.add_mod_import(mod_path, &next_path.1, span);
                 pending_paths.push(next_path);
                 continue;
             }
@@ -421,6 +527,7 @@ impl<'a> ProjectParser<'a> {
                 if let Some((_found, next_path)) =
                     self.find_next_path(*is_abs, enum_path_strs, mod_path, span)
                 {
+                    self.add_mod_import(mod_path, &next_path.1, span);
                     pending_paths.push(next_path);
                     continue;
                 }
@@ -441,6 +548,79 @@ impl<'a> ProjectParser<'a> {
         }
     }
 
+    /// Record that the module at `mod_path` imports from the module at `imported_mod_path`. Only
+    /// the first import between any two modules is kept, and references to the module itself are
+    /// ignored.
+    fn add_mod_import(&mut self, mod_path: &[String], imported_mod_path: &[String], span: &Span) {
+        if mod_path != imported_mod_path {
+            self.mod_imports
+                .entry(mod_path.to_vec())
+                .or_default()
+                .entry(imported_mod_path.to_vec())
+                .or_insert_with(|| span.clone());
+        }
+    }
+
+    /// Check the module import graph for cycles using a depth first search, emitting an error for
+    /// each cycle found.
+    fn check_import_cycles(&self) {
+        let mut visited = FxHashSet::default();
+        let mut stack = Vec::new();
+        let mut in_stack = FxHashSet::default();
+        for mod_path in self.mod_imports.keys() {
+            self.find_import_cycles(mod_path, &mut visited, &mut stack, &mut in_stack);
+        }
+    }
+
+    fn find_import_cycles<'b>(
+        &'b self,
+        mod_path: &'b [String],
+        visited: &mut FxHashSet<&'b [String]>,
+        stack: &mut Vec<&'b [String]>,
+        in_stack: &mut FxHashSet<&'b [String]>,
+    ) {
+        if !visited.insert(mod_path) {
+            return;
+        }
+
+        stack.push(mod_path);
+        in_stack.insert(mod_path);
+
+        for (imported_mod_path, span) in self.mod_imports.get(mod_path).into_iter().flatten() {
+            if in_stack.contains(imported_mod_path.as_slice()) {
+                // The cycle is the part of the stack starting from the imported module, closed off
+                // by the imported module again.
+                let cycle_start = stack
+                    .iter()
+                    .position(|el| *el == imported_mod_path.as_slice())
+                    .expect("module must be on the stack");
+
+                self.handler.emit_err(Error::Compile {
+                    error: CompileError::CyclicImport {
+                        cycle: stack[cycle_start..]
+                            .iter()
+                            .copied()
+                            .chain(std::iter::once(imported_mod_path.as_slice()))
+                            .map(|mod_path| {
+                                mod_path
+                                    .iter()
+                                    .map(|el| format!("::{el}"))
+                                    .collect::<Vec<_>>()
+                                    .concat()
+                            })
+                            .collect(),
+                        span: span.clone(),
+                    },
+                });
+            } else {
+                self.find_import_cycles(imported_mod_path, visited, stack, in_stack);
+            }
+        }
+
+        in_stack.remove(mod_path);
+        stack.pop();
+    }
+
     /// Given a next module path and a path to the current module, decide on a file in the project
     /// to actually parse.
     fn find_next_path(
@@ -465,33 +645,38 @@ impl<'a> ProjectParser<'a> {
             next_mod_path.push(m.clone());
         }
 
-        // An alternative next path includes a folder with the same name as the module.
-        let alternative_next_path = next_path
-            .join(next_mod_path.last().unwrap_or(&String::new()))
-            .with_extension("pnt");
-
-        next_path.set_extension("pnt");
-
-        // Check if the paths actually exist.
-        let next_path = next_path.exists().then_some(next_path);
-        let alternative_next_path = alternative_next_path
-            .exists()
-            .then_some(alternative_next_path);
-
-        // The path might refer to an external dependency.
-        let dep_next_path = self.find_dep_path(path_strs);
+        // Local modules are resolved relative to the project root, considering the following
+        // files in order:
+        //
+        // 1. `a.pnt`,
+        // 2. `a/a.pnt`, a folder with the same name as the module,
+        // 3. `a/mod.pnt`, a folder containing a `mod.pnt` file.
+        let mod_name = next_mod_path.last().cloned().unwrap_or_default();
+        let local_paths = [
+            next_path.with_extension("pnt"),
+            next_path.join(&mod_name).with_extension("pnt"),
+            next_path.join("mod.pnt"),
+        ];
+
+        // Check which of the paths actually exist.  The path might also refer to an external
+        // dependency.
+        let mut found_paths = local_paths
+            .into_iter()
+            .filter(|path| path.exists())
+            .map(|path| (FoundPath::Local, path))
+            .chain(
+                self.find_dep_path(path_strs)
+                    .map(|path| (FoundPath::Dep, path)),
+            );
 
         // Determine which of the file paths above to actually parse. If two files exist,
         // that's an error. If only 1 exists, return that one. If no paths exist, simply return
         // None.
-        let next_path_opt = match (next_path, alternative_next_path, dep_next_path) {
-            (None, None, None) => None,
-            // One of the local paths exist.
-            (Some(p), None, None) | (None, Some(p), None) => Some((FoundPath::Local, p)),
-            // The dependency path exists.
-            (None, None, Some(p)) => Some((FoundPath::Dep, p)),
+        let next_path_opt = match (found_paths.next(), found_paths.next()) {
+            (None, _) => None,
+            (Some(found), None) => Some(found),
             // Two or more potential paths exist, meaning we have an ambiguity.
-            (Some(a), Some(b), _) | (Some(a), _, Some(b)) | (_, Some(a), Some(b)) => {
+            (Some((_, a)), Some((_, b))) => {
                 self.handler.emit_err(Error::Compile {
                     error: CompileError::DualModulity {
                         mod_path: next_mod_path.join("::"),
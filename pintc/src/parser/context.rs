@@ -1,6 +1,6 @@
 use crate::{
     error::{Error, Handler, ParseError},
-    expr::{BinaryOp, Expr, ExternalIntrinsic, Immediate, IntrinsicKind, TupleAccess},
+    expr::{BinaryOp, Expr, ExternalIntrinsic, Immediate, IntrinsicKind, TupleAccess, UnaryOp},
     macros::{MacroCall, MacroDecl},
     parser::{Ident, NextModPath, UsePath, UseTree},
     predicate::{
@@ -10,7 +10,7 @@ use crate::{
     span::{self, Span},
     types::{PrimitiveKind, Type},
 };
-use std::collections::BTreeMap;
+use std::{cell::RefCell, collections::BTreeMap};
 
 pub struct ParserContext<'a> {
     pub(crate) mod_path: &'a [String],
@@ -21,13 +21,33 @@ pub struct ParserContext<'a> {
     pub(crate) macros: &'a mut Vec<MacroDecl>,
     pub(crate) macro_calls:
         &'a mut BTreeMap<PredKey, slotmap::SecondaryMap<CallKey, (ExprKey, MacroCall)>>,
+    pub(crate) doc_comments: &'a RefCell<BTreeMap<usize, String>>,
     pub(crate) span_from: &'a dyn Fn(usize, usize) -> Span,
     pub(crate) use_paths: &'a mut Vec<UsePath>,
     pub(crate) next_paths: &'a mut Vec<NextModPath>,
     pub(crate) experimental_types: bool,
 }
 
+/// A `match` branch as it is parsed, along with the span of its pattern, before the catch-all
+/// branch is separated out from the others.
+pub enum ParsedMatchBranch<B, E> {
+    /// A branch for a single union variant.
+    Variant(B, Span),
+    /// A wildcard `_` or `else` branch, which matches any value.
+    CatchAll(E, Span),
+}
+
 impl<'a> ParserContext<'a> {
+    /// Returns the doc comment for a declaration spanning from `l`, if there's a doc comment
+    /// block directly before a token starting anywhere from `l` up to and including `r`.
+    pub fn doc_comment(&self, l: usize, r: usize) -> Option<String> {
+        self.doc_comments
+            .borrow()
+            .range(l..=r)
+            .next()
+            .map(|(_, doc)| doc.clone())
+    }
+
     pub fn add_top_level_symbol(
         &mut self,
         handler: &Handler,
@@ -297,6 +317,13 @@ impl<'a> ParserContext<'a> {
                 .expect("can only parse vars within predicates")
                 .insert_var(handler, mod_prefix, name.1, is_pub, &name.0, ty)
                 .map(|(var_key, var_full_name)| {
+                    if let Some(doc) = self.doc_comment(l, name.0.span.start()) {
+                        self.current_pred()
+                            .expect("can only parse vars within predicates")
+                            .var_docs
+                            .insert(var_key, doc);
+                    }
+
                     if let Some(expr_key) = init {
                         self.current_pred()
                             .expect("can only parse vars within predicates")
@@ -677,8 +704,15 @@ impl<'a> ParserContext<'a> {
         Expr::IntrinsicCall {
             kind: (
                 match &name.name[..] {
+                    "__abs" => IntrinsicKind::External(ExternalIntrinsic::Abs),
                     "__address_of" => IntrinsicKind::External(ExternalIntrinsic::AddressOf),
                     "__predicate_at" => IntrinsicKind::External(ExternalIntrinsic::PredicateAt),
+                    "__length" => IntrinsicKind::External(ExternalIntrinsic::Length),
+                    "__max" => IntrinsicKind::External(ExternalIntrinsic::Max),
+                    "__min" => IntrinsicKind::External(ExternalIntrinsic::Min),
+                    "__recover_address" => {
+                        IntrinsicKind::External(ExternalIntrinsic::RecoverAddress)
+                    }
                     "__recover_secp256k1" => {
                         IntrinsicKind::External(ExternalIntrinsic::RecoverSECP256k1)
                     }
@@ -688,9 +722,13 @@ impl<'a> ParserContext<'a> {
                     "__this_contract_address" => {
                         IntrinsicKind::External(ExternalIntrinsic::ThisContractAddress)
                     }
+                    "__this_contract_salt" => {
+                        IntrinsicKind::External(ExternalIntrinsic::ThisContractSalt)
+                    }
                     "__this_pathway" => IntrinsicKind::External(ExternalIntrinsic::ThisPathway),
                     "__vec_len" => IntrinsicKind::External(ExternalIntrinsic::VecLen),
                     "__verify_ed25519" => IntrinsicKind::External(ExternalIntrinsic::VerifyEd25519),
+                    "__word" => IntrinsicKind::External(ExternalIntrinsic::Word),
                     _ => {
                         handler.emit_err(Error::Parse {
                             error: ParseError::MissingIntrinsic {
@@ -863,6 +901,105 @@ impl<'a> ParserContext<'a> {
         }
     }
 
+    /// Given a String that represents an integer literal, produce an `Expr::Immediate` and insert
+    /// it into the contract. Hexadecimal and binary literals which fit in an `int` are recorded so
+    /// that they may later be zero-extended to a `b256` where one is expected. `l` and `r` are the
+    /// code locations before and after the integer literal.
+    pub fn parse_int_literal(
+        &mut self,
+        handler: &Handler,
+        s: String,
+        (l, r): (usize, usize),
+    ) -> ExprKey {
+        let is_radix = s.starts_with("0x") || s.starts_with("0b");
+
+        // The magnitude of `i64::MIN` doesn't fit in an `int` on its own, but it's valid when it's
+        // negated. Parse it as `i64::MIN` for now and report it later if no negation shows up.
+        let is_min_int = s.replace('_', "") == "9223372036854775808";
+        let value = if is_min_int {
+            Immediate::Int(i64::MIN)
+        } else {
+            self.parse_int_immediate(handler, s, (l, r))
+        };
+        let is_radix_int = is_radix && matches!(value, Immediate::Int(_));
+
+        let span = (self.span_from)(l, r);
+        let expr_key = self.contract.exprs.insert(
+            Expr::Immediate {
+                value,
+                span: span.clone(),
+            },
+            Type::Unknown(span),
+        );
+
+        if is_radix_int {
+            self.contract.add_radix_int_literal(expr_key);
+        }
+
+        if is_min_int {
+            self.contract.add_min_int_literal(expr_key, span);
+        }
+
+        expr_key
+    }
+
+    /// Produce a `UnaryOp` expression with operator `op` applied to `expr`. A negation applied
+    /// directly to an integer literal is folded into a single negative integer literal. `l` and
+    /// `r` are the code locations before and after the whole expression.
+    pub fn parse_unary_op(
+        &mut self,
+        op: UnaryOp,
+        expr: ExprKey,
+        (l, r): (usize, usize),
+    ) -> ExprKey {
+        let span = (self.span_from)(l, r);
+
+        if let (
+            UnaryOp::Neg,
+            Some(Expr::Immediate {
+                value: Immediate::Int(val),
+                ..
+            }),
+        ) = (op, self.contract.exprs.get(expr))
+        {
+            // A literal `9223372036854775808` and a hex or binary literal with the sign bit set
+            // both negate to a valid `int`.  Otherwise, negating `i64::MIN` overflows, e.g. in
+            // `-(-9223372036854775808)`, and is reported like a too large literal.
+            let val = *val;
+            let is_min_int = self.contract.remove_min_int_literal(expr);
+            let is_radix_int = self.contract.remove_radix_int_literal(expr);
+            let negated = if is_min_int || is_radix_int {
+                Some(val.wrapping_neg())
+            } else {
+                val.checked_neg()
+            };
+            self.contract.exprs.remove(expr);
+
+            let expr_key = self.contract.exprs.insert(
+                Expr::Immediate {
+                    value: Immediate::Int(negated.unwrap_or(val)),
+                    span: span.clone(),
+                },
+                Type::Unknown(span.clone()),
+            );
+
+            if negated.is_none() {
+                self.contract.add_min_int_literal(expr_key, span);
+            }
+
+            return expr_key;
+        }
+
+        self.contract.exprs.insert(
+            Expr::UnaryOp {
+                op,
+                expr,
+                span: span.clone(),
+            },
+            Type::Unknown(span),
+        )
+    }
+
     /// Given a String that represents an integer literal, produce an `Immediate`. The integer can
     /// be in any form (decimal, hex, or binary). `l` and `r` are the code locations before and
     /// after the integer literal.
@@ -890,13 +1027,15 @@ impl<'a> ParserContext<'a> {
                             Immediate::Int(i64::from_str_radix(&s[2..], 2).unwrap())
                         }
                     }
-                    256 => {
-                        let offset = 2;
+                    65..=256 => {
+                        // Too long for an `int`, so this is a `b256`, left-padded with zeros if
+                        // fewer than 256 digits are specified.
+                        let s = format!("{:0>256}", &s[2..]);
                         Immediate::B256([
-                            u64::from_str_radix(&s[offset..offset + 64], 2).unwrap(),
-                            u64::from_str_radix(&s[offset + 64..offset + 128], 2).unwrap(),
-                            u64::from_str_radix(&s[offset + 128..offset + 192], 2).unwrap(),
-                            u64::from_str_radix(&s[offset + 192..offset + 256], 2).unwrap(),
+                            u64::from_str_radix(&s[0..64], 2).unwrap(),
+                            u64::from_str_radix(&s[64..128], 2).unwrap(),
+                            u64::from_str_radix(&s[128..192], 2).unwrap(),
+                            u64::from_str_radix(&s[192..256], 2).unwrap(),
                         ])
                     }
                     _ => {
@@ -927,13 +1066,15 @@ impl<'a> ParserContext<'a> {
                             _ => Immediate::Int(i64::from_str_radix(&s[2..], 16).unwrap()),
                         }
                     }
-                    64 => {
-                        let offset = 2;
+                    17..=64 => {
+                        // Too long for an `int`, so this is a `b256`, left-padded with zeros if
+                        // fewer than 64 digits are specified.
+                        let s = format!("{:0>64}", &s[2..]);
                         Immediate::B256([
-                            u64::from_str_radix(&s[offset..offset + 16], 16).unwrap(),
-                            u64::from_str_radix(&s[offset + 16..offset + 32], 16).unwrap(),
-                            u64::from_str_radix(&s[offset + 32..offset + 48], 16).unwrap(),
-                            u64::from_str_radix(&s[offset + 48..offset + 64], 16).unwrap(),
+                            u64::from_str_radix(&s[0..16], 16).unwrap(),
+                            u64::from_str_radix(&s[16..32], 16).unwrap(),
+                            u64::from_str_radix(&s[32..48], 16).unwrap(),
+                            u64::from_str_radix(&s[48..64], 16).unwrap(),
                         ])
                     }
                     _ => {
@@ -1003,4 +1144,58 @@ impl<'a> ParserContext<'a> {
             }
         }
     }
+
+    /// Produce the catch-all branch for a wildcard `_` pattern in a `match`.  A wildcard cannot
+    /// bind a value, so a `binding` for one is reported as an error.
+    pub fn parse_match_wildcard<B, E>(
+        &self,
+        handler: &Handler,
+        binding: Option<Ident>,
+        branch: E,
+        span: Span,
+    ) -> ParsedMatchBranch<B, E> {
+        if let Some(binding) = binding {
+            handler.emit_err(Error::Parse {
+                error: ParseError::MatchWildcardBinding { span: binding.span },
+            });
+        }
+        ParsedMatchBranch::CatchAll(branch, span)
+    }
+
+    /// Split the parsed branches of a `match`, followed by its optional `else` branch, into the
+    /// variant branches and the catch-all branch.  The catch-all is the first wildcard `_` branch
+    /// or, if there is none, the `else` branch.  Any branches following a wildcard are never
+    /// reached and are reported as errors.
+    pub fn split_match_branches<B, E>(
+        &self,
+        handler: &Handler,
+        branches: Vec<ParsedMatchBranch<B, E>>,
+        else_branch: Option<(E, Span)>,
+    ) -> (Vec<B>, Option<E>) {
+        let mut variant_branches = Vec::new();
+        let mut catch_all: Option<(E, Span)> = None;
+        for branch in branches.into_iter().chain(
+            else_branch.map(|(else_branch, span)| ParsedMatchBranch::CatchAll(else_branch, span)),
+        ) {
+            match (branch, &catch_all) {
+                (ParsedMatchBranch::Variant(branch, _), None) => variant_branches.push(branch),
+                (ParsedMatchBranch::CatchAll(branch, span), None) => {
+                    catch_all = Some((branch, span))
+                }
+                (
+                    ParsedMatchBranch::Variant(_, span) | ParsedMatchBranch::CatchAll(_, span),
+                    Some((_, wildcard_span)),
+                ) => {
+                    handler.emit_err(Error::Parse {
+                        error: ParseError::UnreachableMatchBranch {
+                            span,
+                            wildcard_span: wildcard_span.clone(),
+                        },
+                    });
+                }
+            }
+        }
+
+        (variant_branches, catch_all.map(|(branch, _)| branch))
+    }
 }
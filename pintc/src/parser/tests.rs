@@ -30,6 +30,7 @@ macro_rules! parse_and_collect_errors {
             lexer::Lexer::new($source, &filepath, &[]),
         ) {
             Ok(result) => {
+                $context.contract.check_min_int_literals(&handler);
                 if handler.has_errors() {
                     Err(handler.consume().0)
                 } else {
@@ -61,6 +62,7 @@ macro_rules! context {
             current_pred_key: None,
             macros: &mut vec![],
             macro_calls: &mut BTreeMap::default(),
+            doc_comments: &Default::default(),
             span_from: &|l, r| Span::new(Rc::from(Path::new("")), l..r),
             use_paths: &mut $use_paths,
             next_paths: &mut vec![],
@@ -305,6 +307,38 @@ fn immediates() {
         &run_parser!(immediate, "1_000"),
         expect_test::expect!["1000"],
     );
+    check(
+        &run_parser!(immediate, "1_000_000"),
+        expect_test::expect!["1000000"],
+    );
+    check(
+        &run_parser!(immediate, "0xDEAD_BEEF"),
+        expect_test::expect!["3735928559"],
+    );
+    check(
+        &run_parser!(immediate, "0b1010_1010"),
+        expect_test::expect!["170"],
+    );
+    check(
+        &run_parser!(immediate, "3.141_592"),
+        expect_test::expect!["3.141592e0"],
+    );
+    check(&run_parser!(immediate, "1e5"), expect_test::expect!["1e5"]);
+    check(
+        &run_parser!(immediate, "1.5e-3"),
+        expect_test::expect!["1.5e-3"],
+    );
+    check(
+        &run_parser!(immediate, "2E10"),
+        expect_test::expect!["2e10"],
+    );
+    check(
+        &run_parser!(immediate, "1__000"),
+        expect_test::expect![[r#"
+            invalid token
+            @11..17: tokenization failure, unmatched input
+        "#]],
+    );
 
     // i64 hex literals
     check(
@@ -428,16 +462,18 @@ fn immediates() {
         expect_test::expect!["0x8000000000000000000000000000000000000000000000000000000000000003"],
     );
 
-    // Bad lengths
+    // b256 literals which are too long for an `int` are zero-extended
     check(
         &run_parser!(immediate, "0x4f3f4f3f4f3f4f3f4f3f4f3f4f"),
-        expect_test::expect![[r#"
-            unexpected hexadecimal integer literal length
-            @11..39: 26 is not a valid number of digits in a hexadecimal integer literal
-            number of digits must be either 64 or between 1 and 16
-        "#]],
+        expect_test::expect!["0x000000000000000000000000000000000000004F3F4F3F4F3F4F3F4F3F4F3F4F"],
     );
 
+    check(
+        &run_parser!(immediate, "0b1000000000000000000000000000000000000000000000000000000000000000000000000000000000000"),
+        expect_test::expect!["0x0000000000000000000000000000000000000000001000000000000000000000"],
+    );
+
+    // Bad lengths
     check(
         &run_parser!(
             immediate,
@@ -446,25 +482,16 @@ fn immediates() {
         expect_test::expect![[r#"
             unexpected hexadecimal integer literal length
             @11..78: 65 is not a valid number of digits in a hexadecimal integer literal
-            number of digits must be either 64 or between 1 and 16
+            number of digits must be between 1 and 64
         "#]],
     );
 
-    check(
-            &run_parser!(immediate, "0b1000000000000000000000000000000000000000000000000000000000000000000000000000000000000"),
-            expect_test::expect![[r#"
-                unexpected binary integer literal length
-                @11..98: 85 is not a valid number of digits in a binary integer literal
-                number of digits must be either 256 or between 1 and 64
-            "#]],
-        );
-
     check(
         &run_parser!(immediate, "0b11000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000011"),
         expect_test::expect![[r#"
             unexpected binary integer literal length
             @11..270: 257 is not a valid number of digits in a binary integer literal
-            number of digits must be either 256 or between 1 and 64
+            number of digits must be between 1 and 256
         "#]],
     );
 
@@ -1368,6 +1395,14 @@ fn const_decls() {
         &run_parser!(pint, "const y = 22;"),
         expect_test::expect!["const ::y = 22;"],
     );
+    check(
+        &run_parser!(pint, "const n: int[2] = [1, 2 * 3];"),
+        expect_test::expect!["const ::n: int[2] = [1, (2 * 3)];"],
+    );
+    check(
+        &run_parser!(pint, "const m: bool[n] = [true, false];"),
+        expect_test::expect!["const ::m: bool[::n] = [true, false];"],
+    );
     check(
         &run_parser!(pint, "const z: int;"),
         expect_test::expect![[r#"
@@ -1489,6 +1524,37 @@ fn if_decls() {
                 }
             }"#]],
     );
+
+    // An `else if` chain is sugar for an `else` block holding a single nested `if`.
+    let chained = run_parser!(
+        pint,
+        r#"predicate test {
+            if a { constraint x == 1; } else if b { constraint x == 2; } else { constraint x == 3; }
+        }"#
+    );
+    let nested = run_parser!(
+        pint,
+        r#"predicate test {
+            if a { constraint x == 1; } else { if b { constraint x == 2; } else { constraint x == 3; } }
+        }"#
+    );
+    assert_eq!(chained, nested);
+    check(
+        &chained,
+        expect_test::expect![[r#"
+
+            predicate ::test {
+                if ::a {
+                    constraint (::x == 1)
+                } else {
+                    if ::b {
+                        constraint (::x == 2)
+                    } else {
+                        constraint (::x == 3)
+                    }
+                }
+            }"#]],
+    );
 }
 
 #[test]
@@ -1512,11 +1578,47 @@ fn unary_op_exprs() {
             expr,
             "-0b1101000000001100101010101010101010101010101010101101010101010101"
         ),
-        expect_test::expect!["--3455199164115528363"],
+        expect_test::expect!["3455199164115528363"],
     );
     check(
         &run_parser!(expr, "! - - !  --  -1"),
-        expect_test::expect!["!--!---1"],
+        expect_test::expect!["!--!-1"],
+    );
+    check(
+        &run_parser!(expr, "-9223372036854775808"),
+        expect_test::expect!["-9223372036854775808"],
+    );
+    check(
+        &run_parser!(expr, "-9_223_372_036_854_775_808"),
+        expect_test::expect!["-9223372036854775808"],
+    );
+    check(
+        &run_parser!(expr, "-0x8000000000000000"),
+        expect_test::expect!["-9223372036854775808"],
+    );
+    check(
+        &run_parser!(expr, "1 - 9223372036854775808"),
+        expect_test::expect![[r#"
+            integer literal is too large
+            @15..34: integer literal is too large
+            value exceeds limit of `9,223,372,036,854,775,807`
+        "#]],
+    );
+    check(
+        &run_parser!(expr, "-(-9223372036854775808)"),
+        expect_test::expect![[r#"
+            integer literal is too large
+            @11..34: integer literal is too large
+            value exceeds limit of `9,223,372,036,854,775,807`
+        "#]],
+    );
+    check(
+        &run_parser!(expr, "-(-0x8000000000000000)"),
+        expect_test::expect![[r#"
+            integer literal is too large
+            @11..33: integer literal is too large
+            value exceeds limit of `9,223,372,036,854,775,807`
+        "#]],
     );
     check(
         &run_parser!(expr, "! - - !  --  -t.0.1.2"),
@@ -1554,6 +1656,90 @@ fn unary_op_exprs() {
     );
 }
 
+#[test]
+fn deeply_nested_exprs() {
+    let parse = |src: &str, max_nesting_depth| {
+        let options = super::ParseOptions {
+            max_nesting_depth,
+            ..Default::default()
+        };
+        let handler = Handler::default();
+        let deps = Default::default();
+        let res = super::parse_project_from_str_with_options(
+            &handler,
+            &deps,
+            Path::new("<string>"),
+            src,
+            &options,
+        );
+        res.map(|_| ()).map_err(|_| {
+            handler
+                .consume()
+                .0
+                .iter()
+                .map(|err| err.display_raw())
+                .collect::<String>()
+        })
+    };
+
+    // Parentheses don't nest expressions, so any number of them is fine.
+    let depth = super::DEFAULT_MAX_NESTING_DEPTH * 2;
+    let src = format!("const a = {}1{};", "(".repeat(depth), ")".repeat(depth));
+    assert!(parse(&src, super::DEFAULT_MAX_NESTING_DEPTH).is_ok());
+
+    // Nesting up to the limit is fine, anything deeper is rejected cleanly rather than
+    // overflowing the stack in later passes.
+    assert!(parse("const a = b.0.0.0;", 4).is_ok());
+    check(
+        &parse("const a = b.0.0.0.0.0;", 4).unwrap_err(),
+        expect_test::expect![[r#"
+            expression is nested too deeply
+            @10..19: nesting exceeds the maximum depth of 4
+            consider splitting the expression up using intermediate variables
+        "#]],
+    );
+
+    assert!(parse("const a = !!!x;", 4).is_ok());
+    check(
+        &parse("const a = !!!!!x;", 4).unwrap_err(),
+        expect_test::expect![[r#"
+            expression is nested too deeply
+            @11..16: nesting exceeds the maximum depth of 4
+            consider splitting the expression up using intermediate variables
+        "#]],
+    );
+
+    check(
+        &parse("const a = 1 + 1 + 1 + 1 + 1;", 4).unwrap_err(),
+        expect_test::expect![[r#"
+            expression is nested too deeply
+            @10..27: nesting exceeds the maximum depth of 4
+            consider splitting the expression up using intermediate variables
+        "#]],
+    );
+
+    check(
+        &parse("const a = [[[b]]];", 2).unwrap_err(),
+        expect_test::expect![[r#"
+            expression is nested too deeply
+            @11..16: nesting exceeds the maximum depth of 2
+            consider splitting the expression up using intermediate variables
+        "#]],
+    );
+
+    // The default limit allows deep but reasonable chains.
+    let src = format!(
+        "const a = b{};",
+        ".0".repeat(super::DEFAULT_MAX_NESTING_DEPTH - 1)
+    );
+    assert!(parse(&src, super::DEFAULT_MAX_NESTING_DEPTH).is_ok());
+    let src = format!(
+        "const a = b{};",
+        ".0".repeat(super::DEFAULT_MAX_NESTING_DEPTH)
+    );
+    assert!(parse(&src, super::DEFAULT_MAX_NESTING_DEPTH).is_err());
+}
+
 #[test]
 fn binary_op_exprs() {
     let expr = (yp::TestDelegateParser::new(), "expr");
@@ -1624,6 +1810,51 @@ fn binary_op_exprs() {
         &run_parser!(expr, "a || b && c || d && !e"),
         expect_test::expect!["((::a || (::b && ::c)) || (::d && !::e))"],
     );
+
+    check(
+        &run_parser!(expr, "a & 2"),
+        expect_test::expect!["(::a & 2)"],
+    );
+    check(
+        &run_parser!(expr, "a | 2"),
+        expect_test::expect!["(::a | 2)"],
+    );
+    check(
+        &run_parser!(expr, "a ^ 2"),
+        expect_test::expect!["(::a ^ 2)"],
+    );
+    check(
+        &run_parser!(expr, "a << 2"),
+        expect_test::expect!["(::a << 2)"],
+    );
+    check(
+        &run_parser!(expr, "a >> 2"),
+        expect_test::expect!["(::a >> 2)"],
+    );
+    check(
+        &run_parser!(expr, "a | b ^ c & d"),
+        expect_test::expect!["(::a | (::b ^ (::c & ::d)))"],
+    );
+    check(
+        &run_parser!(expr, "a & b == c && d | e"),
+        expect_test::expect!["((::a & (::b == ::c)) && (::d | ::e))"],
+    );
+    check(
+        &run_parser!(expr, "a << b + 1 < c >> 2"),
+        expect_test::expect!["((::a << (::b + 1)) < (::c >> 2))"],
+    );
+    check(
+        &run_parser!(expr, "a ** 2"),
+        expect_test::expect!["(::a ** 2)"],
+    );
+    check(
+        &run_parser!(expr, "a ** b ** c"),
+        expect_test::expect!["(::a ** (::b ** ::c))"],
+    );
+    check(
+        &run_parser!(expr, "2 * a ** 3 + b"),
+        expect_test::expect!["((2 * (::a ** 3)) + ::b)"],
+    );
 }
 
 #[test]
@@ -1941,8 +2172,8 @@ fn ranges() {
     check(
         &run_parser!(expr, "(1..2) + 3"),
         expect_test::expect![[r#"
-            expected `!=`, `&&`, `)`, `+`, `-`, `<`, `<=`, `==`, `>`, `>=`, `?`, `in`, or `||`, found `..`
-            @13..15: expected `!=`, `&&`, `)`, `+`, `-`, `<`, `<=`, `==`, `>`, `>=`, `?`, `in`, or `||`
+            expected `!=`, `&`, `&&`, `)`, `+`, `-`, `<`, `<<`, `<=`, `==`, `>`, `>=`, `>>`, `?`, `^`, `in`, `|`, or `||`, found `..`
+            @13..15: expected `!=`, `&`, `&&`, `)`, `+`, `-`, `<`, `<<`, `<=`, `==`, `>`, `>=`, `>>`, `?`, `^`, `in`, `|`, or `||`
         "#]],
     );
 }
@@ -2315,6 +2546,41 @@ fn array_element_accesses() {
     );
 }
 
+#[test]
+fn array_slices() {
+    let expr = (yp::TestDelegateParser::new(), "expr");
+
+    check(
+        &run_parser!(expr, r#"a[1..3]"#),
+        expect_test::expect!["::a[1..3]"],
+    );
+
+    check(
+        &run_parser!(expr, r#"a[N][0..M + 1][2]"#),
+        expect_test::expect!["::a[::N][0..(::M + 1)][2]"],
+    );
+
+    check(
+        &run_parser!(expr, r#"[1, 2, 3][0..2]"#),
+        expect_test::expect!["[1, 2, 3][0..2]"],
+    );
+}
+
+#[test]
+fn range_arrays() {
+    let expr = (yp::TestDelegateParser::new(), "expr");
+
+    check(
+        &run_parser!(expr, r#"[0..3]"#),
+        expect_test::expect!["[0..3]"],
+    );
+
+    check(
+        &run_parser!(expr, r#"[N - 1..N + 1][0]"#),
+        expect_test::expect!["[(::N - 1)..(::N + 1)][0]"],
+    );
+}
+
 #[test]
 fn tuple_expressions() {
     let expr = (yp::TestDelegateParser::new(), "expr");
@@ -2550,6 +2816,14 @@ fn tuple_field_accesses() {
         "#]],
     );
 
+    check(
+        &run_parser!(pint, "predicate test { var x = t.2E10; }"),
+        expect_test::expect![[r#"
+            invalid value `2E10` as tuple index
+            @27..31: invalid value as tuple index
+        "#]],
+    );
+
     check(
         &run_parser!(pint, "predicate test { var bad_tuple:{} = {}; }"),
         expect_test::expect![[r#"
@@ -2912,6 +3186,39 @@ fn intrinsic_call() {
         &run_parser!(expr, "__this_address(-a, b+c)"),
         expect_test::expect!["__this_address(-::a, (::b + ::c))"],
     );
+
+    check(
+        &run_parser!(expr, "__this_address(a,)"),
+        expect_test::expect!["__this_address(::a)"],
+    );
+
+    check(
+        &run_parser!(expr, "__this_address(,)"),
+        expect_test::expect![[r#"
+            leading `,` is not allowed in an argument list
+            @26..27: unexpected `,`
+            try removing the `,`
+        "#]],
+    );
+
+    check(
+        &run_parser!(expr, "__this_address(, a, b)"),
+        expect_test::expect![[r#"
+            leading `,` is not allowed in an argument list
+            @26..27: unexpected `,`
+            try removing the `,`
+        "#]],
+    );
+
+    check(
+        &run_parser!(expr, "__max(a, __min(b, 2))"),
+        expect_test::expect!["__max(::a, __min(::b, 2))"],
+    );
+
+    check(
+        &run_parser!(expr, "__abs(a - 1)"),
+        expect_test::expect!["__abs((::a - 1))"],
+    );
 }
 
 #[test]
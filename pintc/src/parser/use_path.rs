@@ -118,6 +118,7 @@ fn gather_use_paths() {
                     current_pred_key: None,
                     macros: &mut Vec::default(),
                     macro_calls: &mut BTreeMap::default(),
+                    doc_comments: &Default::default(),
                     span_from: &|_, _| span::empty_span(),
                     use_paths: &mut Vec::default(),
                     next_paths: &mut Vec::default(),
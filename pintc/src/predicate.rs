@@ -1,5 +1,5 @@
 use crate::{
-    error::{Error, ErrorEmitted, Handler, ParseError},
+    error::{CompileError, Error, ErrorEmitted, Handler, ParseError},
     expr::{Expr, Ident, Immediate, MatchBranch, MatchElse},
     span::{empty_span, Span, Spanned},
     types::{EphemeralDecl, NewTypeDecl, Type, UnionDecl, UnionVariant},
@@ -7,18 +7,26 @@ use crate::{
 use exprs::ExprsIter;
 use pint_abi_types::{ContractABI, PredicateABI, VarABI};
 
-use std::fmt::{self, Formatter};
+use std::{
+    fmt::{self, Formatter},
+    time::Instant,
+};
 
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 
 mod analyse;
 mod display;
+mod dot;
 mod exprs;
+mod ir_json;
 mod optimize;
 mod states;
 mod transform;
 mod vars;
 
+#[cfg(test)]
+mod tests;
+
 pub(crate) use display::{DisplayWithContract, DisplayWithPred};
 pub use exprs::{ExprKey, Exprs};
 pub use states::{State, StateKey, States};
@@ -42,18 +50,78 @@ pub struct Contract {
     pub new_types: Vec<NewTypeDecl>,
 
     removed_macro_calls: slotmap::SecondaryMap<ExprKey, Span>,
+    radix_int_literals: FxHashSet<ExprKey>,
+    min_int_literals: slotmap::SecondaryMap<ExprKey, Span>,
     pub(crate) symbols: SymbolTable,
 }
 
+/// The default number of fractional bits used to encode `real` values as fixed-point integers.
+pub const DEFAULT_REAL_SCALE_BITS: u32 = 32;
+
+/// The maximum number of fractional bits used to encode `real` values.  The scale is `2^bits` and
+/// must fit in an `i64`.
+pub const MAX_REAL_SCALE_BITS: u32 = 62;
+
 pub struct CompileOptions {
     pub skip_optimize: bool,
     pub print_flat: bool,
+    /// The number of fractional bits used to encode `real` values as fixed-point integers. More
+    /// bits give more precision but less range. Must be at most `MAX_REAL_SCALE_BITS`.
+    pub real_scale_bits: u32,
+    /// Inline single-use initialised variables before optimizing. This removes decision
+    /// variables, so solutions must omit values for any variable which has been inlined.
+    pub inline_vars: bool,
+    /// The salt of the contract, which contributes to its address and is returned by
+    /// `__this_contract_salt()`.
+    pub salt: [u8; 32],
+    /// Record a note describing the source construct behind each op, where known, for
+    /// `CompiledContract::explain_asm()`.
+    pub record_asm_notes: bool,
+    /// Print how long each compilation phase takes to stderr.
+    pub time_phases: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            skip_optimize: false,
+            print_flat: false,
+            real_scale_bits: DEFAULT_REAL_SCALE_BITS,
+            inline_vars: false,
+            salt: [0; 32],
+            record_asm_notes: false,
+            time_phases: false,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Print how long the compilation `phase` which began at `start` took, if `time_phases` is
+    /// set.
+    pub fn report_time(&self, phase: &str, start: Instant) {
+        if self.time_phases {
+            eprintln!("{phase}: {:?}", start.elapsed());
+        }
+    }
 }
 
 impl Contract {
-    pub fn compile(self, handler: &Handler, options: CompileOptions) -> Result<Self, ErrorEmitted> {
-        let type_checked = handler.scope(|handler| self.type_check(handler))?;
-        let flattened = handler.scope(|handler| type_checked.flatten(handler))?;
+    pub fn compile(
+        self,
+        handler: &Handler,
+        options: &CompileOptions,
+    ) -> Result<Self, ErrorEmitted> {
+        let start = Instant::now();
+        let type_checked = handler.scope(|handler| self.type_check(handler));
+        options.report_time("type check", start);
+        let type_checked = type_checked?;
+        type_checked.check_unreferenced_pub_vars(handler);
+
+        let start = Instant::now();
+        let flattened = handler.scope(|handler| type_checked.flatten(handler));
+        options.report_time("flatten", start);
+        let flattened = flattened?;
+        flattened.check_unused_vars(handler);
 
         if options.print_flat {
             println!("{flattened}");
@@ -62,30 +130,33 @@ impl Contract {
         if options.skip_optimize {
             Ok(flattened)
         } else {
-            Ok(flattened.optimize(handler))
+            let start = Instant::now();
+            let flattened = if options.inline_vars {
+                flattened.inline_vars()
+            } else {
+                flattened
+            };
+            let optimized = handler.scope(|handler| Ok(flattened.optimize(handler)));
+            options.report_time("optimize", start);
+            optimized
         }
     }
 
+    /// Type check the contract without flattening or optimizing it. This is useful for tools,
+    /// such as editors, which only need diagnostics and must avoid the later compilation stages.
+    pub fn type_check_only(self, handler: &Handler) -> Result<Self, ErrorEmitted> {
+        handler.scope(|handler| self.type_check(handler))
+    }
+
     /// An iterator for all expressions in a predicate.
     pub(crate) fn exprs(&self, pred_key: PredKey) -> ExprsIter {
         ExprsIter::new(self, pred_key)
     }
 
-    /// Visit expression and every sub-expression with a function.
-    fn visitor_from_key(
-        &self,
-        kind: VisitorKind,
-        expr_key: ExprKey,
-        f: &mut impl FnMut(ExprKey, &Expr),
-    ) {
-        let expr = expr_key.get(self);
-
-        if kind == VisitorKind::DepthFirstParentsBeforeChildren {
-            // Visit the parent before recursing.
-            f(expr_key, expr);
-        }
-
-        match expr {
+    /// Call `f` on each immediate sub-expression of an expression, in the order they are visited
+    /// by `visitor_from_key()`.
+    pub(crate) fn for_each_expr_child(&self, expr_key: ExprKey, mut f: impl FnMut(ExprKey)) {
+        match expr_key.get(self) {
             Expr::Error(_)
             | Expr::Path(_, _)
             | Expr::StorageAccess { .. }
@@ -98,36 +169,22 @@ impl Contract {
                 range_expr,
                 ..
             } => {
-                for element in elements {
-                    self.visitor_from_key(kind, *element, f);
-                }
-                self.visitor_from_key(kind, *range_expr, f);
+                elements.iter().copied().for_each(&mut f);
+                f(*range_expr);
             }
 
-            Expr::Tuple { fields, .. } => {
-                for (_, field) in fields {
-                    self.visitor_from_key(kind, *field, f);
-                }
-            }
+            Expr::Tuple { fields, .. } => fields.iter().for_each(|(_, field)| f(*field)),
 
-            Expr::UnionVariant { value, .. } => {
-                if let Some(value) = value {
-                    self.visitor_from_key(kind, *value, f);
-                }
-            }
+            Expr::UnionVariant { value, .. } => value.iter().copied().for_each(&mut f),
 
-            Expr::UnaryOp { expr, .. } => self.visitor_from_key(kind, *expr, f),
+            Expr::UnaryOp { expr, .. } => f(*expr),
 
             Expr::BinaryOp { lhs, rhs, .. } => {
-                self.visitor_from_key(kind, *lhs, f);
-                self.visitor_from_key(kind, *rhs, f);
+                f(*lhs);
+                f(*rhs);
             }
 
-            Expr::IntrinsicCall { args, .. } => {
-                for arg in args {
-                    self.visitor_from_key(kind, *arg, f);
-                }
-            }
+            Expr::IntrinsicCall { args, .. } => args.iter().copied().for_each(&mut f),
 
             Expr::Select {
                 condition,
@@ -135,9 +192,9 @@ impl Contract {
                 else_expr,
                 ..
             } => {
-                self.visitor_from_key(kind, *condition, f);
-                self.visitor_from_key(kind, *then_expr, f);
-                self.visitor_from_key(kind, *else_expr, f);
+                f(*condition);
+                f(*then_expr);
+                f(*else_expr);
             }
 
             Expr::Match {
@@ -146,45 +203,46 @@ impl Contract {
                 else_branch,
                 ..
             } => {
-                self.visitor_from_key(kind, *match_expr, f);
+                f(*match_expr);
                 for MatchBranch {
                     constraints, expr, ..
                 } in match_branches
                 {
-                    for c_expr in constraints {
-                        self.visitor_from_key(kind, *c_expr, f);
-                    }
-                    self.visitor_from_key(kind, *expr, f);
+                    constraints.iter().copied().for_each(&mut f);
+                    f(*expr);
                 }
                 if let Some(MatchElse { constraints, expr }) = else_branch {
-                    for c_expr in constraints {
-                        self.visitor_from_key(kind, *c_expr, f);
-                    }
-                    self.visitor_from_key(kind, *expr, f);
+                    constraints.iter().copied().for_each(&mut f);
+                    f(*expr);
                 }
             }
 
             Expr::Index { expr, index, .. } => {
-                self.visitor_from_key(kind, *expr, f);
-                self.visitor_from_key(kind, *index, f);
+                f(*expr);
+                f(*index);
             }
 
-            Expr::TupleFieldAccess { tuple, .. } => {
-                self.visitor_from_key(kind, *tuple, f);
+            Expr::ArraySlice { array, range, .. } => {
+                f(*array);
+                f(*range);
             }
 
-            Expr::Cast { value, .. } => self.visitor_from_key(kind, *value, f),
+            Expr::RangeArray { range, .. } => f(*range),
+
+            Expr::TupleFieldAccess { tuple, .. } => f(*tuple),
+
+            Expr::Cast { value, .. } => f(*value),
 
             Expr::In {
                 value, collection, ..
             } => {
-                self.visitor_from_key(kind, *value, f);
-                self.visitor_from_key(kind, *collection, f);
+                f(*value);
+                f(*collection);
             }
 
             Expr::Range { lb, ub, .. } => {
-                self.visitor_from_key(kind, *lb, f);
-                self.visitor_from_key(kind, *ub, f);
+                f(*lb);
+                f(*ub);
             }
 
             Expr::Generator {
@@ -193,19 +251,32 @@ impl Contract {
                 body,
                 ..
             } => {
-                for (_, range) in gen_ranges {
-                    self.visitor_from_key(kind, *range, f);
-                }
-                for condition in conditions {
-                    self.visitor_from_key(kind, *condition, f);
-                }
-                self.visitor_from_key(kind, *body, f);
+                gen_ranges.iter().for_each(|(_, range)| f(*range));
+                conditions.iter().copied().for_each(&mut f);
+                f(*body);
             }
 
             Expr::UnionTag { union_expr, .. } | Expr::UnionValue { union_expr, .. } => {
-                self.visitor_from_key(kind, *union_expr, f)
+                f(*union_expr)
             }
         }
+    }
+
+    /// Visit expression and every sub-expression with a function.
+    pub(crate) fn visitor_from_key(
+        &self,
+        kind: VisitorKind,
+        expr_key: ExprKey,
+        f: &mut impl FnMut(ExprKey, &Expr),
+    ) {
+        let expr = expr_key.get(self);
+
+        if kind == VisitorKind::DepthFirstParentsBeforeChildren {
+            // Visit the parent before recursing.
+            f(expr_key, expr);
+        }
+
+        self.for_each_expr_child(expr_key, |child| self.visitor_from_key(kind, child, f));
 
         if kind == VisitorKind::DepthFirstChildrenBeforeParents {
             // Visit the parent after recursing.
@@ -290,6 +361,165 @@ impl Contract {
         }
     }
 
+    /// Merge the contract `other` into `self`, moving over all of its predicates, consts, unions,
+    /// new types, interfaces and storage.  Its expressions and unions are moved into `self` and
+    /// every reference to them is updated with their new keys.
+    ///
+    /// The top level symbols of `other` may not clash with those of `self`, and at most one of
+    /// them may declare storage.  If they do an error is emitted for each clash and nothing is
+    /// merged.
+    pub fn merge(&mut self, mut other: Contract, handler: &Handler) -> Result<(), ErrorEmitted> {
+        handler.scope(|handler| {
+            if let (Some((_, prev_span)), Some((_, span))) = (&self.storage, &other.storage) {
+                handler.emit_err(Error::Parse {
+                    error: ParseError::TooManyStorageBlocks {
+                        span: span.clone(),
+                        prev_span: prev_span.clone(),
+                    },
+                });
+            }
+
+            self.symbols.check_for_clash(handler, &other.symbols)
+        })?;
+
+        // Move the unions over first so that every `Type::Union` and union variant immediate in
+        // `other` can be pointed at their new keys.
+        let union_keys = std::mem::take(&mut other.unions)
+            .into_iter()
+            .map(|(old_key, union_decl)| (old_key, self.unions.insert(union_decl)))
+            .collect::<FxHashMap<_, _>>();
+        other.update_types(|ty| ty.replace_union_keys(&union_keys), false);
+        other.exprs.update_exprs(|_, expr| {
+            if let Expr::Immediate { value, .. } = expr {
+                value.replace_union_keys(&union_keys);
+            }
+        });
+
+        let Contract {
+            preds,
+            exprs,
+            consts,
+            storage,
+            interfaces,
+            unions: _,
+            new_types,
+            removed_macro_calls,
+            radix_int_literals,
+            min_int_literals,
+            symbols,
+        } = other;
+
+        let keys = self.exprs.append(exprs);
+        let remap = |expr: ExprKey| keys.get(&expr).copied().unwrap_or(expr);
+
+        for (_, mut pred) in preds {
+            pred.replace_exprs_by_map(&keys);
+            self.preds.insert(pred);
+        }
+
+        for (name, Const { expr, mut decl_ty }) in consts {
+            decl_ty.replace_type_expr_by_map(&keys);
+            self.consts.insert(
+                name,
+                Const {
+                    expr: remap(expr),
+                    decl_ty,
+                },
+            );
+        }
+
+        for union_key in union_keys.values() {
+            self.unions[*union_key]
+                .variants
+                .iter_mut()
+                .filter_map(|UnionVariant { ty, .. }| ty.as_mut())
+                .for_each(|ty| {
+                    ty.replace_type_expr_by_map(&keys);
+                    ty.replace_union_keys(&union_keys);
+                });
+        }
+
+        for mut new_type in new_types {
+            new_type.ty.replace_type_expr_by_map(&keys);
+            self.new_types.push(new_type);
+        }
+
+        if let Some((mut storage_vars, span)) = storage {
+            storage_vars
+                .iter_mut()
+                .for_each(|StorageVar { ty, .. }| ty.replace_type_expr_by_map(&keys));
+            self.storage = Some((storage_vars, span));
+        }
+
+        for mut interface in interfaces {
+            if let Some((storage_vars, _)) = &mut interface.storage {
+                storage_vars
+                    .iter_mut()
+                    .for_each(|StorageVar { ty, .. }| ty.replace_type_expr_by_map(&keys));
+            }
+            interface
+                .predicate_interfaces
+                .iter_mut()
+                .flat_map(|PredicateInterface { vars, .. }| vars.iter_mut())
+                .for_each(|InterfaceVar { ty, .. }| ty.replace_type_expr_by_map(&keys));
+            self.interfaces.push(interface);
+        }
+
+        for (expr, span) in removed_macro_calls {
+            self.removed_macro_calls.insert(remap(expr), span);
+        }
+        self.radix_int_literals
+            .extend(radix_int_literals.into_iter().map(remap));
+        for (expr, span) in min_int_literals {
+            self.min_int_literals.insert(remap(expr), span);
+        }
+
+        self.symbols.symbols.extend(symbols.symbols);
+
+        Ok(())
+    }
+
+    /// Rename the predicate with the fully qualified name `old_name` (e.g. `::Foo`) to `new_name`,
+    /// updating the top level symbols and every local predicate instance which refers to it.
+    ///
+    /// An error is emitted if no such predicate exists or if `new_name` clashes with an existing
+    /// top level symbol, in which case nothing is renamed.
+    pub fn rename_predicate(
+        &mut self,
+        handler: &Handler,
+        old_name: &str,
+        new_name: String,
+    ) -> Result<(), ErrorEmitted> {
+        let Some((pred_key, _)) = self.predicate_by_name(old_name) else {
+            return Err(handler.emit_err(Error::Compile {
+                error: CompileError::MissingPredicate {
+                    pred_name: old_name.to_string(),
+                    interface_name: None,
+                    span: empty_span(),
+                },
+            }));
+        };
+
+        self.symbols.rename_symbol(handler, old_name, &new_name)?;
+        self.preds[pred_key].rename(new_name.clone());
+
+        // Local predicate instances name their predicate relative to the root, without the
+        // leading `::`.
+        let old_instance_name = old_name.strip_prefix("::").unwrap_or(old_name);
+        let new_instance_name = new_name.strip_prefix("::").unwrap_or(&new_name);
+        for pred in self.preds.values_mut() {
+            for instance in &mut pred.predicate_instances {
+                if instance.interface_instance.is_none()
+                    && instance.predicate.name == old_instance_name
+                {
+                    instance.predicate.name = new_instance_name.to_string();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     // Apply a mutating closure to every single type in the contract.
     pub fn update_types(&mut self, f: impl Fn(&mut Type), skip_new_types: bool) {
         // Update every expression type in the contract.
@@ -387,13 +617,15 @@ impl Contract {
                 .map(|(storage, _)| {
                     storage
                         .iter()
-                        .map(|StorageVar { name, ty, .. }| {
+                        .map(|StorageVar { name, ty, doc, .. }| {
                             // The key of `ty` is either the `index` if the storage type is
                             // primitive or a map, or it's `[index, 0]`. The `0` here is a
                             // placeholder for offsets.
                             Ok(VarABI {
                                 name: name.to_string(),
                                 ty: ty.abi(handler, self)?,
+                                size_in_bytes: None,
+                                doc: doc.clone(),
                             })
                         })
                         .collect::<Result<_, _>>()
@@ -417,43 +649,158 @@ impl Contract {
         self.removed_macro_calls.contains_key(expr_key)
     }
 
+    /// Marks `expr_key` as a hexadecimal or binary integer literal which may be zero-extended to a
+    /// `b256` if it's used where a `b256` is expected.
+    pub fn add_radix_int_literal(&mut self, expr_key: ExprKey) {
+        self.radix_int_literals.insert(expr_key);
+    }
+
+    /// Marks `expr_key` as the decimal literal `9223372036854775808`, which only fits in an `int`
+    /// when it's negated.
+    pub fn add_min_int_literal(&mut self, expr_key: ExprKey, span: Span) {
+        self.min_int_literals.insert(expr_key, span);
+    }
+
+    /// Unmarks `expr_key` as a hexadecimal or binary integer literal once it has been folded into
+    /// another expression.  Returns whether it was marked.
+    pub fn remove_radix_int_literal(&mut self, expr_key: ExprKey) -> bool {
+        self.radix_int_literals.remove(&expr_key)
+    }
+
+    /// Unmarks `expr_key` as a `9223372036854775808` literal once it has been negated.  Returns
+    /// whether it was marked.
+    pub fn remove_min_int_literal(&mut self, expr_key: ExprKey) -> bool {
+        self.min_int_literals.remove(expr_key).is_some()
+    }
+
+    /// Emits an error for every `9223372036854775808` literal which was never negated and replaces
+    /// it with an error immediate.
+    pub fn check_min_int_literals(&mut self, handler: &Handler) {
+        for (expr_key, span) in std::mem::take(&mut self.min_int_literals) {
+            handler.emit_err(Error::Parse {
+                error: ParseError::IntLiteralTooLarge { span },
+            });
+
+            if let Expr::Immediate { value, .. } = expr_key.get_mut(self) {
+                *value = Immediate::Error;
+            }
+        }
+    }
+
+    /// Report expressions which are nested more than `max_depth` deep, e.g., long chains of
+    /// tuple field accesses or unary operators.  Later passes recurse over the expressions and
+    /// could otherwise overflow the stack, so the depths are found here without recursing.
+    pub fn check_expr_depths(&self, handler: &Handler, max_depth: usize) {
+        // The depth of each expression, where an expression without sub-expressions has depth 1.
+        let mut depths = FxHashMap::<ExprKey, usize>::default();
+
+        for expr_key in self.exprs.keys() {
+            // Each expression is pushed once to visit its children and again to find its depth
+            // after they've been visited.
+            let mut stack = vec![(expr_key, false)];
+            while let Some((expr_key, children_visited)) = stack.pop() {
+                if depths.contains_key(&expr_key) {
+                    continue;
+                }
+
+                if children_visited {
+                    let mut depth = 0;
+                    self.for_each_expr_child(expr_key, |child| {
+                        depth = depth.max(depths.get(&child).copied().unwrap_or(0))
+                    });
+                    depths.insert(expr_key, depth + 1);
+
+                    // Only the innermost expressions which exceed the limit are reported, rather
+                    // than every expression containing them too.
+                    if depth == max_depth {
+                        handler.emit_err(Error::Parse {
+                            error: ParseError::ExpressionTooDeep {
+                                max_depth,
+                                span: expr_key.get(self).span().clone(),
+                            },
+                        });
+                    }
+                } else {
+                    stack.push((expr_key, true));
+                    self.for_each_expr_child(expr_key, |child| {
+                        // Removed macro calls may still be referenced.
+                        if child.try_get(self).is_some() && !depths.contains_key(&child) {
+                            stack.push((child, false));
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the predicate with the given fully qualified name (e.g. `::Foo`), if any.
+    pub fn predicate_by_name(&self, name: &str) -> Option<(PredKey, &Predicate)> {
+        self.preds.iter().find(|(_, pred)| pred.name == name)
+    }
+
+    /// Returns a mutable reference to the predicate with the given fully qualified name (e.g.
+    /// `::Foo`), if any.
+    pub fn predicate_by_name_mut(&mut self, name: &str) -> Option<(PredKey, &mut Predicate)> {
+        self.preds.iter_mut().find(|(_, pred)| pred.name == name)
+    }
+
+    /// Returns an iterator over every constraint in the predicate with key `pred_key`, including
+    /// those nested within `if` and `match` declarations.
+    pub fn iter_constraints(&self, pred_key: PredKey) -> impl Iterator<Item = &ConstraintDecl> {
+        let pred = &self.preds[pred_key];
+        pred.constraints
+            .iter()
+            .chain(
+                pred.if_decls
+                    .iter()
+                    .flat_map(|if_decl| if_decl.get_constraints()),
+            )
+            .chain(
+                pred.match_decls
+                    .iter()
+                    .flat_map(|match_decl| match_decl.get_constraints()),
+            )
+    }
+
+    /// Returns a local `StorageVar` and its index given a var name, or `None` if there is no
+    /// storage block or no storage var with that name.
+    pub fn try_storage_var(&self, name: &str) -> Option<(usize, &StorageVar)> {
+        let storage = &self.storage.as_ref()?.0;
+        let storage_index = storage.iter().position(|var| var.name.name == name)?;
+        Some((storage_index, &storage[storage_index]))
+    }
+
     /// Returns a local `StorageVar` given a var name. Panics if anything goes wrong.
     pub fn storage_var(&self, name: &String) -> (usize, &StorageVar) {
-        let storage = &self
-            .storage
-            .as_ref()
-            .expect("a storage block must have been declared")
-            .0;
-        let storage_index = storage
-            .iter()
-            .position(|var| var.name.name == *name)
-            .expect("storage access should have been checked before");
-        (storage_index, &storage[storage_index])
+        self.try_storage_var(name)
+            .expect("storage access should have been checked before")
     }
 
-    /// Returns an external `StorageVar` given an interface name and a var name. Panics if anything
-    /// goes wrong.
-    pub fn external_storage_var(&self, interface: &String, name: &String) -> (usize, &StorageVar) {
+    /// Returns an external `StorageVar` and its index given an interface name and a var name, or
+    /// `None` if the interface, its storage block, or the storage var can't be found.
+    pub fn try_external_storage_var(
+        &self,
+        interface: &str,
+        name: &str,
+    ) -> Option<(usize, &StorageVar)> {
         // Get the `interface` declaration that the storage access refers to
-        let interface = &self
+        let interface = self
             .interfaces
             .iter()
-            .find(|e| e.name.to_string() == *interface)
-            .expect("missing interface");
+            .find(|e| e.name.to_string() == interface)?;
 
         // Get the index of the storage variable in the storage block declaration
-        let storage = &interface
-            .storage
-            .as_ref()
-            .expect("a storage block must have been declared")
-            .0;
+        let storage = &interface.storage.as_ref()?.0;
+        let storage_index = storage.iter().position(|var| var.name.name == name)?;
 
-        let storage_index = storage
-            .iter()
-            .position(|var| var.name.name == *name)
-            .expect("storage access should have been checked before");
+        Some((storage_index, &storage[storage_index]))
+    }
 
-        (storage_index, &storage[storage_index])
+    /// Returns an external `StorageVar` given an interface name and a var name. Panics if anything
+    /// goes wrong.
+    pub fn external_storage_var(&self, interface: &String, name: &String) -> (usize, &StorageVar) {
+        self.try_external_storage_var(interface, name)
+            .expect("storage access should have been checked before")
     }
 
     pub(crate) fn root_array_range_exprs(&self) -> impl Iterator<Item = ExprKey> + '_ {
@@ -515,6 +862,9 @@ impl Contract {
 pub struct Predicate {
     pub name: String,
 
+    // The `///` doc comment directly before the predicate declaration, if any.
+    pub doc: Option<String>,
+
     pub vars: Vars,
     pub states: States,
 
@@ -527,6 +877,9 @@ pub struct Predicate {
     // Each of the initialised variables.  Used by type inference.
     pub var_inits: slotmap::SecondaryMap<VarKey, ExprKey>,
 
+    // The `///` doc comments of the variables which have them.  Exported in the ABI.
+    pub var_docs: slotmap::SecondaryMap<VarKey, String>,
+
     // CallKey is used in a secondary map in the parser context to access the actual call data.
     pub calls: slotmap::SlotMap<CallKey, String>,
 
@@ -547,6 +900,35 @@ impl Predicate {
         }
     }
 
+    /// Set the fully qualified name of this predicate.  This does not update the contract's
+    /// symbols or any predicate instances referring to this predicate; use
+    /// `Contract::rename_predicate()` for that.
+    pub fn rename(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    /// Returns the number of non-`pub` decision variables in this predicate.
+    pub fn decision_var_count(&self) -> usize {
+        self.vars().filter(|(_, var)| !var.is_pub).count()
+    }
+
+    /// Returns the number of `pub` decision variables in this predicate.
+    pub fn pub_var_count(&self) -> usize {
+        self.vars().filter(|(_, var)| var.is_pub).count()
+    }
+
+    /// Returns the total number of state slots required by the state variables in this predicate.
+    /// Each state variable requires as many slots as its type occupies in storage.
+    pub fn state_slot_count(
+        &self,
+        handler: &Handler,
+        contract: &Contract,
+    ) -> Result<usize, ErrorEmitted> {
+        self.states().try_fold(0, |acc, (state_key, _)| {
+            Ok(acc + state_key.get_ty(self).storage_slots(handler, contract)?)
+        })
+    }
+
     /// Generate a `PredicateABI` given a `Predicate`
     pub fn abi(
         &self,
@@ -555,6 +937,7 @@ impl Predicate {
     ) -> Result<PredicateABI, ErrorEmitted> {
         Ok(PredicateABI {
             name: self.name.clone(),
+            doc: self.doc.clone(),
             vars: self
                 .vars()
                 .filter(|(_, var)| !var.is_pub)
@@ -564,15 +947,18 @@ impl Predicate {
                 .vars()
                 .filter(|(_, var)| var.is_pub)
                 .map(|(var_key, Var { name, .. })| {
+                    let ty = var_key.get_ty(self);
                     Ok(VarABI {
                         name: name.to_string(),
-                        ty: {
-                            let ty = var_key.get_ty(self);
-                            ty.abi(handler, contract)?
-                        },
+                        ty: ty.abi(handler, contract)?,
+                        size_in_bytes: Some(ty.size_in_bytes(handler, contract)?),
+                        doc: self.var_docs.get(var_key).cloned(),
                     })
                 })
                 .collect::<Result<Vec<_>, _>>()?,
+            decision_var_count: self.decision_var_count(),
+            pub_var_count: self.pub_var_count(),
+            state_slot_count: self.state_slot_count(handler, contract)?,
         })
     }
 
@@ -653,6 +1039,56 @@ impl Predicate {
             });
     }
 
+    /// Replace every expr key found in `keys` at once, including those within types.
+    pub(crate) fn replace_exprs_by_map(&mut self, keys: &FxHashMap<ExprKey, ExprKey>) {
+        let replace = |expr: &mut ExprKey| {
+            if let Some(new_expr) = keys.get(expr) {
+                *expr = *new_expr;
+            }
+        };
+
+        self.vars
+            .update_types(|_var_key, var_ty| var_ty.replace_type_expr_by_map(keys));
+
+        self.states
+            .update_types(|_state_key, state_ty| state_ty.replace_type_expr_by_map(keys));
+
+        self.states
+            .update_states(|State { expr, .. }| replace(expr));
+
+        self.constraints
+            .iter_mut()
+            .for_each(|ConstraintDecl { expr, .. }| replace(expr));
+
+        self.if_decls
+            .iter_mut()
+            .for_each(|if_decl| if_decl.replace_exprs_by_map(keys));
+
+        self.match_decls
+            .iter_mut()
+            .for_each(|match_decl| match_decl.replace_exprs_by_map(keys));
+
+        self.ephemerals
+            .iter_mut()
+            .for_each(|EphemeralDecl { ty, .. }| ty.replace_type_expr_by_map(keys));
+
+        self.var_inits
+            .iter_mut()
+            .for_each(|(_, expr)| replace(expr));
+
+        self.interface_instances
+            .iter_mut()
+            .for_each(|InterfaceInstance { address, .. }| replace(address));
+
+        self.predicate_instances
+            .iter_mut()
+            .for_each(|PredicateInstance { address, .. }| {
+                if let Some(address) = address {
+                    replace(address);
+                }
+            });
+    }
+
     /// Return an iterator to the 'root set' of expressions, based on the constraints, states,
     /// interface instances, and predicate instances.
     fn root_set(&self) -> impl Iterator<Item = ExprKey> + '_ {
@@ -725,6 +1161,20 @@ impl BlockStatement {
         }
     }
 
+    fn replace_exprs_by_map(&mut self, keys: &FxHashMap<ExprKey, ExprKey>) {
+        match self {
+            BlockStatement::Constraint(ConstraintDecl { expr, .. }) => {
+                if let Some(new_expr) = keys.get(expr) {
+                    *expr = *new_expr;
+                }
+            }
+
+            BlockStatement::If(if_decl) => if_decl.replace_exprs_by_map(keys),
+
+            BlockStatement::Match(match_decl) => match_decl.replace_exprs_by_map(keys),
+        }
+    }
+
     fn fmt_with_indent(
         &self,
         f: &mut Formatter,
@@ -807,6 +1257,22 @@ impl IfDecl {
         }
     }
 
+    fn replace_exprs_by_map(&mut self, keys: &FxHashMap<ExprKey, ExprKey>) {
+        if let Some(new_condition) = keys.get(&self.condition) {
+            self.condition = *new_condition;
+        }
+
+        self.then_block
+            .iter_mut()
+            .for_each(|stmt| stmt.replace_exprs_by_map(keys));
+
+        if let Some(else_block) = &mut self.else_block {
+            else_block
+                .iter_mut()
+                .for_each(|stmt| stmt.replace_exprs_by_map(keys));
+        }
+    }
+
     fn fmt_with_indent(
         &self,
         f: &mut Formatter,
@@ -896,6 +1362,24 @@ impl MatchDecl {
         }
     }
 
+    fn replace_exprs_by_map(&mut self, keys: &FxHashMap<ExprKey, ExprKey>) {
+        if let Some(new_match_expr) = keys.get(&self.match_expr) {
+            self.match_expr = *new_match_expr;
+        }
+
+        for MatchDeclBranch { block, .. } in &mut self.match_branches {
+            for stmt in block {
+                stmt.replace_exprs_by_map(keys);
+            }
+        }
+
+        if let Some(else_block) = &mut self.else_branch {
+            for stmt in else_block {
+                stmt.replace_exprs_by_map(keys);
+            }
+        }
+    }
+
     fn fmt_with_indent(
         &self,
         f: &mut Formatter,
@@ -968,6 +1452,7 @@ pub struct StorageVar {
     pub name: Ident,
     pub ty: Type,
     pub span: Span,
+    pub doc: Option<String>,
 }
 
 /// A a predicate interface that belong in an `Interface`.
@@ -1094,6 +1579,31 @@ impl SymbolTable {
         }
     }
 
+    /// Move the symbol `old` to `new`, keeping its span.  Emits a name clash error if `new` is
+    /// already a symbol.  Does nothing if `old` is not a symbol.
+    pub fn rename_symbol(
+        &mut self,
+        handler: &Handler,
+        old: &str,
+        new: &str,
+    ) -> std::result::Result<(), ErrorEmitted> {
+        if let Some(prev_span) = self.symbols.get(new) {
+            return Err(handler.emit_err(Error::Parse {
+                error: ParseError::NameClash {
+                    sym: new.to_string(),
+                    span: self.symbols.get(old).cloned().unwrap_or_else(empty_span),
+                    prev_span: prev_span.clone(),
+                },
+            }));
+        }
+
+        if let Some(span) = self.symbols.remove(old) {
+            self.symbols.insert(new.to_string(), span);
+        }
+
+        Ok(())
+    }
+
     fn make_full_symbol(mod_prefix: &str, local_scope: Option<&str>, name: &Ident) -> String {
         let local_scope_str = local_scope
             .map(|ls| ls.to_owned() + "::")
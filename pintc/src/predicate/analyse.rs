@@ -1,12 +1,14 @@
 mod array_check;
 mod type_check;
+mod unused_vars;
 
 use super::{Const, Contract, Expr, ExprKey, Ident};
 use crate::{
     error::{CompileError, Error, ErrorEmitted, Handler},
-    expr::evaluate::Evaluator,
-    span::{empty_span, Span},
+    expr::{evaluate::Evaluator, BinaryOp, Immediate},
+    span::{empty_span, Span, Spanned},
     types::Type,
+    warning::Warning,
 };
 
 #[derive(Debug)]
@@ -26,6 +28,9 @@ impl Contract {
         // Ensure that all storage accesses are used legally, i.e., in state initializers only.
         let _ = handler.scope(|handler| self.check_storage_accesses(handler));
 
+        // Expand chained comparisons such as `a < b < c` into `a < b && b < c`.
+        self.desugar_chained_comparisons(handler);
+
         // Evaluate all the constant decls to ensure they're all immediates. Each Const expr is
         // updated and has its type set.
         handler.scope(|handler| self.evaluate_all_consts(handler))?;
@@ -69,6 +74,75 @@ impl Contract {
         handler.result(self)
     }
 
+    fn desugar_chained_comparisons(&mut self, handler: &Handler) {
+        // Relational operators are left associative, so `a < b < c` is parsed as `(a < b) < c`,
+        // which compares a `bool` to an `int` and is never what was intended.  Instead we expand
+        // it to `a < b && b < c`, sharing the middle operand between both comparisons.  Longer
+        // chains such as `a < b < c < d` are expanded link by link.
+        let is_ordering = |op: &BinaryOp| {
+            matches!(
+                op,
+                BinaryOp::LessThan
+                    | BinaryOp::LessThanOrEqual
+                    | BinaryOp::GreaterThan
+                    | BinaryOp::GreaterThanOrEqual
+            )
+        };
+
+        let mut chains = Vec::new();
+        for pred_key in self.preds.keys() {
+            for expr_key in self.exprs(pred_key) {
+                if let Some(Expr::BinaryOp { op, lhs, rhs, span }) = expr_key.try_get(self) {
+                    if let Some(Expr::BinaryOp {
+                        op: lhs_op,
+                        rhs: middle,
+                        ..
+                    }) = lhs.try_get(self)
+                    {
+                        if is_ordering(op) && is_ordering(lhs_op) {
+                            chains.push((expr_key, *op, *lhs, *middle, *rhs, span.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Report the chains in source order.
+        chains.sort_by_key(|(.., span)| span.start());
+
+        for (expr_key, op, lhs, middle, rhs, span) in &chains {
+            // Only warn once per chain, i.e., for the outermost comparison.
+            if !chains
+                .iter()
+                .any(|(_, _, chain_lhs, ..)| chain_lhs == expr_key)
+            {
+                handler.emit_warn(Warning::ChainedComparison { span: span.clone() });
+            }
+
+            let middle_span = middle.get(self).span().clone();
+            let rhs_span = Span::new(
+                middle_span.context(),
+                middle_span.start()..rhs.get(self).span().end(),
+            );
+            let new_rhs = self.exprs.insert(
+                Expr::BinaryOp {
+                    op: *op,
+                    lhs: *middle,
+                    rhs: *rhs,
+                    span: rhs_span.clone(),
+                },
+                Type::Unknown(rhs_span),
+            );
+
+            *expr_key.get_mut(self) = Expr::BinaryOp {
+                op: BinaryOp::LogicalAnd,
+                lhs: *lhs,
+                rhs: new_rhs,
+                span: span.clone(),
+            };
+        }
+    }
+
     fn evaluate_all_consts(&mut self, handler: &Handler) -> Result<(), ErrorEmitted> {
         // Evaluate every const initialiser which isn't already an immediate.
         //
@@ -80,6 +154,17 @@ impl Contract {
         // performing N-1 evaluation passes for N consts should resolve all dependencies and in
         // most cases will be done in only 1 or 2 passes.
 
+        // Const initialisers are evaluated ahead of type checking, so any hex or binary literal
+        // initialising a `b256` const must be zero-extended to its declared type first.
+        let const_inits = self
+            .consts
+            .values()
+            .map(|Const { expr, decl_ty, .. }| (*expr, decl_ty.clone()))
+            .collect::<Vec<_>>();
+        for (init_key, decl_ty) in const_inits {
+            self.zero_extend_radix_literal(init_key, &decl_ty);
+        }
+
         let mut evaluator = Evaluator::new(self);
         let mut new_immediates = Vec::default();
 
@@ -96,6 +181,17 @@ impl Contract {
                     if let Expr::Immediate { value, .. } = expr {
                         evaluator.insert_value(path.clone(), value.clone());
                     } else if let Ok(imm) = evaluator.evaluate_key(&cnst.expr, &tmp_handler, self) {
+                        // Intermediate results may overflow but the const itself must be an
+                        // `int`.
+                        if let Immediate::OverflowedInt(_) = imm {
+                            tmp_handler.emit_err(Error::Compile {
+                                error: CompileError::IntegerOverflow {
+                                    span: expr.span().clone(),
+                                },
+                            });
+                            continue;
+                        }
+
                         evaluator.insert_value(path.clone(), imm);
 
                         // Take note of this const as we need to update the const declaration
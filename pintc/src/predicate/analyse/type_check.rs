@@ -105,7 +105,7 @@ impl Contract {
                 if !state_ty.is_unknown() {
                     let expr_ty = state.expr.get_ty(self);
                     if !expr_ty.is_unknown() {
-                        if !state_ty.eq(self, expr_ty) {
+                        if !state_ty.is_assignable_from(self, expr_ty) {
                             handler.emit_err(Error::Compile {
                                 error: CompileError::StateVarInitTypeError {
                                     large_err: Box::new(LargeTypeError::StateVarInitTypeError {
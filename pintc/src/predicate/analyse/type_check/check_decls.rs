@@ -110,9 +110,8 @@ impl Contract {
 
                     // If the predicate does not exist, emit an error
                     if self
-                        .preds
-                        .iter()
-                        .all(|(_, pred)| pred.name != "::".to_owned() + &predicate.name)
+                        .predicate_by_name(&("::".to_owned() + &predicate.name))
+                        .is_none()
                     {
                         handler.emit_err(Error::Compile {
                             error: CompileError::MissingPredicate {
@@ -136,6 +135,8 @@ impl Contract {
         addr_keys: &[ExprKey],
     ) {
         for address in addr_keys {
+            self.zero_extend_radix_literal(*address, &b256());
+
             if self
                 .type_check_single_expr(handler, pred_key, *address)
                 .is_ok()
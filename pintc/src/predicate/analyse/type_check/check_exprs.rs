@@ -49,6 +49,8 @@ impl Contract {
             if !next_key.get_ty(self).is_unknown() {
                 queue.pop();
             } else {
+                self.zero_extend_radix_operands(next_key);
+
                 match self.infer_expr_key_type(
                     handler,
                     pred_key.map(|pred_key| &self.preds[pred_key]),
@@ -114,6 +116,60 @@ impl Contract {
         Ok(())
     }
 
+    /// Zero-extend any hex or binary integer literal operands of `expr_key` which are used where
+    /// a `b256` is expected, i.e., compared with a `b256` or passed as a `b256` intrinsic argument.
+    fn zero_extend_radix_operands(&mut self, expr_key: ExprKey) {
+        match expr_key.get(self) {
+            Expr::BinaryOp {
+                op: BinaryOp::Equal | BinaryOp::NotEqual,
+                lhs,
+                rhs,
+                ..
+            } => {
+                let (lhs, rhs) = (*lhs, *rhs);
+                let lhs_ty = lhs.get_ty(self).clone();
+                let rhs_ty = rhs.get_ty(self).clone();
+                self.zero_extend_radix_literal(lhs, &rhs_ty);
+                self.zero_extend_radix_literal(rhs, &lhs_ty);
+            }
+
+            Expr::IntrinsicCall {
+                kind: (kind, _),
+                args,
+                ..
+            } => {
+                for (expected_ty, arg_key) in kind.args().iter().zip(args.clone()) {
+                    self.zero_extend_radix_literal(arg_key, expected_ty);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    /// Hex and binary literals which fit in an `int` are parsed as such, but if `expected_ty` is
+    /// `b256` then the literal at `expr_key` is left-padded with zeros instead.
+    pub(in crate::predicate::analyse) fn zero_extend_radix_literal(
+        &mut self,
+        expr_key: ExprKey,
+        expected_ty: &Type,
+    ) {
+        if !expected_ty.is_b256() || !self.radix_int_literals.contains(&expr_key) {
+            return;
+        }
+
+        if let Expr::Immediate {
+            value: Immediate::Int(val),
+            span,
+        } = expr_key.get(self).clone()
+        {
+            let value = Immediate::B256([0, 0, 0, val as u64]);
+            expr_key.set_ty(value.get_ty(Some(&span)), self);
+            *expr_key.get_mut(self) = Expr::Immediate { value, span };
+            self.remove_radix_int_literal(expr_key);
+        }
+    }
+
     fn infer_expr_key_type(
         &self,
         handler: &Handler,
@@ -207,6 +263,14 @@ impl Contract {
                 Ok(self.infer_index_expr(handler, *expr, *index, span))
             }
 
+            Expr::ArraySlice { array, range, span } => {
+                Ok(self.infer_array_slice_expr(handler, *array, *range, span))
+            }
+
+            Expr::RangeArray { range, span } => {
+                Ok(self.infer_range_array_expr(handler, *range, span))
+            }
+
             Expr::TupleFieldAccess { tuple, field, span } => {
                 Ok(self.infer_tuple_access_expr(handler, *tuple, field, span))
             }
@@ -429,11 +493,7 @@ impl Contract {
                 // `predicate` must not reference `pred`.
                 let full_predicate_name = "::".to_owned() + &predicate.name;
                 if full_predicate_name != pred.name {
-                    if let Some((_, predicate)) = self
-                        .preds
-                        .iter()
-                        .find(|(_, e)| e.name == full_predicate_name)
-                    {
+                    if let Some((_, predicate)) = self.predicate_by_name(&full_predicate_name) {
                         for (var_key, var) in predicate.vars() {
                             if var.is_pub && name.to_string() + &var.name == *path {
                                 return Some(Inference::Type(var_key.get_ty(predicate).clone()));
@@ -642,6 +702,29 @@ impl Contract {
                         check_numeric_args(&lhs_ty, rhs_ty);
                         Inference::Type(lhs_ty)
                     }
+                    BinaryOp::Pow
+                    | BinaryOp::BitwiseAnd
+                    | BinaryOp::BitwiseOr
+                    | BinaryOp::BitwiseXor
+                    | BinaryOp::ShiftLeft
+                    | BinaryOp::ShiftRight => {
+                        // Both args must be ints; binary op type is int.  A `real` power would
+                        // need a fixed-point exponentiation which isn't supported.
+                        if let Some(bad_ty) = [&lhs_ty, rhs_ty]
+                            .into_iter()
+                            .find(|ty| !ty.is_int() && !ty.is_error())
+                        {
+                            handler.emit_err(Error::Compile {
+                                error: CompileError::OperatorInvalidType {
+                                    op: op.as_str(),
+                                    ty_kind: "non-integer",
+                                    bad_ty: self.with_ctrct(bad_ty).to_string(),
+                                    span: span.clone(),
+                                },
+                            });
+                        }
+                        Inference::Type(lhs_ty)
+                    }
                     BinaryOp::Equal | BinaryOp::NotEqual => {
                         // We can special case implicit constraints which are injected by variable
                         // initialiser handling.  Each `var a = b` gets a magic `constraint a == b`
@@ -662,16 +745,33 @@ impl Contract {
                         }
 
                         // Both args must be equatable, which at this stage is any type *except*
-                        // unions; binary op type is bool.
-                        if !lhs_ty.eq(self, rhs_ty) {
-                            // Only emit an error if neither side is nil nor error, nor an
-                            // initialiser constraint as per above.
-                            if !lhs_ty.is_nil()
-                                && !rhs_ty.is_nil()
-                                && !lhs_ty.is_error()
-                                && !rhs_ty.is_error()
-                                && !is_init_constraint
+                        // unions; binary op type is bool.  Only emit an error if neither side is
+                        // nil nor error, nor an initialiser constraint as per above.
+                        if !lhs_ty.eq(self, rhs_ty)
+                            && !lhs_ty.is_nil()
+                            && !rhs_ty.is_nil()
+                            && !lhs_ty.is_error()
+                            && !rhs_ty.is_error()
+                            && !is_init_constraint
+                        {
+                            if let Some((lhs_el_ty, rhs_el_ty)) = lhs_ty
+                                .get_array_el_type()
+                                .zip(rhs_ty.get_array_el_type())
+                                .filter(|(lhs_el_ty, rhs_el_ty)| {
+                                    !lhs_el_ty.is_error() && !rhs_el_ty.is_error()
+                                })
                             {
+                                // Arrays are compared element-wise, so point out their element
+                                // types rather than the array types.
+                                handler.emit_err(Error::Compile {
+                                    error: CompileError::MismatchedArrayComparisonElementTypes {
+                                        op: op.to_string(),
+                                        lhs_el_ty: self.with_ctrct(lhs_el_ty).to_string(),
+                                        rhs_el_ty: self.with_ctrct(rhs_el_ty).to_string(),
+                                        span: span.clone(),
+                                    },
+                                });
+                            } else {
                                 handler.emit_err(Error::Compile {
                                     error: CompileError::OperatorTypeError {
                                         arity: "binary",
@@ -859,20 +959,20 @@ impl Contract {
                 Inference::Type(Type::Error(span.clone()))
             } else if !then_ty.is_unknown() {
                 if !else_ty.is_unknown() {
-                    if !then_ty.eq(self, else_ty) {
+                    if let Some(ty) = then_ty.common_supertype(self, else_ty) {
+                        Inference::Type(ty)
+                    } else {
                         handler.emit_err(Error::Compile {
-                            error: CompileError::SelectBranchesTypeMismatch {
-                                large_err: Box::new(LargeTypeError::SelectBranchesTypeMismatch {
-                                    then_type: self.with_ctrct(then_ty).to_string(),
-                                    then_span: self.expr_key_to_span(then_expr_key),
-                                    else_type: self.with_ctrct(else_ty).to_string(),
-                                    else_span: self.expr_key_to_span(else_expr_key),
-                                    span: span.clone(),
-                                }),
+                            error: CompileError::MismatchedIfBranchTypes {
+                                then_ty: self.with_ctrct(then_ty).to_string(),
+                                then_span: self.expr_key_to_span(then_expr_key),
+                                else_ty: self.with_ctrct(else_ty).to_string(),
+                                else_span: self.expr_key_to_span(else_expr_key),
+                                span: span.clone(),
                             },
                         });
+                        Inference::Type(then_ty.clone())
                     }
-                    Inference::Type(then_ty.clone())
                 } else {
                     Inference::Dependant(else_expr_key)
                 }
@@ -1190,8 +1290,8 @@ impl Contract {
                     })
                 } else {
                     handler.emit_err(Error::Compile {
-                        error: CompileError::Internal {
-                            msg: "range ty is not numeric or array?",
+                        error: CompileError::InRangeInvalid {
+                            found_ty: self.with_ctrct(collection_ty).to_string(),
                             span: span.clone(),
                         },
                     });
@@ -1370,6 +1470,87 @@ impl Contract {
         }
     }
 
+    fn infer_array_slice_expr(
+        &self,
+        handler: &Handler,
+        array_expr_key: ExprKey,
+        range_expr_key: ExprKey,
+        span: &Span,
+    ) -> Inference {
+        let range_ty = range_expr_key.get_ty(self);
+        if range_ty.is_unknown() {
+            return Inference::Dependant(range_expr_key);
+        }
+
+        let ary_ty = array_expr_key.get_ty(self);
+        if ary_ty.is_unknown() {
+            return Inference::Dependant(array_expr_key);
+        }
+
+        if let Some(el_ty) = ary_ty.get_array_el_type() {
+            if !range_ty.is_int() {
+                handler.emit_err(Error::Compile {
+                    error: CompileError::ArrayAccessWithWrongType {
+                        found_ty: self.with_ctrct(range_ty).to_string(),
+                        expected_ty: "int".to_string(),
+                        span: self.expr_key_to_span(range_expr_key),
+                    },
+                });
+            }
+
+            // The size of the slice is only known once its bounds are evaluated.  Until then the
+            // slice range stands in for the array range and is replaced when the slice is lowered.
+            Inference::Type(Type::Array {
+                ty: Box::new(el_ty.clone()),
+                range: Some(range_expr_key),
+                size: None,
+                span: span.clone(),
+            })
+        } else {
+            handler.emit_err(Error::Compile {
+                error: CompileError::IndexExprNonIndexable {
+                    non_indexable_type: self.with_ctrct(ary_ty).to_string(),
+                    span: span.clone(),
+                },
+            });
+
+            Inference::Type(Type::Error(span.clone()))
+        }
+    }
+
+    fn infer_range_array_expr(
+        &self,
+        handler: &Handler,
+        range_expr_key: ExprKey,
+        span: &Span,
+    ) -> Inference {
+        let range_ty = range_expr_key.get_ty(self);
+        if range_ty.is_unknown() {
+            return Inference::Dependant(range_expr_key);
+        }
+
+        if !range_ty.is_int() {
+            handler.emit_err(Error::Compile {
+                error: CompileError::InvalidArrayRangeType {
+                    found_ty: self.with_ctrct(range_ty).to_string(),
+                    span: self.expr_key_to_span(range_expr_key),
+                },
+            });
+        }
+
+        // As with slices, the range stands in for the array range until the bounds are evaluated
+        // and the range array is lowered.
+        Inference::Type(Type::Array {
+            ty: Box::new(Type::Primitive {
+                kind: PrimitiveKind::Int,
+                span: span.clone(),
+            }),
+            range: Some(range_expr_key),
+            size: None,
+            span: span.clone(),
+        })
+    }
+
     fn infer_tuple_expr(&self, fields: &[(Option<Ident>, ExprKey)], span: &Span) -> Inference {
         let mut field_tys = Vec::with_capacity(fields.len());
 
@@ -273,7 +273,7 @@ impl Contract {
                 if !var_decl_ty.is_unknown() {
                     let init_ty = init_expr_key.get_ty(self);
 
-                    if !var_decl_ty.eq(self, init_ty) {
+                    if !var_decl_ty.is_assignable_from(self, init_ty) {
                         handler.emit_err(Error::Compile {
                             error: CompileError::InitTypeError {
                                 init_kind: "variable",
@@ -300,7 +300,7 @@ impl Contract {
         {
             let init_ty = init_expr_key.get_ty(self);
 
-            if !init_ty.eq(self, decl_ty) {
+            if !decl_ty.is_assignable_from(self, init_ty) {
                 handler.emit_err(Error::Compile {
                     error: CompileError::InitTypeError {
                         init_kind: "const",
@@ -0,0 +1,114 @@
+use super::Contract;
+use crate::{
+    error::Handler,
+    expr::Expr,
+    predicate::{PredKey, VisitorKind},
+    warning::Warning,
+};
+use fxhash::FxHashSet;
+
+impl Contract {
+    /// Warn about any non-`pub` decision variable in a predicate which is never referenced by any
+    /// of its expressions. Variables whose names start with an underscore, including those
+    /// generated by the compiler, are ignored.
+    pub(crate) fn check_unused_vars(&self, handler: &Handler) {
+        for pred_key in self.preds.keys() {
+            self.check_unused_vars_in_pred(handler, pred_key);
+        }
+    }
+
+    fn check_unused_vars_in_pred(&self, handler: &Handler, pred_key: PredKey) {
+        let used_paths = self.used_paths(pred_key);
+
+        for (_, var) in self.preds[pred_key].vars() {
+            let local_name = var.name.rsplit("::").next().unwrap_or(&var.name);
+            if var.is_pub
+                || var.name.starts_with("__")
+                || local_name.starts_with('_')
+                || used_paths.contains(&var.name)
+            {
+                continue;
+            }
+
+            handler.emit_warn(Warning::UnusedVar {
+                name: local_name.to_string(),
+                span: var.span.clone(),
+            });
+        }
+    }
+
+    /// Warn about any `pub` decision variable which is never read by another predicate in the
+    /// contract through a predicate instance. Reads of a `pub var` from within its own predicate
+    /// don't count since a `pub var` is only useful to other predicates. Variables whose names
+    /// start with an underscore are ignored.
+    ///
+    /// Only predicates which are instantiated within the contract are checked. The `pub var`s of
+    /// any other predicate may be read by other contracts through an interface.
+    ///
+    /// This must run before flattening, while accesses through predicate instances are still
+    /// paths such as `::FooI::x`.
+    pub(crate) fn check_unreferenced_pub_vars(&self, handler: &Handler) {
+        // Collect the full names of every `pub var` read through a local predicate instance, e.g.,
+        // `::Foo::x` for `FooI::x` where `predicate FooI = Foo()`, and the names of the
+        // instantiated predicates.
+        let mut instantiated = FxHashSet::default();
+        let mut referenced = FxHashSet::default();
+        for (pred_key, pred) in self.preds.iter() {
+            let used_paths = self.used_paths(pred_key);
+
+            for instance in pred
+                .predicate_instances
+                .iter()
+                .filter(|instance| instance.interface_instance.is_none())
+            {
+                let target_name = "::".to_owned() + &instance.predicate.name;
+                let Some((_, target)) = self.preds.iter().find(|(_, e)| e.name == target_name)
+                else {
+                    continue;
+                };
+                instantiated.insert(&target.name);
+
+                for (_, var) in target.vars().filter(|(_, var)| var.is_pub) {
+                    if used_paths.contains(&(instance.name.to_string() + &var.name)) {
+                        referenced.insert(target.name.clone() + &var.name);
+                    }
+                }
+            }
+        }
+
+        for (_, pred) in self
+            .preds
+            .iter()
+            .filter(|(_, pred)| instantiated.contains(&pred.name))
+        {
+            for (_, var) in pred.vars() {
+                let local_name = var.name.rsplit("::").next().unwrap_or(&var.name);
+                if !var.is_pub
+                    || local_name.starts_with('_')
+                    || referenced.contains(&(pred.name.clone() + &var.name))
+                {
+                    continue;
+                }
+
+                handler.emit_warn(Warning::UnreferencedPubVar {
+                    name: local_name.to_string(),
+                    span: var.span.clone(),
+                });
+            }
+        }
+    }
+
+    fn used_paths(&self, pred_key: PredKey) -> FxHashSet<String> {
+        let mut used_paths = FxHashSet::default();
+        self.visitor(
+            pred_key,
+            VisitorKind::DepthFirstParentsBeforeChildren,
+            |_, expr| {
+                if let Expr::Path(path, _) = expr {
+                    used_paths.insert(path.clone());
+                }
+            },
+        );
+        used_paths
+    }
+}
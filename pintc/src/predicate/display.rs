@@ -118,6 +118,50 @@ impl Display for Contract {
     }
 }
 
+impl Contract {
+    /// Renders the contract as Pint source which can be parsed again. This is the `Display`
+    /// output with the `::` prefix dropped from declared names and every constraint terminated
+    /// with a `;`.  Only contracts declared entirely in the root module are supported.
+    pub fn to_source(&self) -> String {
+        const DECL_KEYWORDS: [&str; 8] = [
+            "const ",
+            "interface ",
+            "predicate ",
+            "pub var ",
+            "state ",
+            "type ",
+            "union ",
+            "var ",
+        ];
+
+        self.to_string()
+            .lines()
+            .map(|line| {
+                let stmt = line.trim_start();
+                let mut source_line = line[..line.len() - stmt.len()].to_string();
+
+                match DECL_KEYWORDS.iter().find_map(|keyword| {
+                    stmt.strip_prefix(keyword)
+                        .and_then(|decl| decl.strip_prefix("::"))
+                        .map(|decl| (keyword, decl))
+                }) {
+                    Some((keyword, decl)) => {
+                        source_line.push_str(keyword);
+                        source_line.push_str(decl);
+                    }
+                    None => source_line.push_str(stmt),
+                }
+
+                if stmt.starts_with("constraint ") && !stmt.ends_with(';') {
+                    source_line.push(';');
+                }
+
+                source_line + "\n"
+            })
+            .collect()
+    }
+}
+
 impl Contract {
     fn fmt_interfaces(&self, f: &mut Formatter) -> Result {
         for Interface {
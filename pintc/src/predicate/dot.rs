@@ -0,0 +1,81 @@
+use super::*;
+
+use std::fmt::Write;
+
+impl Contract {
+    /// Renders the expression graph of a predicate in Graphviz DOT format, for debugging the IR.
+    /// Every expression reachable from the predicate's root set is a node labelled by its
+    /// variant, with an edge to each of its immediate sub-expressions.  Expressions which are
+    /// shared by multiple parents appear only once.
+    pub fn to_dot(&self, pred_key: PredKey) -> String {
+        let mut node_ids = FxHashMap::<ExprKey, usize>::default();
+        let mut emitted = FxHashSet::<ExprKey>::default();
+        let mut dot = String::new();
+
+        let pred_name = self.preds[pred_key].name.replace('"', "\\\"");
+        writeln!(dot, "digraph \"{pred_name}\" {{").unwrap();
+
+        let mut stack = self.root_set(pred_key).collect::<Vec<_>>();
+        stack.reverse();
+        while let Some(expr_key) = stack.pop() {
+            if !emitted.insert(expr_key) {
+                continue;
+            }
+            let next_id = node_ids.len();
+            let id = *node_ids.entry(expr_key).or_insert(next_id);
+
+            let label = self.expr_dot_label(expr_key).replace('"', "\\\"");
+            writeln!(dot, "    n{id} [label=\"{label}\"];").unwrap();
+
+            // Push the children in reverse so that they're popped, and emitted, in order.
+            let first_child = stack.len();
+            self.for_each_expr_child(expr_key, |child| {
+                let next_id = node_ids.len();
+                let child_id = *node_ids.entry(child).or_insert(next_id);
+                writeln!(dot, "    n{id} -> n{child_id};").unwrap();
+                stack.push(child);
+            });
+            stack[first_child..].reverse();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn expr_dot_label(&self, expr_key: ExprKey) -> String {
+        let Some(expr) = expr_key.try_get(self) else {
+            return "Error".to_string();
+        };
+
+        match expr {
+            Expr::Error(_) => "Error".to_string(),
+            Expr::Immediate { value, .. } => format!("Immediate({})", self.with_ctrct(value)),
+            Expr::Array { .. } => "Array".to_string(),
+            Expr::Tuple { .. } => "Tuple".to_string(),
+            Expr::UnionVariant { path, .. } => format!("UnionVariant({path})"),
+            Expr::Path(path, _) => format!("Path({path})"),
+            Expr::StorageAccess { name, .. } => format!("StorageAccess({name})"),
+            Expr::ExternalStorageAccess {
+                interface_instance,
+                name,
+                ..
+            } => format!("ExternalStorageAccess({interface_instance}::{name})"),
+            Expr::UnaryOp { op, .. } => format!("UnaryOp({op:?})"),
+            Expr::BinaryOp { op, .. } => format!("BinaryOp({op:?})"),
+            Expr::MacroCall { path, .. } => format!("MacroCall({path})"),
+            Expr::IntrinsicCall { kind, .. } => format!("IntrinsicCall({})", kind.0),
+            Expr::Select { .. } => "Select".to_string(),
+            Expr::Match { .. } => "Match".to_string(),
+            Expr::Index { .. } => "Index".to_string(),
+            Expr::ArraySlice { .. } => "ArraySlice".to_string(),
+            Expr::RangeArray { .. } => "RangeArray".to_string(),
+            Expr::TupleFieldAccess { field, .. } => format!("TupleFieldAccess({field})"),
+            Expr::Cast { ty, .. } => format!("Cast({})", self.with_ctrct(ty)),
+            Expr::In { .. } => "In".to_string(),
+            Expr::Range { .. } => "Range".to_string(),
+            Expr::Generator { kind, .. } => format!("Generator({kind})"),
+            Expr::UnionTag { .. } => "UnionTag".to_string(),
+            Expr::UnionValue { .. } => "UnionValue".to_string(),
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use super::{Contract, PredKey, Predicate};
 use crate::{
-    expr::{Expr, ExternalIntrinsic, InternalIntrinsic, IntrinsicKind, MatchBranch, MatchElse},
+    expr::{
+        BinaryOp, Expr, ExternalIntrinsic, InternalIntrinsic, IntrinsicKind, MatchBranch, MatchElse,
+    },
     predicate::Immediate,
     span::empty_span,
     types::{PrimitiveKind, Type},
 };
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use std::collections::HashSet;
 
 slotmap::new_key_type! { pub struct ExprKey; }
@@ -35,6 +37,11 @@ impl Exprs {
         self.exprs.get(key)
     }
 
+    /// Returns an iterator over the keys of every expression
+    pub fn keys(&self) -> impl Iterator<Item = ExprKey> + '_ {
+        self.exprs.keys()
+    }
+
     /// Apply function `f` on every expression
     /// Only the `Expr`s can be mutated, not the keys
     pub fn update_exprs(&mut self, f: impl FnOnce(ExprKey, &mut Expr) + std::marker::Copy) {
@@ -51,6 +58,33 @@ impl Exprs {
         }
     }
 
+    /// Moves every expression and its type from `other` into `self`, returning a map from each
+    /// key in `other` to its new key in `self`.  Keys within the moved expressions and their types
+    /// are updated to match.
+    pub(crate) fn append(&mut self, other: Exprs) -> FxHashMap<ExprKey, ExprKey> {
+        let Exprs {
+            exprs,
+            mut expr_types,
+        } = other;
+
+        let keys = exprs
+            .into_iter()
+            .map(|(old_key, expr)| {
+                let ty = expr_types
+                    .remove(old_key)
+                    .unwrap_or_else(|| Type::Unknown(empty_span()));
+                (old_key, self.insert(expr, ty))
+            })
+            .collect::<FxHashMap<_, _>>();
+
+        for new_key in keys.values() {
+            self.exprs[*new_key].replace_ref_by_map(&keys);
+            self.expr_types[*new_key].replace_type_expr_by_map(&keys);
+        }
+
+        keys
+    }
+
     /// Inserts an integer expression with an empty span into the `exprs` map. Returns the
     /// `ExprKey` of the expression.
     pub fn insert_int(&mut self, i: i64) -> ExprKey {
@@ -119,7 +153,14 @@ impl ExprKey {
         contract.exprs.expr_types.insert(*self, ty);
     }
 
-    /// Return whether this expression can panic (typically related to storage).
+    /// Return whether evaluating this expression can panic at run time.
+    ///
+    /// This is the case for any expression which reads state, since the state may be `nil`, or
+    /// which accesses storage.  Division and modulo can also panic unless the divisor is a
+    /// constant other than `0`, or `-1` which overflows when dividing `i64::MIN`.
+    ///
+    /// An expression which can't panic may be freely moved, duplicated or removed without
+    /// changing the meaning of its predicate.
     pub fn can_panic(&self, contract: &Contract, pred: &Predicate) -> bool {
         contract.exprs.get(*self).map_or(false, |expr| match expr {
             Expr::StorageAccess { .. } | Expr::ExternalStorageAccess { .. } => true,
@@ -145,8 +186,17 @@ impl ExprKey {
 
             Expr::UnaryOp { expr, .. } => expr.can_panic(contract, pred),
 
-            Expr::BinaryOp { lhs, rhs, .. } => {
-                lhs.can_panic(contract, pred) || rhs.can_panic(contract, pred)
+            Expr::BinaryOp { op, lhs, rhs, .. } => {
+                (matches!(op, BinaryOp::Div | BinaryOp::Mod)
+                    && !matches!(
+                        rhs.get(contract),
+                        Expr::Immediate {
+                            value: Immediate::Int(divisor),
+                            ..
+                        } if *divisor != 0 && *divisor != -1
+                    ))
+                    || lhs.can_panic(contract, pred)
+                    || rhs.can_panic(contract, pred)
             }
 
             Expr::IntrinsicCall { kind, args, .. } => {
@@ -201,6 +251,12 @@ impl ExprKey {
                 expr.can_panic(contract, pred) || index.can_panic(contract, pred)
             }
 
+            Expr::ArraySlice { array, range, .. } => {
+                array.can_panic(contract, pred) || range.can_panic(contract, pred)
+            }
+
+            Expr::RangeArray { range, .. } => range.can_panic(contract, pred),
+
             Expr::TupleFieldAccess { tuple, .. } => tuple.can_panic(contract, pred),
 
             Expr::Cast { value, .. } => value.can_panic(contract, pred),
@@ -311,6 +367,15 @@ impl ExprKey {
                     }
                 }
 
+                Expr::ArraySlice { array, range, .. } => {
+                    storage_accesses.extend(array.collect_storage_accesses(contract));
+                    storage_accesses.extend(range.collect_storage_accesses(contract));
+                }
+
+                Expr::RangeArray { range, .. } => {
+                    storage_accesses.extend(range.collect_storage_accesses(contract));
+                }
+
                 Expr::TupleFieldAccess { tuple, .. } => {
                     storage_accesses.extend(tuple.collect_storage_accesses(contract));
                     if storage_accesses.remove(tuple) {
@@ -553,6 +618,13 @@ impl<'a> Iterator for ExprsIter<'a> {
                 queue_if_new!(self, index);
             }
 
+            Expr::ArraySlice { array, range, .. } => {
+                queue_if_new!(self, array);
+                queue_if_new!(self, range);
+            }
+
+            Expr::RangeArray { range, .. } => queue_if_new!(self, range),
+
             Expr::TupleFieldAccess { tuple, .. } => queue_if_new!(self, tuple),
 
             Expr::Cast { value, .. } => queue_if_new!(self, value),
@@ -0,0 +1,213 @@
+use super::*;
+use crate::expr::{Expr, UnaryOp};
+
+use serde_json::{json, Value};
+
+impl Contract {
+    /// Builds a JSON representation of the contract's predicates, intended for inspecting the
+    /// flattened IR from other tools.  Each predicate lists its decision variables, its state
+    /// variables and its constraints, with every expression rendered as a tree of nodes tagged by
+    /// `kind`.
+    ///
+    /// Expressions which should no longer exist after flattening (e.g., `match` or generators)
+    /// are emitted as `{ "kind": "other", "text": .. }` using their source form.
+    pub fn to_ir_json(&self) -> Value {
+        let preds = self
+            .preds
+            .values()
+            .map(|pred| pred.to_ir_json(self))
+            .collect::<Vec<_>>();
+
+        json!({ "predicates": preds })
+    }
+
+    fn expr_to_ir_json(&self, expr_key: &ExprKey) -> Value {
+        let Some(expr) = expr_key.try_get(self) else {
+            return json!({ "kind": "error" });
+        };
+
+        let mut node = match expr {
+            Expr::Immediate { value, .. } => json!({
+                "kind": "immediate",
+                "value": self.with_ctrct(value).to_string(),
+            }),
+
+            Expr::Array { elements, .. } => json!({
+                "kind": "array",
+                "elements": self.exprs_to_ir_json(elements),
+            }),
+
+            Expr::Tuple { fields, .. } => json!({
+                "kind": "tuple",
+                "fields": fields
+                    .iter()
+                    .map(|(name, value)| json!({
+                        "name": name.as_ref().map(|name| name.name.clone()),
+                        "value": self.expr_to_ir_json(value),
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+
+            Expr::UnionVariant { path, value, .. } => json!({
+                "kind": "union_variant",
+                "path": path,
+                "value": value.as_ref().map(|value| self.expr_to_ir_json(value)),
+            }),
+
+            Expr::Path(path, _) => json!({
+                "kind": "path",
+                "path": path,
+            }),
+
+            Expr::StorageAccess { name, mutable, .. } => json!({
+                "kind": "storage_access",
+                "name": name,
+                "mutable": mutable,
+            }),
+
+            Expr::ExternalStorageAccess {
+                interface_instance,
+                name,
+                ..
+            } => json!({
+                "kind": "external_storage_access",
+                "interface_instance": interface_instance,
+                "name": name,
+            }),
+
+            Expr::UnaryOp { op, expr, .. } => json!({
+                "kind": "unary_op",
+                "op": match op {
+                    UnaryOp::Error => "error",
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::NextState => "'",
+                },
+                "expr": self.expr_to_ir_json(expr),
+            }),
+
+            Expr::BinaryOp { op, lhs, rhs, .. } => json!({
+                "kind": "binary_op",
+                "op": op.to_string(),
+                "lhs": self.expr_to_ir_json(lhs),
+                "rhs": self.expr_to_ir_json(rhs),
+            }),
+
+            Expr::IntrinsicCall {
+                kind: (kind, _),
+                args,
+                ..
+            } => json!({
+                "kind": "intrinsic_call",
+                "name": kind.to_string(),
+                "args": self.exprs_to_ir_json(args),
+            }),
+
+            Expr::Select {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => json!({
+                "kind": "select",
+                "condition": self.expr_to_ir_json(condition),
+                "then": self.expr_to_ir_json(then_expr),
+                "else": self.expr_to_ir_json(else_expr),
+            }),
+
+            Expr::Index { expr, index, .. } => json!({
+                "kind": "index",
+                "expr": self.expr_to_ir_json(expr),
+                "index": self.expr_to_ir_json(index),
+            }),
+
+            Expr::TupleFieldAccess { tuple, field, .. } => json!({
+                "kind": "tuple_field_access",
+                "tuple": self.expr_to_ir_json(tuple),
+                "field": field.to_string(),
+            }),
+
+            Expr::Cast { value, ty, .. } => json!({
+                "kind": "cast",
+                "value": self.expr_to_ir_json(value),
+                "to": self.with_ctrct(ty).to_string(),
+            }),
+
+            Expr::UnionTag { union_expr, .. } => json!({
+                "kind": "union_tag",
+                "union_expr": self.expr_to_ir_json(union_expr),
+            }),
+
+            Expr::UnionValue {
+                union_expr,
+                variant_ty,
+                ..
+            } => json!({
+                "kind": "union_value",
+                "union_expr": self.expr_to_ir_json(union_expr),
+                "variant_ty": self.with_ctrct(variant_ty).to_string(),
+            }),
+
+            Expr::Error(_)
+            | Expr::MacroCall { .. }
+            | Expr::Match { .. }
+            | Expr::ArraySlice { .. }
+            | Expr::RangeArray { .. }
+            | Expr::In { .. }
+            | Expr::Range { .. }
+            | Expr::Generator { .. } => json!({
+                "kind": "other",
+                "text": self.with_ctrct(expr).to_string(),
+            }),
+        };
+
+        node["ty"] = Value::String(self.with_ctrct(expr_key.get_ty(self)).to_string());
+        node
+    }
+
+    fn exprs_to_ir_json(&self, expr_keys: &[ExprKey]) -> Vec<Value> {
+        expr_keys
+            .iter()
+            .map(|expr_key| self.expr_to_ir_json(expr_key))
+            .collect()
+    }
+}
+
+impl Predicate {
+    fn to_ir_json(&self, contract: &Contract) -> Value {
+        let vars = self
+            .vars()
+            .map(|(var_key, var)| {
+                json!({
+                    "name": var.name,
+                    "ty": contract.with_ctrct(var_key.get_ty(self)).to_string(),
+                    "is_pub": var.is_pub,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let states = self
+            .states()
+            .map(|(state_key, state)| {
+                json!({
+                    "name": state.name,
+                    "ty": contract.with_ctrct(state_key.get_ty(self)).to_string(),
+                    "expr": contract.expr_to_ir_json(&state.expr),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|constraint| contract.expr_to_ir_json(&constraint.expr))
+            .collect::<Vec<_>>();
+
+        json!({
+            "name": self.name,
+            "vars": vars,
+            "states": states,
+            "constraints": constraints,
+        })
+    }
+}
@@ -1,16 +1,39 @@
 mod const_folding;
 mod dead_code_elimination;
+mod duplicate_constraint_elimination;
+mod strength_reduction;
+mod var_inlining;
 
 use const_folding::const_folding;
-use dead_code_elimination::dead_code_elimination;
+use dead_code_elimination::{dead_code_elimination, dead_constraint_elimination};
+use duplicate_constraint_elimination::duplicate_constraint_elimination;
+use strength_reduction::strength_reduction;
+use var_inlining::inline_single_use_vars;
 
 use crate::error::Handler;
 
 impl super::Contract {
     pub fn optimize(mut self, handler: &Handler) -> Self {
-        dead_code_elimination(handler, &mut self);
+        dead_code_elimination(&mut self);
 
-        const_folding(&mut self);
+        const_folding(handler, &mut self);
+
+        // Constant folding may reduce more constraints to `true` or `false`, so only check for
+        // trivial constraints now.
+        dead_constraint_elimination(handler, &mut self);
+
+        strength_reduction(&mut self);
+
+        duplicate_constraint_elimination(&mut self);
+
+        self
+    }
+
+    /// Inline every non-`pub` variable which is initialised with a pure expression and used only
+    /// once.  This removes decision variables and so changes the solution layout of the affected
+    /// predicates, which is why it isn't part of `optimize()`.
+    pub fn inline_vars(mut self) -> Self {
+        inline_single_use_vars(&mut self);
 
         self
     }
@@ -1,30 +1,48 @@
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 
 use crate::{
-    error::Handler,
-    expr::{evaluate::Evaluator, Expr},
+    error::{CompileError, Error, Handler},
+    expr::{evaluate::Evaluator, BinaryOp, Expr, Immediate},
     predicate::{Contract, ExprKey},
     span::Spanned,
     types::Type,
 };
 
-/// In a given contract, simplify all sub-expressions that evaluate to constants.
-pub(crate) fn const_folding(contract: &mut Contract) {
-    fold_consts(contract);
+/// In a given contract, simplify all sub-expressions that evaluate to constants.  Any expression
+/// left evaluating to an integer which doesn't fit in an `int` is reported as an overflow.
+pub(crate) fn const_folding(handler: &Handler, contract: &mut Contract) {
+    // Short-circuit folding may expose new constant sub-expressions, e.g., `!(false && x)`, so
+    // keep going until it has nothing left to do.
+    loop {
+        fold_consts(contract);
+
+        if !fold_short_circuits(contract) {
+            break;
+        }
+    }
+
+    report_int_overflows(handler, contract);
 }
 
 pub(crate) fn fold_consts(contract: &mut Contract) {
-    let mut replace_map: FxHashMap<ExprKey, (Expr, Type)> = FxHashMap::default();
-
     let evaluator = Evaluator::new(contract);
 
     for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let mut replace_map: FxHashMap<ExprKey, (Expr, Type)> = FxHashMap::default();
+
         for expr_key in contract.exprs(pred_key) {
             if expr_key.get(contract).is_immediate() {
                 continue;
             }
 
+            // Intermediate results may overflow `i64` so long as the final result fits, e.g.,
+            // `(9223372036854775807 + 1) - 1`.  An overflowed result can't be written as an
+            // `int` literal so leave it alone and let `report_int_overflows()` deal with it.
             if let Ok(imm) = evaluator.evaluate_key(&expr_key, &Handler::default(), contract) {
+                if matches!(imm, Immediate::OverflowedInt(_)) {
+                    continue;
+                }
+
                 let simplified_expr = Expr::Immediate {
                     value: imm.clone(),
                     span: expr_key.get(contract).span().clone(),
@@ -46,3 +64,112 @@ pub(crate) fn fold_consts(contract: &mut Contract) {
         }
     }
 }
+
+/// Report every expression which evaluates to an integer that overflows `i64`.  Only the innermost
+/// such expression is reported, i.e., not those which overflow only because a sub-expression does.
+fn report_int_overflows(handler: &Handler, contract: &Contract) {
+    let evaluator = Evaluator::new(contract);
+
+    for pred_key in contract.preds.keys() {
+        let overflowed: FxHashSet<ExprKey> = contract
+            .exprs(pred_key)
+            .filter(|expr_key| {
+                matches!(
+                    evaluator.evaluate_key(expr_key, &Handler::default(), contract),
+                    Ok(Immediate::OverflowedInt(_))
+                )
+            })
+            .collect();
+
+        for expr_key in contract.exprs(pred_key) {
+            if !overflowed.contains(&expr_key) {
+                continue;
+            }
+
+            let mut child_overflowed = false;
+            contract.for_each_expr_child(expr_key, |child| {
+                child_overflowed |= overflowed.contains(&child)
+            });
+            if !child_overflowed {
+                handler.emit_err(Error::Compile {
+                    error: CompileError::IntegerOverflow {
+                        span: expr_key.get(contract).span().clone(),
+                    },
+                });
+            }
+        }
+    }
+}
+
+/// Simplify logical `&&` and `||` expressions which have a constant `bool` operand.
+///
+/// When the constant is the `lhs` the `rhs` is never evaluated, so `true && y` and `false || y`
+/// become `y` while `false && y` and `true || y` become the constant.  When the constant is the
+/// `rhs`, `x && true` and `x || false` become `x`, but `x && false` and `x || true` are only
+/// reduced to the constant if `x` cannot panic, since `x` is always evaluated.
+///
+/// Constraints which are simplified to `true` or `false` are left for
+/// `dead_constraint_elimination()` to report and remove.  Returns whether any expression was
+/// simplified.
+pub(crate) fn fold_short_circuits(contract: &mut Contract) -> bool {
+    let as_bool = |expr_key: &ExprKey, contract: &Contract| match expr_key.get(contract) {
+        Expr::Immediate {
+            value: Immediate::Bool(b),
+            ..
+        } => Some(*b),
+        _ => None,
+    };
+
+    let mut simplified = false;
+
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        // Simplifying one expression may expose another, e.g., `(x && false) || y`, so repeat
+        // until nothing changes.
+        loop {
+            let pred = &contract.preds[pred_key];
+            let mut replace_map: FxHashMap<ExprKey, ExprKey> = FxHashMap::default();
+
+            for expr_key in contract.exprs(pred_key) {
+                let Expr::BinaryOp { op, lhs, rhs, .. } = expr_key.get(contract) else {
+                    continue;
+                };
+
+                let replacement = match (op, as_bool(lhs, contract), as_bool(rhs, contract)) {
+                    (BinaryOp::LogicalAnd, Some(true), _)
+                    | (BinaryOp::LogicalOr, Some(false), _) => *rhs,
+                    (BinaryOp::LogicalAnd, Some(false), _)
+                    | (BinaryOp::LogicalOr, Some(true), _) => *lhs,
+                    (BinaryOp::LogicalAnd, None, Some(true))
+                    | (BinaryOp::LogicalOr, None, Some(false)) => *lhs,
+                    (BinaryOp::LogicalAnd, None, Some(false))
+                    | (BinaryOp::LogicalOr, None, Some(true))
+                        if !lhs.can_panic(contract, pred) =>
+                    {
+                        *rhs
+                    }
+                    _ => continue,
+                };
+
+                replace_map.insert(expr_key, replacement);
+            }
+
+            if replace_map.is_empty() {
+                break;
+            }
+
+            simplified = true;
+
+            for (old_expr_key, new_expr_key) in &replace_map {
+                // The replacement may itself be replaced, so follow the chain to its end.
+                let mut new_expr_key = *new_expr_key;
+                while let Some(next_expr_key) = replace_map.get(&new_expr_key) {
+                    new_expr_key = *next_expr_key;
+                }
+
+                contract.replace_exprs(Some(pred_key), *old_expr_key, new_expr_key);
+            }
+        }
+    }
+
+    simplified
+}
@@ -1,7 +1,7 @@
 use fxhash::{FxHashMap, FxHashSet};
 
 use crate::{
-    error::Handler,
+    error::{CompileError, Error, Handler},
     expr::{evaluate::Evaluator, Expr, Immediate},
     predicate::{ConstraintDecl, Contract, ExprKey, StateKey},
     span::empty_span,
@@ -10,11 +10,10 @@ use crate::{
 
 /// In a given contract, remove any code that is not reachable or used.
 ///
-/// If an error occurs, the specific optimization process is aborted to ensure the contract remains
-/// functional.
-pub(crate) fn dead_code_elimination(handler: &Handler, contract: &mut Contract) {
+/// Trivial constraints are removed separately by `dead_constraint_elimination()`, once constant
+/// folding has had a chance to reduce more of them to `true` or `false`.
+pub(crate) fn dead_code_elimination(contract: &mut Contract) {
     dead_state_elimination(contract);
-    dead_constraint_elimination(handler, contract);
     dead_select_elimination(contract);
 }
 
@@ -53,8 +52,9 @@ pub(crate) fn dead_state_elimination(contract: &mut Contract) {
 
 /// Remove all trivial Constraints in their respective predicates.
 ///
-/// If any constraint evaluates to false, all constraints are removed and replaced with a single
-/// instance of `constraint false`
+/// Constraints which always evaluate to true are removed with a warning.  Any constraint which
+/// always evaluates to false makes its predicate unsatisfiable and is reported as an error.  In that
+/// case all constraints are removed and replaced with a single instance of `constraint false`.
 pub(crate) fn dead_constraint_elimination(handler: &Handler, contract: &mut Contract) {
     let evaluator = Evaluator::new(contract);
 
@@ -74,11 +74,17 @@ pub(crate) fn dead_constraint_elimination(handler: &Handler, contract: &mut Cont
                     if let Ok(Immediate::Bool(b)) =
                         evaluator.evaluate_key(&constraint.expr, &Handler::default(), contract)
                     {
-                        if !b {
-                            has_false_constraint = true;
-                            handler.emit_warn(Warning::AlwaysFalseConstraint {
+                        if b {
+                            handler.emit_warn(Warning::AlwaysTrueConstraint {
                                 span: constraint.span.clone(),
                             });
+                        } else {
+                            has_false_constraint = true;
+                            handler.emit_err(Error::Compile {
+                                error: CompileError::UnsatisfiableConstraint {
+                                    span: constraint.span.clone(),
+                                },
+                            });
                         }
 
                         Some(i)
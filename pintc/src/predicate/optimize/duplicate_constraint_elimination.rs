@@ -0,0 +1,24 @@
+use crate::predicate::{ConstraintDecl, Contract};
+
+/// In a given contract, remove all constraints that are structurally identical to an earlier
+/// constraint in the same predicate. The first occurrence, along with its span, is kept.
+///
+/// Duplicate constraints generally have distinct `ExprKey`s, so they are compared using
+/// `Expr::struct_eq()` which recurses into their sub-expressions and ignores spans.
+pub(crate) fn duplicate_constraint_elimination(contract: &mut Contract) {
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let mut unique_constraints: Vec<ConstraintDecl> = Vec::new();
+
+        for constraint in &contract.preds[pred_key].constraints {
+            let expr = constraint.expr.get(contract);
+            if !unique_constraints
+                .iter()
+                .any(|unique| unique.expr.get(contract).struct_eq(expr, contract))
+            {
+                unique_constraints.push(constraint.clone());
+            }
+        }
+
+        contract.preds[pred_key].constraints = unique_constraints;
+    }
+}
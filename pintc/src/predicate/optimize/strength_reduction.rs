@@ -0,0 +1,149 @@
+use crate::{
+    expr::{BinaryOp, Expr, ExternalIntrinsic, Immediate, IntrinsicKind},
+    predicate::{Contract, ExprKey},
+};
+
+/// Replace integer multiplications and divisions by a constant power of two with the equivalent,
+/// cheaper, shifts.
+///
+/// `x * 8` and `8 * x` only become `x << 3` when `x` is known to be small enough for the shift not
+/// to overflow, e.g., `(x % 100) * 8`, since `<<` wraps on overflow whereas `*` panics.
+///
+/// `x / 4` only becomes `x >> 2` when `x` is known to be non-negative, since `>>` rounds towards
+/// negative infinity whereas `/` rounds towards zero.
+///
+/// Negative constants are never replaced.
+pub(crate) fn strength_reduction(contract: &mut Contract) {
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let reductions = contract
+            .exprs(pred_key)
+            .filter(|expr_key| expr_key.get_ty(contract).is_int())
+            .filter_map(|expr_key| match expr_key.get(contract) {
+                Expr::BinaryOp {
+                    op: BinaryOp::Mul,
+                    lhs,
+                    rhs,
+                    span,
+                } => power_of_two_exponent(rhs, contract)
+                    .map(|exp| (*lhs, exp))
+                    .or_else(|| power_of_two_exponent(lhs, contract).map(|exp| (*rhs, exp)))
+                    .filter(|(value, exp)| {
+                        significant_bits(value, contract).is_some_and(|bits| bits + exp <= 63)
+                    })
+                    .map(|(value, exp)| (expr_key, BinaryOp::ShiftLeft, value, exp, span.clone())),
+
+                Expr::BinaryOp {
+                    op: BinaryOp::Div,
+                    lhs,
+                    rhs,
+                    span,
+                } if is_non_negative(lhs, contract) => power_of_two_exponent(rhs, contract)
+                    .map(|exp| (expr_key, BinaryOp::ShiftRight, *lhs, exp, span.clone())),
+
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (old_expr_key, op, value, exp, span) in reductions {
+            let ty = old_expr_key.get_ty(contract).clone();
+            let exp_key = contract.exprs.insert(
+                Expr::Immediate {
+                    value: Immediate::Int(exp),
+                    span: span.clone(),
+                },
+                ty.clone(),
+            );
+            let shift_key = contract.exprs.insert(
+                Expr::BinaryOp {
+                    op,
+                    lhs: value,
+                    rhs: exp_key,
+                    span,
+                },
+                ty,
+            );
+
+            contract.replace_exprs(Some(pred_key), old_expr_key, shift_key);
+        }
+    }
+}
+
+/// If `expr_key` is an integer immediate which is a power of two greater than one then return its
+/// base two logarithm.
+fn power_of_two_exponent(expr_key: &ExprKey, contract: &Contract) -> Option<i64> {
+    match expr_key.get(contract) {
+        Expr::Immediate {
+            value: Immediate::Int(val),
+            ..
+        } if *val > 1 && val.count_ones() == 1 => Some(val.trailing_zeros() as i64),
+        _ => None,
+    }
+}
+
+/// If `expr_key` is known to always evaluate to an integer in the range `-2^bits..2^bits` then
+/// return `bits`.
+fn significant_bits(expr_key: &ExprKey, contract: &Contract) -> Option<i64> {
+    let int_immediate = |expr_key: &ExprKey| match expr_key.get(contract) {
+        Expr::Immediate {
+            value: Immediate::Int(val),
+            ..
+        } => Some(*val),
+        _ => None,
+    };
+
+    match expr_key.get(contract) {
+        Expr::Immediate {
+            value: Immediate::Int(val),
+            ..
+        } => Some(64 - (if *val < 0 { !val } else { *val }).leading_zeros() as i64),
+
+        // The remainder is always smaller in magnitude than the divisor.
+        Expr::BinaryOp {
+            op: BinaryOp::Mod,
+            rhs,
+            ..
+        } => int_immediate(rhs)
+            .filter(|divisor| *divisor != 0)
+            .map(|divisor| 64 - (divisor.unsigned_abs() - 1).leading_zeros() as i64),
+
+        Expr::BinaryOp {
+            op: BinaryOp::ShiftRight,
+            lhs,
+            rhs,
+            ..
+        } => int_immediate(rhs)
+            .filter(|shift| (0..64).contains(shift))
+            .map(|shift| (significant_bits(lhs, contract).unwrap_or(63) - shift).max(0)),
+
+        _ => None,
+    }
+}
+
+/// Whether `expr_key` is known to always evaluate to a non-negative integer.
+fn is_non_negative(expr_key: &ExprKey, contract: &Contract) -> bool {
+    match expr_key.get(contract) {
+        Expr::Immediate {
+            value: Immediate::Int(val),
+            ..
+        } => *val >= 0,
+
+        Expr::IntrinsicCall {
+            kind: (IntrinsicKind::External(kind), _),
+            ..
+        } => matches!(
+            kind,
+            ExternalIntrinsic::Length
+                | ExternalIntrinsic::SizeOf
+                | ExternalIntrinsic::ThisPathway
+                | ExternalIntrinsic::VecLen
+        ),
+
+        Expr::BinaryOp {
+            op: BinaryOp::ShiftRight,
+            lhs,
+            ..
+        } => is_non_negative(lhs, contract),
+
+        _ => false,
+    }
+}
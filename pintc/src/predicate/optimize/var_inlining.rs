@@ -0,0 +1,111 @@
+use fxhash::FxHashMap;
+
+use crate::{
+    expr::{BinaryOp, Expr},
+    predicate::{Contract, ExprKey, VisitorKind},
+};
+
+/// In a given contract, inline any non-`pub` variable which is initialised with a pure expression
+/// and is then used exactly once.
+///
+/// A `var z = e;` declaration is represented by the variable `z`, its initialiser `e` and the
+/// constraint `z == e`.  If `z` is referenced in just one other place then that reference is
+/// replaced with `e` and both the variable and its defining constraint are removed.
+///
+/// Initialisers which may panic, such as those which read state, are never inlined since moving
+/// them could change whether they are evaluated at all.
+pub(crate) fn inline_single_use_vars(contract: &mut Contract) {
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        // Collect every reference to every path.  Unlike `exprs()` the visitor doesn't skip keys
+        // which it has already seen, so a shared path is counted once for each of its parents.
+        let mut path_refs: FxHashMap<String, Vec<ExprKey>> = FxHashMap::default();
+        contract.visitor(
+            pred_key,
+            VisitorKind::DepthFirstParentsBeforeChildren,
+            |expr_key, expr| {
+                if let Expr::Path(name, _) = expr {
+                    path_refs.entry(name.clone()).or_default().push(expr_key);
+                }
+            },
+        );
+
+        let pred = &contract.preds[pred_key];
+
+        let mut inlined_vars = Vec::new();
+        let mut dead_constraints = Vec::new();
+        let mut replace_map: FxHashMap<ExprKey /* use */, ExprKey /* init */> =
+            FxHashMap::default();
+
+        for (var_key, var) in pred.vars() {
+            // Only primitive values are inlined.  Aggregates are left alone since an inlined
+            // array or tuple expression may appear in positions which flattening never produces.
+            if var.is_pub || !var_key.get_ty(pred).is_any_primitive() {
+                continue;
+            }
+
+            let Some(&init_key) = pred.var_inits.get(var_key) else {
+                continue;
+            };
+
+            // Find the defining constraint `z == e`.
+            let Some((constraint_idx, def_key)) = pred.constraints.iter().enumerate().find_map(
+                |(idx, constraint)| match constraint.expr.get(contract) {
+                    Expr::BinaryOp {
+                        op: BinaryOp::Equal,
+                        lhs,
+                        rhs,
+                        ..
+                    } if *rhs == init_key
+                        && matches!(lhs.get(contract), Expr::Path(name, _) if *name == var.name) =>
+                    {
+                        Some((idx, *lhs))
+                    }
+                    _ => None,
+                },
+            ) else {
+                continue;
+            };
+
+            // Apart from the defining constraint there must be exactly one other reference.
+            let Some(use_key) = path_refs
+                .get(&var.name)
+                .filter(|refs| refs.len() == 2)
+                .and_then(|refs| refs.iter().find(|key| **key != def_key))
+            else {
+                continue;
+            };
+
+            if init_key.can_panic(contract, pred) {
+                continue;
+            }
+
+            inlined_vars.push(var_key);
+            dead_constraints.push(constraint_idx);
+            replace_map.insert(*use_key, init_key);
+        }
+
+        // An initialiser may itself be a use of another inlined variable, e.g., `var y = z;`, in
+        // which case it must be resolved all the way through to the final initialiser.
+        for (use_key, init_key) in &replace_map {
+            let mut new_key = *init_key;
+            while let Some(next_key) = replace_map.get(&new_key) {
+                new_key = *next_key;
+            }
+            contract.replace_exprs(Some(pred_key), *use_key, new_key);
+        }
+
+        if let Some(pred) = contract.preds.get_mut(pred_key) {
+            // Remove the defining constraints in reverse so that the earlier indices stay valid.
+            dead_constraints.sort_unstable();
+            dead_constraints.iter().rev().for_each(|idx| {
+                pred.constraints.remove(*idx);
+            });
+
+            for var_key in inlined_vars {
+                pred.vars.remove(var_key);
+                pred.var_inits.remove(var_key);
+                pred.var_docs.remove(var_key);
+            }
+        }
+    }
+}
@@ -0,0 +1,914 @@
+use super::{CompileOptions, Contract, Predicate};
+use crate::{
+    error::{Handler, ReportableError},
+    expr::Expr,
+    parser::parse_project_from_str,
+    span::Spanned,
+    types::Type,
+    warning::ReportableWarning,
+};
+use pint_abi_types::TypeABI;
+use std::path::Path;
+
+/// Parse `src` as the root module of a contract, panicking if it fails to parse.  Any warnings
+/// are left in `handler`.
+fn parse_str(handler: &Handler, src: &str) -> Contract {
+    let deps = Default::default();
+    parse_project_from_str(handler, &deps, Path::new("<string>"), src).unwrap()
+}
+
+#[test]
+fn predicate_by_name() {
+    let mut contract = Contract::default();
+    let foo_key = contract.preds.insert(Predicate::new("::Foo".to_string()));
+    let bar_key = contract.preds.insert(Predicate::new("::Bar".to_string()));
+
+    let (key, pred) = contract.predicate_by_name("::Foo").unwrap();
+    assert_eq!(key, foo_key);
+    assert_eq!(pred.name, "::Foo");
+
+    let (key, pred) = contract.predicate_by_name("::Bar").unwrap();
+    assert_eq!(key, bar_key);
+    assert_eq!(pred.name, "::Bar");
+
+    assert!(contract.predicate_by_name("Foo").is_none());
+    assert!(contract.predicate_by_name("::Baz").is_none());
+
+    let (key, pred) = contract.predicate_by_name_mut("::Bar").unwrap();
+    assert_eq!(key, bar_key);
+    pred.name = "::Baz".to_string();
+    assert!(contract.predicate_by_name("::Bar").is_none());
+    assert_eq!(contract.predicate_by_name("::Baz").unwrap().0, bar_key);
+}
+
+#[test]
+fn rename_predicate() {
+    let handler = Handler::default();
+    let src = "predicate Foo { pub var x: int; constraint x == 1; } \
+        predicate Bar { predicate FooI = Foo(); constraint FooI::x == 1; }";
+    let mut contract = parse_str(&handler, src);
+
+    // Renaming to an existing symbol is a clash and renames nothing.
+    assert!(contract
+        .rename_predicate(&handler, "::Foo", "::Bar".to_string())
+        .is_err());
+    assert!(handler.consume().0[0]
+        .display_raw()
+        .starts_with("symbol `::Bar` has already been declared\n"));
+    assert!(contract.predicate_by_name("::Foo").is_some());
+
+    // Renaming a missing predicate fails too.
+    let handler = Handler::default();
+    assert!(contract
+        .rename_predicate(&handler, "::Qux", "::Quux".to_string())
+        .is_err());
+
+    let handler = Handler::default();
+    contract
+        .rename_predicate(&handler, "::Foo", "::Baz".to_string())
+        .unwrap();
+    assert!(contract.predicate_by_name("::Foo").is_none());
+    assert!(contract.predicate_by_name("::Baz").is_some());
+
+    // The instance in `Bar` now refers to the new name.
+    let (_, bar) = contract.predicate_by_name("::Bar").unwrap();
+    assert_eq!(bar.predicate_instances[0].predicate.name, "Baz");
+
+    // The symbols have been updated too, so the old name may be reused and the new name clashes.
+    assert!(contract
+        .rename_predicate(&handler, "::Bar", "::Foo".to_string())
+        .is_ok());
+    assert!(contract
+        .rename_predicate(&handler, "::Foo", "::Baz".to_string())
+        .is_err());
+    let handler = Handler::default();
+
+    // And the renamed contract still compiles.
+    contract
+        .compile(&handler, &CompileOptions::default())
+        .unwrap();
+}
+
+#[test]
+fn unused_vars() {
+    let handler = Handler::default();
+    let src =
+        "predicate test { var used: int; var unused: int; var _ignored: int; pub var p: int; \
+        constraint used > 0; }";
+    parse_str(&handler, src)
+        .compile(&handler, &CompileOptions::default())
+        .unwrap();
+
+    // The `pub var` isn't reported as unused, nor as unreferenced since `test` is never
+    // instantiated and so `p` may be read by another contract.
+    let warnings = handler.consume().1;
+    assert_eq!(warnings.len(), 1);
+    expect_test::expect![[r#"
+        unused variable `unused`
+        @36..42: this variable is never used
+        if this is intentional, consider prefixing it with an underscore: `_unused`
+    "#]]
+    .assert_eq(&warnings[0].display_raw());
+}
+
+#[test]
+fn unreferenced_pub_vars() {
+    let compile = |src: &str, handler: &Handler| {
+        parse_str(handler, src)
+            .compile(handler, &CompileOptions::default())
+            .unwrap();
+    };
+
+    // `y` is never read through an instance of `Foo`.
+    let handler = Handler::default();
+    compile(
+        "predicate Foo { pub var x: int; pub var y: int; } \
+        predicate Bar { predicate FooI = Foo(); constraint FooI::x == 1; }",
+        &handler,
+    );
+
+    let warnings = handler.consume().1;
+    assert_eq!(warnings.len(), 1);
+    expect_test::expect![[r#"
+        unreferenced public variable `y`
+        @40..41: this `pub var` is never read by another predicate
+        if this is intentional, consider prefixing it with an underscore: `_y`
+    "#]]
+    .assert_eq(&warnings[0].display_raw());
+
+    // Reading `y` too silences the warning.
+    let handler = Handler::default();
+    compile(
+        "predicate Foo { pub var x: int; pub var y: int; } \
+        predicate Bar { predicate FooI = Foo(); constraint FooI::x == FooI::y; }",
+        &handler,
+    );
+    assert!(!handler.has_warnings());
+
+    // `Foo` is never instantiated locally, so its `pub var` is exposed to other contracts and
+    // isn't reported.
+    let handler = Handler::default();
+    compile(
+        "predicate Foo { pub var x: int; } predicate Bar { var y: int; constraint y == 1; }",
+        &handler,
+    );
+    assert!(!handler.has_warnings());
+}
+
+#[test]
+fn trivial_constraints() {
+    let compile = |src: &str, handler: &Handler| {
+        parse_str(handler, src).compile(handler, &CompileOptions::default())
+    };
+
+    // A constraint which is always `true` is removed with a warning.
+    let handler = Handler::default();
+    let contract = compile(
+        "predicate test { var x: int; constraint true; constraint x > 0; }",
+        &handler,
+    )
+    .unwrap();
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    let constraints = pred
+        .constraints
+        .iter()
+        .map(|constraint| contract.with_ctrct(constraint.expr).to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        constraints,
+        vec!["(::x > 0)", "__eq_set(__mut_keys(), {0})"]
+    );
+
+    let warnings = handler.consume().1;
+    assert_eq!(warnings.len(), 1);
+    expect_test::expect![[r#"
+        constraint is always `true`
+        @29..44: this constraint always evaluates to `true` and has no effect
+        this constraint has been removed and may be deleted from the source
+    "#]]
+    .assert_eq(&warnings[0].display_raw());
+
+    // A constraint which is always `false` is an error.
+    let handler = Handler::default();
+    assert!(compile(
+        "predicate test { var x: int; constraint 1 > 2; constraint x > 0; }",
+        &handler,
+    )
+    .is_err());
+
+    let errors = handler.consume().0;
+    assert_eq!(errors.len(), 1);
+    expect_test::expect![[r#"
+        constraint is always `false`
+        @29..45: this constraint always evaluates to `false`
+        a predicate containing an unsatisfiable constraint can never be solved
+    "#]]
+    .assert_eq(&errors[0].display_raw());
+
+    // A constraint which is only reduced to `false` by constant folding is also an error.
+    let handler = Handler::default();
+    assert!(compile(
+        "predicate test { var x: int; constraint x > 0 && false; }",
+        &handler,
+    )
+    .is_err());
+
+    let errors = handler.consume().0;
+    assert_eq!(errors.len(), 1);
+    expect_test::expect![[r#"
+        constraint is always `false`
+        @29..54: this constraint always evaluates to `false`
+        a predicate containing an unsatisfiable constraint can never be solved
+    "#]]
+    .assert_eq(&errors[0].display_raw());
+}
+
+#[test]
+fn chained_comparisons() {
+    let handler = Handler::default();
+    let src = "predicate test { var a: int; var b: int; var c: int; var d: int; \
+        constraint a < b < c; constraint a <= b < c >= d; }";
+    let contract = parse_str(&handler, src).type_check(&handler).unwrap();
+
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    let constraints = pred
+        .constraints
+        .iter()
+        .map(|constraint| contract.with_ctrct(constraint.expr).to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        constraints,
+        vec![
+            "((::a < ::b) && (::b < ::c))",
+            "(((::a <= ::b) && (::b < ::c)) && (::c >= ::d))"
+        ]
+    );
+
+    let warnings = handler.consume().1;
+    assert_eq!(warnings.len(), 2);
+    expect_test::expect![[r#"
+        chained comparison
+        @76..85: this chain of comparisons has been expanded
+        `a < b < c` is treated as `a < b && b < c`; use that form explicitly to silence this warning
+    "#]]
+    .assert_eq(&warnings[0].display_raw());
+    expect_test::expect![[r#"
+        chained comparison
+        @98..113: this chain of comparisons has been expanded
+        `a < b < c` is treated as `a < b && b < c`; use that form explicitly to silence this warning
+    "#]]
+    .assert_eq(&warnings[1].display_raw());
+}
+
+#[test]
+fn merge_contracts() {
+    let parse = |src: &str| {
+        let handler = Handler::default();
+        parse_str(&handler, src)
+    };
+
+    let mut contract = parse("const a: int = 1;");
+    let handler = Handler::default();
+    contract
+        .merge(parse("const b: int[2] = [2, 3];"), &handler)
+        .unwrap();
+    assert!(!handler.has_errors());
+
+    let mut consts = contract
+        .consts
+        .iter()
+        .map(|(name, cnst)| {
+            format!(
+                "{name}: {} = {}",
+                contract.with_ctrct(&cnst.decl_ty),
+                contract.with_ctrct(cnst.expr)
+            )
+        })
+        .collect::<Vec<_>>();
+    consts.sort();
+    assert_eq!(consts, vec!["::a: int = 1", "::b: int[2] = [2, 3]"]);
+
+    // Merging a contract with a clashing symbol fails and merges nothing.
+    let handler = Handler::default();
+    assert!(contract.merge(parse("const a = 4;"), &handler).is_err());
+    assert_eq!(handler.consume().0.len(), 1);
+    assert_eq!(contract.consts.len(), 2);
+
+    // Storage is merged if only one contract declares it.
+    let handler = Handler::default();
+    contract
+        .merge(parse("storage { x: int, y: b256 }"), &handler)
+        .unwrap();
+    assert!(!handler.has_errors());
+    let (storage_vars, _) = contract.storage.as_ref().unwrap();
+    assert_eq!(storage_vars.len(), 2);
+
+    // Merging a second storage declaration fails and merges nothing.
+    let handler = Handler::default();
+    assert!(contract
+        .merge(parse("const c = 5; storage { z: int }"), &handler)
+        .is_err());
+    expect_test::expect![[r#"
+        `storage` block has already been declared
+        @0..27: previous declaration of a `storage` block here
+        @13..31: another `storage` block is declared here
+    "#]]
+    .assert_eq(&handler.consume().0[0].display_raw());
+    assert_eq!(contract.consts.len(), 2);
+}
+
+#[test]
+fn merge_type_checked_contracts() {
+    let type_check = |src: &str| {
+        let handler = Handler::default();
+        parse_str(&handler, src).type_check(&handler).unwrap()
+    };
+
+    // The union keys in both contracts start from the same slot and so must be remapped.
+    let mut contract = type_check("union A = X | Y; predicate Foo { var a: A; }");
+    let handler = Handler::default();
+    contract
+        .merge(
+            type_check("union C = M; union B = P | Q(C); predicate Bar { var b: B; }"),
+            &handler,
+        )
+        .unwrap();
+    assert!(!handler.has_errors());
+    assert_eq!(contract.unions.len(), 3);
+
+    let var_ty = |pred_name: &str| {
+        let (_, pred) = contract.predicate_by_name(pred_name).unwrap();
+        let (var_key, _) = pred.vars().next().unwrap();
+        contract.with_ctrct(var_key.get_ty(pred)).to_string()
+    };
+    assert_eq!(var_ty("::Foo"), "::A");
+    assert_eq!(var_ty("::Bar"), "::B");
+
+    let variant_ty = contract
+        .unions
+        .values()
+        .find(|union_decl| union_decl.name.name == "::B")
+        .and_then(|union_decl| union_decl.variants[1].ty.as_ref())
+        .unwrap();
+    assert_eq!(contract.with_ctrct(variant_ty).to_string(), "::C");
+    assert_eq!(var_ty("::Bar"), "::B");
+}
+
+#[test]
+fn iter_constraints() {
+    let handler = Handler::default();
+    let src = "predicate test { var x: int; constraint x > 0; if x > 5 { constraint x < 10; } }";
+    let contract = parse_str(&handler, src);
+    let (pred_key, _) = contract.predicate_by_name("::test").unwrap();
+
+    let constraints = contract
+        .iter_constraints(pred_key)
+        .map(|constraint| contract.with_ctrct(constraint.expr).to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(constraints, vec!["(::x > 0)", "(::x < 10)"]);
+}
+
+#[test]
+fn to_source_round_trip() {
+    let parse = |src: &str| {
+        let handler = Handler::default();
+        parse_str(&handler, src)
+    };
+
+    let src = r#"
+const c: int = 5;
+union Colour = Red | Green | Blue;
+type Pair = { int, int };
+
+predicate test {
+    var x: int = 3;
+    pub var col: Colour;
+    var p: Pair;
+    constraint x < c;
+    if x > 1 {
+        constraint p.0 == 1;
+    } else {
+        constraint col == Colour::Red;
+    }
+}
+"#;
+
+    let source = parse(src).to_source();
+    assert!(!source.contains("var ::"));
+    assert_eq!(parse(&source).to_source(), source);
+}
+
+#[test]
+fn try_storage_vars() {
+    let handler = Handler::default();
+    let src = "storage { x: int, y: bool, } interface Foo { storage { z: b256, } }";
+    let contract = parse_str(&handler, src);
+
+    let (idx, var) = contract.try_storage_var("y").unwrap();
+    assert_eq!(idx, 1);
+    assert_eq!(var.name.name, "y");
+    assert!(contract.try_storage_var("z").is_none());
+
+    let (idx, var) = contract.try_external_storage_var("::Foo", "z").unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(var.name.name, "z");
+    assert!(contract.try_external_storage_var("::Foo", "x").is_none());
+    assert!(contract.try_external_storage_var("::Bar", "z").is_none());
+
+    // Neither method panics when there is no storage block at all.
+    let contract = Contract::default();
+    assert!(contract.try_storage_var("x").is_none());
+    assert!(contract.try_external_storage_var("::Foo", "z").is_none());
+}
+
+#[test]
+fn var_and_state_counts() {
+    let compile = |src: &str| {
+        let handler = Handler::default();
+        parse_str(&handler, src)
+            .compile(&handler, &CompileOptions::default())
+            .unwrap()
+    };
+
+    // The decision variables from the `binary_ops` asm test.
+    let contract = compile(
+        r#"
+predicate test {
+    var x: int; var y: int; var z: int;
+    var b0: bool; var b1: bool;
+    constraint x + y == z;
+    constraint x > y;
+}
+"#,
+    );
+    let handler = Handler::default();
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    assert_eq!(pred.decision_var_count(), 5);
+    assert_eq!(pred.pub_var_count(), 0);
+    assert_eq!(pred.state_slot_count(&handler, &contract).unwrap(), 0);
+
+    // Each state needs as many slots as its type occupies in storage.
+    let contract = compile(
+        r#"
+storage {
+    x: int,
+    t: { int, b256, { bool, int } },
+    a: int[3],
+}
+
+predicate test {
+    var v: int;
+    pub var p: b256;
+    pub var q: bool;
+    state x = storage::x;
+    state t = storage::t;
+    state a = storage::a;
+    constraint v == x' + t'.0 + a'[0];
+    constraint p == t'.1 && q == t'.2.0;
+}
+"#,
+    );
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    assert_eq!(pred.decision_var_count(), 1);
+    assert_eq!(pred.pub_var_count(), 2);
+    assert_eq!(
+        pred.state_slot_count(&handler, &contract).unwrap(),
+        1 + 4 + 3
+    );
+
+    let abi = pred.abi(&handler, &contract).unwrap();
+    assert_eq!(abi.decision_var_count, abi.vars.len());
+    assert_eq!(abi.pub_var_count, abi.pub_vars.len());
+    assert_eq!(abi.state_slot_count, 8);
+}
+
+#[test]
+fn interface_storage_alias() {
+    let handler = Handler::default();
+    let src = r#"
+type Balance = int;
+
+interface Token {
+    storage {
+        bal: Balance,
+    }
+}
+
+predicate test {
+    interface TokenInstance = Token(0x0000000000000000000000000000000000000000000000000000000000000001);
+    state b: Balance = TokenInstance::storage::bal;
+    var v: Balance;
+    constraint v == b;
+}
+"#;
+    let contract = parse_str(&handler, src).type_check(&handler).unwrap();
+
+    // The interface storage var is still an alias after type checking, but its ABI is expanded.
+    let (_, bal) = contract.try_external_storage_var("::Token", "bal").unwrap();
+    assert!(matches!(bal.ty, Type::Alias { .. }));
+    assert_eq!(bal.ty.abi(&handler, &contract).unwrap(), TypeABI::Int);
+
+    // After flattening every alias, including in the interface, is lowered away.
+    let contract = contract.flatten(&handler).unwrap();
+    let (_, bal) = contract.try_external_storage_var("::Token", "bal").unwrap();
+    assert!(bal.ty.is_int());
+
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    assert_eq!(pred.state_slot_count(&handler, &contract).unwrap(), 1);
+    let abi = pred.abi(&handler, &contract).unwrap();
+    assert_eq!(abi.vars.len(), 1);
+    assert_eq!(abi.vars[0].ty, TypeABI::Int);
+    assert!(!handler.has_errors());
+}
+
+#[test]
+fn doc_comments_in_abi() {
+    let handler = Handler::default();
+    let src = r#"
+storage {
+    /// The current count.
+    /// Never negative.
+    count: int,
+    //// Not a doc comment.
+    total: int,
+}
+
+/// Increments the counter.
+predicate Increment {
+    /// The amount to add.
+    var amount: int;
+    // Not a doc comment either.
+    pub var result: int;
+    /// The new count.
+    pub var next: int;
+    state count = storage::count;
+    constraint result == count + amount;
+    constraint next == result;
+}
+"#;
+    let contract = parse_str(&handler, src)
+        .compile(&handler, &CompileOptions::default())
+        .unwrap();
+    let abi = contract.abi(&handler).unwrap();
+
+    assert_eq!(
+        abi.storage[0].doc.as_deref(),
+        Some("The current count.\nNever negative.")
+    );
+    assert_eq!(abi.storage[1].doc, None);
+
+    let pred_abi = &abi.predicates[0];
+    assert_eq!(pred_abi.doc.as_deref(), Some("Increments the counter."));
+    assert_eq!(pred_abi.vars[0].name, "::amount");
+    assert_eq!(pred_abi.vars[0].doc.as_deref(), Some("The amount to add."));
+    assert_eq!(pred_abi.pub_vars[0].doc, None);
+    assert_eq!(pred_abi.pub_vars[1].doc.as_deref(), Some("The new count."));
+
+    // Docs are serialized when present and omitted otherwise.
+    let json = serde_json::to_string(&abi).unwrap();
+    assert!(json.contains(r#""doc":"The amount to add.""#));
+    assert_eq!(json.matches(r#""doc""#).count(), 4);
+}
+
+#[test]
+fn inline_vars() {
+    let handler = Handler::default();
+    let src = r#"
+storage {
+    x: int,
+}
+
+predicate test {
+    var a: int;
+    // Used once, so inlined.
+    var once = a * 2;
+    // Used twice, so kept.
+    var twice = a + 1;
+    // Used once, but only via `chained` which is also inlined.
+    var inner = a - 3;
+    var chained = inner * 4;
+    // Reads state, so kept.
+    state s = storage::x;
+    var from_state = s + 1;
+    constraint once > 0;
+    constraint twice > 0;
+    constraint twice < 10;
+    constraint chained != 7;
+    constraint from_state > 0;
+}
+"#;
+    let contract = parse_str(&handler, src)
+        .compile(
+            &handler,
+            &CompileOptions {
+                inline_vars: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    assert_eq!(
+        pred.vars()
+            .map(|(_, var)| var.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["::a", "::twice", "::from_state"]
+    );
+
+    let display = format!("{contract}");
+    assert!(display.contains("constraint ((::a * 2) > 0);"));
+    assert!(display.contains("constraint (((::a - 3) * 4) != 7);"));
+    assert!(display.contains("constraint (::twice == (::a + 1));"));
+    assert!(display.contains("constraint (::from_state == (::s + 1));"));
+    assert!(!display.contains("::once"));
+    assert!(!display.contains("::inner"));
+    assert!(!display.contains("::chained"));
+}
+
+#[test]
+fn expr_can_panic() {
+    let handler = Handler::default();
+    let src = r#"
+storage {
+    x: int,
+}
+
+predicate test {
+    var a: int;
+    var b: int;
+    state s = storage::x;
+    constraint a / b > 0;
+    constraint a % b == 0;
+    constraint a / 2 > 0;
+    constraint a % 0 == 0;
+    constraint s == 3;
+    constraint a + 1 > 0;
+}
+"#;
+    let mut contract = parse_str(&handler, src)
+        .compile(
+            &handler,
+            &CompileOptions {
+                skip_optimize: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let immediate = contract.exprs.insert_int(42);
+
+    let (_, pred) = contract.predicate_by_name("::test").unwrap();
+    let constraint = |src: &str| {
+        pred.constraints
+            .iter()
+            .map(|constraint| constraint.expr)
+            .find(|expr| format!("{}", contract.with_ctrct(*expr)) == src)
+            .unwrap()
+    };
+
+    // Division and modulo can panic when the divisor may be zero.
+    assert!(constraint("((::a / ::b) > 0)").can_panic(&contract, pred));
+    assert!(constraint("((::a % ::b) == 0)").can_panic(&contract, pred));
+    assert!(constraint("((::a % 0) == 0)").can_panic(&contract, pred));
+    assert!(!constraint("((::a / 2) > 0)").can_panic(&contract, pred));
+
+    // Reading state can panic since the state may be `nil`.
+    assert!(constraint("(::s == 3)").can_panic(&contract, pred));
+
+    // Immediates and plain arithmetic on decision variables can't panic.
+    assert!(!immediate.can_panic(&contract, pred));
+    assert!(!constraint("((::a + 1) > 0)").can_panic(&contract, pred));
+}
+
+#[test]
+fn size_in_bytes() {
+    use crate::{span::empty_span, types::PrimitiveKind};
+
+    let contract = Contract::default();
+    let handler = Handler::default();
+
+    let int_ty = Type::Primitive {
+        kind: PrimitiveKind::Int,
+        span: empty_span(),
+    };
+    let b256_ty = Type::Primitive {
+        kind: PrimitiveKind::B256,
+        span: empty_span(),
+    };
+
+    let tuple_ty = Type::Tuple {
+        fields: vec![(None, int_ty), (None, b256_ty)],
+        span: empty_span(),
+    };
+    assert_eq!(tuple_ty.size_in_bytes(&handler, &contract).unwrap(), 40);
+
+    // Aliases, including those nested within tuples, are looked through.
+    let alias_ty = Type::Alias {
+        name: "::Pair".to_string(),
+        ty: Box::new(tuple_ty.clone()),
+        span: empty_span(),
+    };
+    assert_eq!(alias_ty.size_in_bytes(&handler, &contract).unwrap(), 40);
+
+    let nested_ty = Type::Tuple {
+        fields: vec![(None, alias_ty), (None, tuple_ty)],
+        span: empty_span(),
+    };
+    assert_eq!(nested_ty.size_in_bytes(&handler, &contract).unwrap(), 80);
+    assert!(!handler.has_errors());
+}
+
+// The `int` and `bool` types and a `::MyInt` alias of `int`, shared by the type relation tests.
+fn int_bool_alias_types() -> (Type, Type, Type) {
+    use crate::{span::empty_span, types::PrimitiveKind};
+
+    let int_ty = Type::Primitive {
+        kind: PrimitiveKind::Int,
+        span: empty_span(),
+    };
+    let bool_ty = Type::Primitive {
+        kind: PrimitiveKind::Bool,
+        span: empty_span(),
+    };
+    let alias_ty = Type::Alias {
+        name: "::MyInt".to_string(),
+        ty: Box::new(int_ty.clone()),
+        span: empty_span(),
+    };
+
+    (int_ty, bool_ty, alias_ty)
+}
+
+fn unnamed_tuple_ty(fields: Vec<Type>) -> Type {
+    Type::Tuple {
+        fields: fields.into_iter().map(|ty| (None, ty)).collect(),
+        span: crate::span::empty_span(),
+    }
+}
+
+#[test]
+fn common_supertype() {
+    let contract = Contract::default();
+    let (int_ty, bool_ty, alias_ty) = int_bool_alias_types();
+
+    assert!(int_ty
+        .common_supertype(&contract, &int_ty)
+        .is_some_and(|ty| ty.is_int() && ty.is_alias().is_none()));
+    assert!(int_ty.common_supertype(&contract, &bool_ty).is_none());
+    assert!(bool_ty.common_supertype(&contract, &int_ty).is_none());
+
+    // Aliases are resolved, unless both sides are the same alias.
+    assert!(alias_ty
+        .common_supertype(&contract, &int_ty)
+        .is_some_and(|ty| ty.is_int() && ty.is_alias().is_none()));
+    assert!(int_ty
+        .common_supertype(&contract, &alias_ty)
+        .is_some_and(|ty| ty.is_int() && ty.is_alias().is_none()));
+    assert!(alias_ty
+        .common_supertype(&contract, &alias_ty)
+        .is_some_and(|ty| ty.is_alias().is_some()));
+    assert!(alias_ty.common_supertype(&contract, &bool_ty).is_none());
+
+    // Tuples are compatible when their fields are.
+    let int_bool_ty = unnamed_tuple_ty(vec![int_ty.clone(), bool_ty.clone()]);
+    assert!(unnamed_tuple_ty(vec![alias_ty, bool_ty.clone()])
+        .common_supertype(&contract, &int_bool_ty)
+        .is_some_and(|ty| ty.eq(&contract, &int_bool_ty)));
+    assert!(unnamed_tuple_ty(vec![bool_ty, int_ty.clone()])
+        .common_supertype(&contract, &int_bool_ty)
+        .is_none());
+    assert!(unnamed_tuple_ty(vec![int_ty])
+        .common_supertype(&contract, &int_bool_ty)
+        .is_none());
+}
+
+#[test]
+fn type_is_assignable_from() {
+    let contract = Contract::default();
+    let (int_ty, bool_ty, alias_ty) = int_bool_alias_types();
+
+    assert!(int_ty.is_assignable_from(&contract, &int_ty));
+    assert!(!int_ty.is_assignable_from(&contract, &bool_ty));
+    assert!(!bool_ty.is_assignable_from(&contract, &int_ty));
+
+    // Aliases are resolved.
+    assert!(alias_ty.is_assignable_from(&contract, &int_ty));
+    assert!(int_ty.is_assignable_from(&contract, &alias_ty));
+    assert!(!alias_ty.is_assignable_from(&contract, &bool_ty));
+
+    // Tuples are assignable when they have the same structure.
+    let int_bool_ty = unnamed_tuple_ty(vec![int_ty.clone(), bool_ty.clone()]);
+    assert!(int_bool_ty.is_assignable_from(&contract, &int_bool_ty));
+    assert!(int_bool_ty.is_assignable_from(
+        &contract,
+        &unnamed_tuple_ty(vec![alias_ty, bool_ty.clone()])
+    ));
+    assert!(!int_bool_ty
+        .is_assignable_from(&contract, &unnamed_tuple_ty(vec![bool_ty, int_ty.clone()])));
+    assert!(!int_bool_ty.is_assignable_from(&contract, &unnamed_tuple_ty(vec![int_ty])));
+}
+
+#[test]
+fn to_dot() {
+    let handler = Handler::default();
+    let src = "predicate Foo { var x: int; constraint x + 1 == 2; }";
+    let contract = parse_str(&handler, src);
+    let (pred_key, _) = contract.predicate_by_name("::Foo").unwrap();
+    let dot = contract.to_dot(pred_key);
+
+    assert!(dot.starts_with("digraph \"::Foo\" {\n"), "{dot}");
+    assert!(dot.ends_with("}\n"), "{dot}");
+
+    // Find the node IDs by their labels.
+    let node_id = |label: &str| {
+        let suffix = format!(" [label=\"{label}\"];");
+        dot.lines()
+            .find_map(|line| line.trim().strip_suffix(&suffix))
+            .unwrap_or_else(|| panic!("no `{label}` node in:\n{dot}"))
+            .to_string()
+    };
+    let add = node_id("BinaryOp(Add)");
+    let eq = node_id("BinaryOp(Equal)");
+    let x = node_id("Path(::x)");
+    let one = node_id("Immediate(1)");
+    let two = node_id("Immediate(2)");
+
+    for (from, to) in [(&eq, &add), (&eq, &two), (&add, &x), (&add, &one)] {
+        assert!(dot.contains(&format!("    {from} -> {to};\n")), "{dot}");
+    }
+}
+
+#[test]
+fn expr_struct_eq() {
+    let handler = Handler::default();
+    let src = "predicate Foo { var x: int; constraint x + 1 == x + 1; constraint x + 1 == x + 2; }";
+    let contract = parse_str(&handler, src);
+    let (pred_key, _) = contract.predicate_by_name("::Foo").unwrap();
+
+    let operands = contract
+        .iter_constraints(pred_key)
+        .map(|constraint| match constraint.expr.get(&contract) {
+            Expr::BinaryOp { lhs, rhs, .. } => (lhs.get(&contract), rhs.get(&contract)),
+            _ => unreachable!("constraints are all `==`"),
+        })
+        .collect::<Vec<_>>();
+
+    // `x + 1` and `x + 1` have different spans but the same structure.
+    let (lhs, rhs) = operands[0];
+    assert_ne!(lhs.span(), rhs.span());
+    assert!(lhs.struct_eq(rhs, &contract));
+    assert!(lhs.struct_eq(lhs, &contract));
+
+    // `x + 1` and `x + 2` differ.
+    let (lhs, rhs) = operands[1];
+    assert!(!lhs.struct_eq(rhs, &contract));
+    assert!(!rhs.struct_eq(lhs, &contract));
+
+    // `x + 1` is not `x`.
+    let Expr::BinaryOp { lhs: x, .. } = lhs else {
+        unreachable!()
+    };
+    assert!(!lhs.struct_eq(x.get(&contract), &contract));
+}
+
+#[test]
+fn range_array_errors() {
+    let first_error = |src: &str| {
+        let handler = Handler::default();
+        assert!(parse_str(&handler, src)
+            .compile(&handler, &CompileOptions::default())
+            .is_err());
+        handler.consume().0[0].display_raw()
+    };
+
+    expect_test::expect![[r#"
+        attempt to use a non-constant value as an array length
+        @41..42: this must be a constant
+    "#]]
+    .assert_eq(&first_error(
+        "predicate test { var n: int; var a = [0..n]; }",
+    ));
+
+    expect_test::expect![[r#"
+        illegal empty array value
+        @26..30: empty array values are illegal
+    "#]]
+    .assert_eq(&first_error("predicate test { var a = [3..3]; }"));
+
+    expect_test::expect![[r#"
+        range array is too long
+        @26..48: this range has more than 65536 elements
+    "#]]
+    .assert_eq(&first_error(
+        "predicate test { var a = [0..9223372036854775807]; }",
+    ));
+
+    // The length of this range doesn't even fit in an `int`.
+    expect_test::expect![[r#"
+        range array is too long
+        @26..67: this range has more than 65536 elements
+    "#]]
+    .assert_eq(&first_error(
+        "predicate test { var a = [-9223372036854775808..9223372036854775807]; }",
+    ));
+}
@@ -6,9 +6,11 @@ mod validate;
 use crate::error::{ErrorEmitted, Handler};
 use legalize::legalize_vector_accesses;
 use lower::{
-    coalesce_prime_ops, lower_aliases, lower_array_ranges, lower_casts, lower_compares_to_nil,
-    lower_ifs, lower_imm_accesses, lower_ins, lower_matches, lower_pub_var_accesses,
-    lower_storage_accesses, lower_union_variant_paths, replace_const_refs,
+    coalesce_prime_ops, lower_aliases, lower_array_ranges, lower_array_slices, lower_casts,
+    lower_compares_to_nil, lower_ifs, lower_imm_accesses, lower_ins, lower_instance_addresses,
+    lower_lengths, lower_matches, lower_pub_var_accesses, lower_range_arrays,
+    lower_storage_accesses, lower_tuple_compares, lower_union_variant_paths, lower_word_indices,
+    replace_const_refs,
 };
 use unroll::unroll_generators;
 use validate::validate;
@@ -33,9 +35,19 @@ impl super::Contract {
         // Convert comparisons to `nil` into comparisons between __state_len() and 0.
         lower_compares_to_nil(&mut self);
 
+        // Reorder named tuples in comparisons so that their fields line up positionally.
+        lower_tuple_compares(handler, &mut self)?;
+
         // Unroll each generator into one large conjuction
         let _ = handler.scope(|handler| unroll_generators(handler, &mut self));
 
+        // Expand range arrays into arrays of their indices, now that their bounds are constant.
+        // This must precede slice lowering, as slices of range arrays wait for their size.
+        let _ = lower_range_arrays(handler, &mut self);
+
+        // Lower array slices into arrays of their elements, now that their bounds are constant.
+        let _ = lower_array_slices(handler, &mut self);
+
         // Lower `in` expressions into more explicit comparisons.
         let _ = lower_ins(handler, &mut self);
 
@@ -64,6 +76,14 @@ impl super::Contract {
             let _ = lower_array_ranges(handler, &mut self);
         }
 
+        // Fold `__length` calls into the now known sizes of their array arguments.
+        if !array_check_failed {
+            let _ = lower_lengths(handler, &mut self);
+        }
+
+        // Evaluate `__word` indices, which must be constants within the bounds of a `b256`.
+        let _ = lower_word_indices(handler, &mut self);
+
         // Lower indexing or field access into immediates to the actual element or field.
         let _ = lower_imm_accesses(handler, &mut self);
 
@@ -83,6 +103,10 @@ impl super::Contract {
         // (e.g., `option::none`) from Expr::Path to Expr::UnionVariant.
         lower_union_variant_paths(&mut self);
 
+        // Fold constant interface and predicate instance addresses into `b256` immediates before
+        // they're used to lower external storage and pub var accesses below.
+        lower_instance_addresses(&mut self);
+
         // Insert OOB checks for storage vector accesses
         let _ = legalize_vector_accesses(handler, &mut self);
 
@@ -1,8 +1,9 @@
 use crate::{
     error::{CompileError, Error, ErrorEmitted, Handler},
     expr::{
-        evaluate::Evaluator, BinaryOp, Expr, ExternalIntrinsic, Immediate, IntrinsicKind,
-        MatchBranch, TupleAccess, UnaryOp,
+        evaluate::{range_array_len, Evaluator},
+        BinaryOp, Expr, ExternalIntrinsic, Immediate, IntrinsicKind, MatchBranch, TupleAccess,
+        UnaryOp,
     },
     predicate::{
         BlockStatement, Const, ConstraintDecl, Contract, ExprKey, ExprsIter, Ident, IfDecl,
@@ -258,6 +259,432 @@ pub(crate) fn lower_array_ranges(
     Ok(())
 }
 
+/// Replace every `__length` intrinsic call with an integer immediate equal to the size of its
+/// array argument.  This must run after `lower_array_ranges` so that the array range expressions
+/// have already been evaluated.
+pub(crate) fn lower_lengths(
+    handler: &Handler,
+    contract: &mut Contract,
+) -> Result<(), ErrorEmitted> {
+    let int_ty = Type::Primitive {
+        kind: PrimitiveKind::Int,
+        span: empty_span(),
+    };
+
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let mut replacements = Vec::new();
+
+        for old_expr_key in contract.exprs(pred_key) {
+            if let Some(Expr::IntrinsicCall {
+                kind: (IntrinsicKind::External(ExternalIntrinsic::Length), _),
+                args,
+                span,
+            }) = old_expr_key.try_get(contract)
+            {
+                // The type checker has already confirmed there is a single array argument.
+                let Some(arg_key) = args.first() else {
+                    continue;
+                };
+                let arg_ty = arg_key.get_ty(contract);
+
+                let size = match (arg_ty.get_array_size(), arg_ty.get_array_range_expr()) {
+                    (Some(size), _) => size,
+                    (None, Some(range_expr_key)) => Type::get_array_size_from_range_expr(
+                        handler,
+                        range_expr_key.get(contract),
+                        contract,
+                    )?,
+                    (None, None) => {
+                        return Err(handler.emit_err(Error::Compile {
+                            error: CompileError::NonConstArrayLength {
+                                span: contract.expr_key_to_span(*arg_key),
+                            },
+                        }))
+                    }
+                };
+
+                replacements.push((
+                    old_expr_key,
+                    Expr::Immediate {
+                        value: Immediate::Int(size),
+                        span: span.clone(),
+                    },
+                ));
+            }
+        }
+
+        for (old_expr_key, new_expr) in replacements {
+            let new_expr_key = contract.exprs.insert(new_expr, int_ty.clone());
+            contract.replace_exprs(Some(pred_key), old_expr_key, new_expr_key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluate the index argument of every `__word` intrinsic call into an integer immediate.  The
+/// index must be a constant from 0 to 3 so that the word can be selected at compile time.  This
+/// must run after generators are unrolled and consts are plugged in so that indices built from
+/// them are already known.
+pub(crate) fn lower_word_indices(
+    handler: &Handler,
+    contract: &mut Contract,
+) -> Result<(), ErrorEmitted> {
+    let int_ty = Type::Primitive {
+        kind: PrimitiveKind::Int,
+        span: empty_span(),
+    };
+
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let mut replacements = Vec::new();
+
+        let evaluator = Evaluator::new(contract);
+        for expr_key in contract.exprs(pred_key) {
+            if let Some(Expr::IntrinsicCall {
+                kind: (IntrinsicKind::External(ExternalIntrinsic::Word), _),
+                args,
+                ..
+            }) = expr_key.try_get(contract)
+            {
+                // The type checker has already confirmed there are two arguments.
+                let Some(index_key) = args.get(1) else {
+                    continue;
+                };
+                let index_expr = index_key.get(contract);
+
+                match evaluator.evaluate(index_expr, &Handler::default(), contract) {
+                    Ok(Immediate::Int(index)) if (0..4).contains(&index) => {
+                        if !matches!(index_expr, Expr::Immediate { .. }) {
+                            replacements.push((
+                                *index_key,
+                                Expr::Immediate {
+                                    value: Immediate::Int(index),
+                                    span: index_expr.span().clone(),
+                                },
+                            ));
+                        }
+                    }
+                    _ => {
+                        handler.emit_err(Error::Compile {
+                            error: CompileError::InvalidWordIndex {
+                                span: index_expr.span().clone(),
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        for (old_expr_key, new_expr) in replacements {
+            let new_expr_key = contract.exprs.insert(new_expr, int_ty.clone());
+            contract.replace_exprs(Some(pred_key), old_expr_key, new_expr_key);
+        }
+    }
+
+    handler.result(())
+}
+
+/// Fold every interface instance and predicate instance address which is a constant expression,
+/// e.g., one built from `const`s, into a `b256` immediate.  Addresses are otherwise only folded by
+/// the optimizer, so this ensures they are known at compile time even when optimizations are
+/// skipped.  Addresses which aren't constant, such as decision variables, are left alone.
+pub(crate) fn lower_instance_addresses(contract: &mut Contract) {
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let pred = &contract.preds[pred_key];
+
+        let evaluator = Evaluator::new(contract);
+        let replacements = pred
+            .interface_instances
+            .iter()
+            .map(|instance| instance.address)
+            .chain(
+                pred.predicate_instances
+                    .iter()
+                    .filter_map(|instance| instance.address),
+            )
+            .filter_map(|addr_key| {
+                let addr_expr = addr_key.get(contract);
+                if addr_expr.is_immediate() {
+                    return None;
+                }
+
+                match evaluator.evaluate(addr_expr, &Handler::default(), contract) {
+                    Ok(value @ Immediate::B256(_)) => Some((
+                        addr_key,
+                        Expr::Immediate {
+                            value,
+                            span: addr_expr.span().clone(),
+                        },
+                    )),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (old_expr_key, new_expr) in replacements {
+            let new_expr_key = contract.exprs.insert(new_expr, types::b256());
+            contract.replace_exprs(Some(pred_key), old_expr_key, new_expr_key);
+        }
+    }
+}
+
+/// Lower range arrays into array expressions of the individual indices.  `[0..3]` becomes
+/// `[0, 1, 2]`.  The bounds of each range must be constant.
+pub(crate) fn lower_range_arrays(
+    handler: &Handler,
+    contract: &mut Contract,
+) -> Result<(), ErrorEmitted> {
+    let int_ty = Type::Primitive {
+        kind: PrimitiveKind::Int,
+        span: empty_span(),
+    };
+
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let range_arrays = contract
+            .exprs(pred_key)
+            .filter_map(|expr_key| match expr_key.try_get(contract) {
+                Some(Expr::RangeArray { range, span }) => Some((expr_key, *range, span.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (range_array_key, range_key, span) in range_arrays {
+            let Some(Expr::Range { lb, ub, .. }) = range_key.try_get(contract) else {
+                return Err(handler.emit_err(Error::Compile {
+                    error: CompileError::Internal {
+                        msg: "range array must have a range",
+                        span,
+                    },
+                }));
+            };
+
+            // The bounds must evaluate to integers.  Any error from evaluating a bound only means
+            // that it isn't constant, so it's dropped in favour of a clearer one.
+            let evaluator = Evaluator::new(contract);
+            let mut bounds = [*lb, *ub].into_iter().map(|bound_key| {
+                let bound_expr = bound_key.get(contract);
+                match evaluator.evaluate(bound_expr, &Handler::default(), contract) {
+                    Ok(Immediate::Int(val)) => Ok(val),
+                    Ok(_) => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::InvalidConstArrayLength {
+                            span: bound_expr.span().clone(),
+                        },
+                    })),
+                    Err(_) => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::NonConstArrayLength {
+                            span: bound_expr.span().clone(),
+                        },
+                    })),
+                }
+            });
+            let (lb_val, ub_val) = (bounds.next().unwrap()?, bounds.next().unwrap()?);
+
+            let range_span = contract.expr_key_to_span(range_key);
+            let size = range_array_len(handler, lb_val, ub_val, &range_span)?;
+
+            let elements = (lb_val..ub_val)
+                .map(|idx| {
+                    contract.exprs.insert(
+                        Expr::Immediate {
+                            value: Immediate::Int(idx),
+                            span: span.clone(),
+                        },
+                        int_ty.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let size_expr_key = contract.exprs.insert(
+                Expr::Immediate {
+                    value: Immediate::Int(size),
+                    span: range_span,
+                },
+                int_ty.clone(),
+            );
+
+            let array_expr_key = contract.exprs.insert(
+                Expr::Array {
+                    elements,
+                    range_expr: size_expr_key,
+                    span: span.clone(),
+                },
+                Type::Array {
+                    ty: Box::new(int_ty.clone()),
+                    range: Some(size_expr_key),
+                    size: Some(size),
+                    span: span.clone(),
+                },
+            );
+
+            contract.replace_exprs(Some(pred_key), range_array_key, array_expr_key);
+
+            // Any array types which were inferred from the range array refer to its range, so
+            // replace that with the now known size.
+            contract.replace_exprs(Some(pred_key), range_key, size_expr_key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Lower array slices into array expressions of the individual elements.  `a[1..3]` becomes
+/// `[a[1], a[2]]`.  The bounds of each slice must be constant and within the bounds of the array.
+pub(crate) fn lower_array_slices(
+    handler: &Handler,
+    contract: &mut Contract,
+) -> Result<(), ErrorEmitted> {
+    let int_ty = Type::Primitive {
+        kind: PrimitiveKind::Int,
+        span: empty_span(),
+    };
+
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        loop {
+            // Find the next slice to lower.  A slice of another slice, directly or via a
+            // variable, has an array type whose range is still the inner slice range.  These must
+            // wait until the inner slice is lowered and the size of its array is known.
+            let Some((slice_key, array_key, range_key, span)) =
+                contract.exprs(pred_key).find_map(|expr_key| {
+                    let Some(Expr::ArraySlice { array, range, span }) = expr_key.try_get(contract)
+                    else {
+                        return None;
+                    };
+
+                    let array_range = array
+                        .get_ty(contract)
+                        .get_array_range_expr()
+                        .and_then(|range_expr_key| range_expr_key.try_get(contract));
+
+                    (!matches!(array_range, Some(Expr::Range { .. })))
+                        .then(|| (expr_key, *array, *range, span.clone()))
+                })
+            else {
+                break;
+            };
+
+            let Some(Expr::Range { lb, ub, .. }) = range_key.try_get(contract) else {
+                return Err(handler.emit_err(Error::Compile {
+                    error: CompileError::Internal {
+                        msg: "array slice must have a range",
+                        span,
+                    },
+                }));
+            };
+
+            // The bounds must evaluate to integers.  Any error from evaluating a bound only means
+            // that it isn't constant, so it's dropped in favour of a clearer one.
+            let evaluator = Evaluator::new(contract);
+            let mut bounds = [*lb, *ub].into_iter().map(|bound_key| {
+                let bound_expr = bound_key.get(contract);
+                match evaluator.evaluate(bound_expr, &Handler::default(), contract) {
+                    Ok(Immediate::Int(val)) => Ok(val),
+                    Ok(_) => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::InvalidConstArrayIndex {
+                            span: bound_expr.span().clone(),
+                        },
+                    })),
+                    Err(_) => Err(handler.emit_err(Error::Compile {
+                        error: CompileError::NonConstArrayIndex {
+                            span: bound_expr.span().clone(),
+                        },
+                    })),
+                }
+            });
+            let (lb_val, ub_val) = (bounds.next().unwrap()?, bounds.next().unwrap()?);
+
+            let array_ty = array_key.get_ty(contract);
+            let Some(el_ty) = array_ty.get_array_el_type().cloned() else {
+                return Err(handler.emit_err(Error::Compile {
+                    error: CompileError::Internal {
+                        msg: "array slice must be of an array",
+                        span,
+                    },
+                }));
+            };
+            let array_size = match (array_ty.get_array_size(), array_ty.get_array_range_expr()) {
+                (Some(size), _) => size,
+                (None, Some(range_expr_key)) => Type::get_array_size_from_range_expr(
+                    handler,
+                    range_expr_key.get(contract),
+                    contract,
+                )?,
+                (None, None) => {
+                    return Err(handler.emit_err(Error::Compile {
+                        error: CompileError::NonConstArrayLength {
+                            span: contract.expr_key_to_span(array_key),
+                        },
+                    }))
+                }
+            };
+
+            let range_span = contract.expr_key_to_span(range_key);
+            if lb_val < 0 || ub_val > array_size {
+                return Err(handler.emit_err(Error::Compile {
+                    error: CompileError::ArrayIndexOutOfBounds { span: range_span },
+                }));
+            }
+            if lb_val >= ub_val {
+                return Err(handler.emit_err(Error::Compile {
+                    error: CompileError::EmptyArrayExpression { span: range_span },
+                }));
+            }
+
+            // Build the array of element accesses.
+            let elements = (lb_val..ub_val)
+                .map(|idx| {
+                    let idx_key = contract.exprs.insert(
+                        Expr::Immediate {
+                            value: Immediate::Int(idx),
+                            span: span.clone(),
+                        },
+                        int_ty.clone(),
+                    );
+
+                    contract.exprs.insert(
+                        Expr::Index {
+                            expr: array_key,
+                            index: idx_key,
+                            span: span.clone(),
+                        },
+                        el_ty.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let size = ub_val - lb_val;
+            let size_expr_key = contract.exprs.insert(
+                Expr::Immediate {
+                    value: Immediate::Int(size),
+                    span: range_span,
+                },
+                int_ty.clone(),
+            );
+
+            let array_expr_key = contract.exprs.insert(
+                Expr::Array {
+                    elements,
+                    range_expr: size_expr_key,
+                    span: span.clone(),
+                },
+                Type::Array {
+                    ty: Box::new(el_ty),
+                    range: Some(size_expr_key),
+                    size: Some(size),
+                    span: span.clone(),
+                },
+            );
+
+            contract.replace_exprs(Some(pred_key), slice_key, array_expr_key);
+
+            // Any array types which were inferred from the slice refer to its range, so replace
+            // that with the now known size.
+            contract.replace_exprs(Some(pred_key), range_key, size_expr_key);
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn lower_imm_accesses(
     handler: &Handler,
     contract: &mut Contract,
@@ -615,7 +1042,8 @@ pub(crate) fn lower_ins(handler: &Handler, contract: &mut Contract) -> Result<()
         };
 
         // Replace the array expressions.
-        // `x in ary` becomes `(x == ary[0]) || (x == ary[1]) || (x == ary[2]) || ...`.
+        // `x in ary` becomes `(x == ary[0]) || (x == ary[1]) || (x == ary[2]) || ...`, or `false`
+        // if `ary` is empty.
         for (in_expr_key, value_key, array_key, element_count, element_ty, span) in
             array_collections
         {
@@ -661,7 +1089,8 @@ pub(crate) fn lower_ins(handler: &Handler, contract: &mut Contract) -> Result<()
                         bool_ty.clone(),
                     )
                 })
-                .expect("can't have empty array expressions");
+                // Nothing is ever in an empty array.
+                .unwrap_or_else(|| contract.exprs.insert_bool(false));
 
             contract.replace_exprs(Some(pred_key), in_expr_key, or_key);
         }
@@ -771,6 +1200,172 @@ pub(crate) fn lower_compares_to_nil(contract: &mut Contract) {
     }
 }
 
+/// Reorder the `rhs` of comparisons between fully named tuples which declare their fields in a
+/// different order, so that the fields can be compared positionally. For example:
+///
+/// var t: { x: int, y: int };
+/// constraint t == { y: 1, x: 0 };
+///
+/// is equivalent to
+///
+/// constraint t == { x: 0, y: 1 };
+///
+/// If the `rhs` is not a tuple expression then it is replaced by a tuple of named field accesses
+/// instead, e.g., `{ x: u.x, y: u.y }`.  Tuples nested within the compared tuples are reordered
+/// the same way.
+///
+pub(crate) fn lower_tuple_compares(
+    handler: &Handler,
+    contract: &mut Contract,
+) -> Result<(), ErrorEmitted> {
+    for pred_key in contract.preds.keys().collect::<Vec<_>>() {
+        let tuple_compares = contract
+            .exprs(pred_key)
+            .filter_map(|expr_key| match expr_key.try_get(contract) {
+                Some(Expr::BinaryOp { op, lhs, rhs, .. })
+                    if *op == BinaryOp::Equal || *op == BinaryOp::NotEqual =>
+                {
+                    tuple_fields_misordered(lhs.get_ty(contract), rhs.get_ty(contract))
+                        .then_some((expr_key, *lhs, *rhs))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (compare_key, lhs, rhs) in tuple_compares {
+            let lhs_ty = lhs.get_ty(contract).clone();
+            if let Some(new_rhs) = reorder_tuple_fields(handler, contract, &lhs_ty, rhs) {
+                if let Expr::BinaryOp { rhs, .. } = compare_key.get_mut(contract) {
+                    *rhs = new_rhs;
+                }
+            }
+        }
+    }
+
+    handler.result(())
+}
+
+/// For each field of the tuple `lhs_fields` find the index of the field it is compared with in
+/// `rhs_fields`.  Fields are matched by name if both tuples are fully named, otherwise by
+/// position.  `None` marks a field which has no match.
+fn match_tuple_fields(
+    lhs_fields: &[(Option<Ident>, Type)],
+    rhs_fields: &[(Option<Ident>, Type)],
+) -> Vec<Option<usize>> {
+    let is_fully_named =
+        |fields: &[(Option<Ident>, Type)]| fields.iter().all(|(name, _)| name.is_some());
+
+    if is_fully_named(lhs_fields) && is_fully_named(rhs_fields) {
+        lhs_fields
+            .iter()
+            .map(|(lhs_name, _)| {
+                rhs_fields.iter().position(|(rhs_name, _)| {
+                    rhs_name.as_ref().map(|name| &name.name)
+                        == lhs_name.as_ref().map(|name| &name.name)
+                })
+            })
+            .collect()
+    } else {
+        (0..lhs_fields.len())
+            .map(|idx| (idx < rhs_fields.len()).then_some(idx))
+            .collect()
+    }
+}
+
+/// Whether comparing the tuple types `lhs_ty` and `rhs_ty` positionally would compare fields with
+/// different names, either directly or within nested tuples.
+fn tuple_fields_misordered(lhs_ty: &Type, rhs_ty: &Type) -> bool {
+    let (Some(lhs_fields), Some(rhs_fields)) =
+        (lhs_ty.get_tuple_fields(), rhs_ty.get_tuple_fields())
+    else {
+        return false;
+    };
+
+    match_tuple_fields(lhs_fields, rhs_fields)
+        .into_iter()
+        .zip(lhs_fields)
+        .enumerate()
+        .any(|(idx, (rhs_idx, (_, lhs_field_ty)))| match rhs_idx {
+            Some(rhs_idx) => {
+                rhs_idx != idx || tuple_fields_misordered(lhs_field_ty, &rhs_fields[rhs_idx].1)
+            }
+            None => true,
+        })
+}
+
+/// Build a tuple from the fields of the tuple `rhs` in the same order as the tuple type `lhs_ty`,
+/// recursing into nested tuples.  Returns `rhs` itself if its fields are already in order, or
+/// `None` if a field of `lhs_ty` is missing from `rhs`, in which case an error is emitted.
+fn reorder_tuple_fields(
+    handler: &Handler,
+    contract: &mut Contract,
+    lhs_ty: &Type,
+    rhs: ExprKey,
+) -> Option<ExprKey> {
+    let rhs_ty = rhs.get_ty(contract).clone();
+    if !tuple_fields_misordered(lhs_ty, &rhs_ty) {
+        return Some(rhs);
+    }
+
+    let lhs_fields = lhs_ty.get_tuple_fields()?;
+    let rhs_fields = rhs_ty.get_tuple_fields()?;
+    let rhs_span = contract.expr_key_to_span(rhs);
+    let rhs_tuple_fields = match rhs.get(contract) {
+        Expr::Tuple { fields, .. } => Some(fields.clone()),
+        _ => None,
+    };
+
+    let mut new_fields = Vec::new();
+    for (idx, ((lhs_name, lhs_field_ty), rhs_idx)) in lhs_fields
+        .iter()
+        .zip(match_tuple_fields(lhs_fields, rhs_fields))
+        .enumerate()
+    {
+        let Some(rhs_idx) = rhs_idx else {
+            handler.emit_err(Error::Compile {
+                error: CompileError::InvalidTupleAccessor {
+                    accessor: lhs_name
+                        .as_ref()
+                        .map_or_else(|| idx.to_string(), |name| name.name.clone()),
+                    tuple_type: contract.with_ctrct(&rhs_ty).to_string(),
+                    span: rhs_span,
+                },
+            });
+            return None;
+        };
+
+        // Take the matching `rhs` field either directly from the tuple expression or via a new
+        // field access.
+        let field_key = match &rhs_tuple_fields {
+            Some(rhs_tuple_fields) => rhs_tuple_fields[rhs_idx].1,
+            None => {
+                let (rhs_name, rhs_field_ty) = &rhs_fields[rhs_idx];
+                contract.exprs.insert(
+                    Expr::TupleFieldAccess {
+                        tuple: rhs,
+                        field: rhs_name
+                            .clone()
+                            .map_or(TupleAccess::Index(rhs_idx), TupleAccess::Name),
+                        span: rhs_span.clone(),
+                    },
+                    rhs_field_ty.clone(),
+                )
+            }
+        };
+
+        let field_key = reorder_tuple_fields(handler, contract, lhs_field_ty, field_key)?;
+        new_fields.push((lhs_name.clone(), field_key));
+    }
+
+    Some(contract.exprs.insert(
+        Expr::Tuple {
+            fields: new_fields,
+            span: rhs_span,
+        },
+        lhs_ty.clone(),
+    ))
+}
+
 /// Convert all `if` declarations into individual constraints that are pushed to `pred.constraints`.
 /// For example:
 ///
@@ -1041,6 +1636,8 @@ pub(super) fn coalesce_prime_ops(contract: &mut Contract) {
                 | Expr::Cast { .. }
                 | Expr::In { .. }
                 | Expr::Range { .. }
+                | Expr::ArraySlice { .. }
+                | Expr::RangeArray { .. }
                 | Expr::Generator { .. }
                 | Expr::UnionTag { .. }
                 | Expr::UnionValue { .. } => Coalescence::None,
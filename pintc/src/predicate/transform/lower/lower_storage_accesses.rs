@@ -444,7 +444,14 @@ fn get_base_storage_key(
                             .as_ref()
                             .map_or(false, |name| name.name == ident.name)
                     })
-                    .expect("field name must exist, this was checked in type checking"),
+                    .ok_or_else(|| {
+                        handler.emit_err(Error::Compile {
+                            error: CompileError::UnknownTupleField {
+                                field: ident.name.clone(),
+                                span: ident.span.clone(),
+                            },
+                        })
+                    })?,
                 TupleAccess::Error => {
                     return Err(handler.emit_err(Error::Compile {
                         error: CompileError::Internal {
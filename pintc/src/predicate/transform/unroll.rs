@@ -74,48 +74,26 @@ fn unroll_generator(
         .map(|range| {
             match range.1.get(contract) {
                 Expr::Range { lb, ub, .. } => {
-                    let lb = lb.get(contract);
-                    let ub = ub.get(contract);
-                    match (lb, ub) {
-                        // Only support integer literals as bounds, for now
-                        (
-                            Expr::Immediate {
-                                value: Immediate::Int(lb),
-                                ..
+                    // The bounds may be any constant integer expression, such as `0..N - 1`
+                    // where `N` is a `const`.  Errors from the evaluator aren't interesting here,
+                    // anything which doesn't evaluate to an integer is simply an invalid bound.
+                    let evaluator = Evaluator::new(contract);
+                    let evaluate_bound = |bound: &ExprKey| match evaluator.evaluate_key(
+                        bound,
+                        &Handler::default(),
+                        contract,
+                    ) {
+                        Ok(Immediate::Int(val)) => Ok(val),
+                        _ => Err(handler.emit_err(Error::Compile {
+                            error: CompileError::InvalidGeneratorIndexBound {
+                                name: range.0.name.clone(),
+                                gen_kind: kind.to_string(),
+                                span: bound.get(contract).span().clone(),
                             },
-                            Expr::Immediate {
-                                value: Immediate::Int(ub),
-                                ..
-                            },
-                        ) => Ok(*lb..=*ub),
-                        _ if !matches!(
-                            lb,
-                            Expr::Immediate {
-                                value: Immediate::Int(_),
-                                ..
-                            }
-                        ) =>
-                        {
-                            // Bad lower bound
-                            Err(handler.emit_err(Error::Compile {
-                                error: CompileError::InvalidGeneratorIndexBound {
-                                    name: range.0.name.clone(),
-                                    gen_kind: kind.to_string(),
-                                    span: lb.span().clone(),
-                                },
-                            }))
-                        }
-                        _ => {
-                            // Bad upper bound
-                            Err(handler.emit_err(Error::Compile {
-                                error: CompileError::InvalidGeneratorIndexBound {
-                                    name: range.0.name.clone(),
-                                    gen_kind: kind.to_string(),
-                                    span: ub.span().clone(),
-                                },
-                            }))
-                        }
-                    }
+                        })),
+                    };
+
+                    Ok(evaluate_bound(lb)?..=evaluate_bound(ub)?)
                 }
                 _ => panic!("guaranteed by the parser"),
             }
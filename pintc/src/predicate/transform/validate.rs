@@ -171,6 +171,20 @@ fn check_expr(
 
         Expr::Range { span, .. } => Err(emit_illegal_type_error!(handler, span, "range", "exprs")),
 
+        Expr::ArraySlice { span, .. } => Err(emit_illegal_type_error!(
+            handler,
+            span,
+            "array slice",
+            "exprs"
+        )),
+
+        Expr::RangeArray { span, .. } => Err(emit_illegal_type_error!(
+            handler,
+            span,
+            "range array",
+            "exprs"
+        )),
+
         Expr::Generator { kind, span, .. } => match kind {
             GeneratorKind::ForAll => Err(emit_illegal_type_error!(
                 handler,
@@ -258,6 +272,7 @@ fn run_parser(src: &str, handler: &Handler) -> Contract {
                 current_pred_key: None,
                 macros: &mut Vec::new(),
                 macro_calls: &mut BTreeMap::default(),
+                doc_comments: &Default::default(),
                 span_from: &|_, _| span::empty_span(),
                 use_paths: &mut Vec::new(),
                 next_paths: &mut Vec::new(),
@@ -115,6 +115,8 @@ impl VarKey {
         Ok(VarABI {
             name: self.get(pred).name.clone(),
             ty: self.get_ty(pred).abi(handler, contract)?,
+            size_in_bytes: Some(self.get_ty(pred).size_in_bytes(handler, contract)?),
+            doc: pred.var_docs.get(*self).cloned(),
         })
     }
 }
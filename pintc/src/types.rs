@@ -4,6 +4,7 @@ use crate::{
     predicate::{Contract, ExprKey, UnionKey},
     span::{empty_span, Span, Spanned},
 };
+use fxhash::FxHashMap;
 use pint_abi_types::{TupleField, TypeABI};
 
 mod display;
@@ -477,14 +478,13 @@ impl Type {
     }
 
     // Checks if type `self` is allowed in storage. For now, all types are allowed except for:
-    // - Storage maps where the "from" type is not bool, int, nor b256
+    // - Storage maps where the "from" type is not bool, int, b256, nor a tuple of those
     // - Storage vectors where the element type is not bool, int, nor b256
     pub fn is_allowed_in_storage(&self) -> bool {
         match self {
             Type::Map { ty_from, ty_to, .. } => {
                 // We only support maps from these types for now
-                (ty_from.is_bool() || ty_from.is_int() || ty_from.is_b256())
-                    && ty_to.is_allowed_in_storage()
+                ty_from.is_allowed_as_storage_map_key() && ty_to.is_allowed_in_storage()
             }
             Type::Vector { ty, .. } => {
                 // We only support vectors of these types for now
@@ -504,6 +504,22 @@ impl Type {
         }
     }
 
+    // Checks if type `self` can be used as the "from" type of a storage map. The key is laid out
+    // word by word after the storage index, so any type with a fixed size made of bools, ints and
+    // b256s works, including tuples of those.
+    fn is_allowed_as_storage_map_key(&self) -> bool {
+        match self {
+            Type::Tuple { fields, .. } => {
+                !fields.is_empty()
+                    && fields
+                        .iter()
+                        .all(|(_, field)| field.is_allowed_as_storage_map_key())
+            }
+            Type::Alias { ty, .. } => ty.is_allowed_as_storage_map_key(),
+            _ => self.is_bool() || self.is_int() || self.is_b256(),
+        }
+    }
+
     pub fn size(&self, handler: &Handler, contract: &Contract) -> Result<usize, ErrorEmitted> {
         match self {
             Self::Primitive {
@@ -575,6 +591,38 @@ impl Type {
         }
     }
 
+    /// Calculate the size of this type in bytes, i.e., its size in words multiplied by 8. Unlike
+    /// `size()`, aliases are looked through, including within tuples and arrays.
+    pub fn size_in_bytes(
+        &self,
+        handler: &Handler,
+        contract: &Contract,
+    ) -> Result<usize, ErrorEmitted> {
+        match self {
+            Self::Alias { ty, .. } => ty.size_in_bytes(handler, contract),
+
+            Self::Tuple { fields, .. } => fields.iter().try_fold(0, |acc, (_, field_ty)| {
+                field_ty
+                    .size_in_bytes(handler, contract)
+                    .map(|size| acc + size)
+            }),
+
+            Self::Array {
+                ty, range, size, ..
+            } => Ok(ty.size_in_bytes(handler, contract)?
+                * size.unwrap_or(Self::get_array_size_from_range_expr(
+                    handler,
+                    range
+                        .as_ref()
+                        .and_then(|e| e.try_get(contract))
+                        .expect("expr key guaranteed to exist"),
+                    contract,
+                )?) as usize),
+
+            _ => Ok(self.size(handler, contract)? * 8),
+        }
+    }
+
     /// Calculate the number of storage or pub var slots required for this type. All primitive
     /// types fit in a single slot even if their size is > 1. The math is the same for storage and
     /// pub var data
@@ -700,6 +748,9 @@ impl Type {
                 ty_to: Box::new((*ty_to).abi(handler, contract)?),
             }),
 
+            // An alias has the same ABI as the type it refers to.
+            Type::Alias { ty, .. } => ty.abi(handler, contract),
+
             // This, of course, is incorrect. It's just a placeholder until we can support ABI gen
             // for vectors, which is non-trivial.
             Type::Vector { .. } => Ok(TypeABI::Int),
@@ -873,6 +924,103 @@ impl Type {
         }
     }
 
+    /// Returns whether a value of type `src` may initialise a variable, state or const declared
+    /// with type `self`. Pint has no implicit conversions, so this is the case exactly when the
+    /// two types have a `common_supertype()`:
+    /// - aliases are resolved to the types they refer to, in either position,
+    /// - `Any` is assignable to and from anything,
+    /// - tuples are matched field by field, by name if every field of both is named or otherwise
+    ///   in order,
+    /// - arrays and vectors are matched by element type, and maps by key and value types, and
+    /// - unions and other custom types must be the same declaration.
+    ///
+    /// Everything else, e.g. assigning a `bool` to an `int`, is an error.
+    pub fn is_assignable_from(&self, contract: &Contract, src: &Self) -> bool {
+        self.common_supertype(contract, src).is_some()
+    }
+
+    /// Returns the most specific type which both `self` and `other` are compatible with, or `None`
+    /// if they are incompatible. Aliases are resolved to the types they refer to, `Any` yields to
+    /// the other type, and tuples and arrays are compatible when their fields or elements are.
+    /// Named tuple fields are matched by name, in which case the result uses the field order of
+    /// `self`.
+    pub fn common_supertype(&self, contract: &Contract, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Any(_), _) => Some(other.clone()),
+            (_, Self::Any(_)) => Some(self.clone()),
+
+            (Self::Alias { name: lhs_name, .. }, Self::Alias { name: rhs_name, .. })
+                if lhs_name == rhs_name =>
+            {
+                Some(self.clone())
+            }
+            (Self::Alias { ty: lhs_ty, .. }, rhs) => lhs_ty.common_supertype(contract, rhs),
+            (lhs, Self::Alias { ty: rhs_ty, .. }) => lhs.common_supertype(contract, rhs_ty),
+
+            (
+                Self::Array {
+                    ty: lhs_ty,
+                    range,
+                    size,
+                    span,
+                },
+                Self::Array { ty: rhs_ty, .. },
+            ) => Some(Self::Array {
+                ty: Box::new(lhs_ty.common_supertype(contract, rhs_ty)?),
+                range: *range,
+                size: *size,
+                span: span.clone(),
+            }),
+
+            (
+                Self::Tuple {
+                    fields: lhs_fields,
+                    span,
+                },
+                Self::Tuple {
+                    fields: rhs_fields, ..
+                },
+            ) => {
+                if lhs_fields.len() != rhs_fields.len() {
+                    return None;
+                }
+
+                let all_named = |fields: &[(Option<Ident>, Self)]| {
+                    fields.iter().all(|(name, _)| name.is_some())
+                };
+                let fields = if all_named(lhs_fields) && all_named(rhs_fields) {
+                    // Match the fields name-wise, as `eq()` does.
+                    lhs_fields
+                        .iter()
+                        .map(|(name, lhs_ty)| {
+                            let (_, rhs_ty) = rhs_fields.iter().find(|(rhs_name, _)| {
+                                rhs_name.as_ref().map(|n| &n.name) == name.as_ref().map(|n| &n.name)
+                            })?;
+                            Some((name.clone(), lhs_ty.common_supertype(contract, rhs_ty)?))
+                        })
+                        .collect::<Option<Vec<_>>>()?
+                } else {
+                    // Otherwise match them in declared order.
+                    lhs_fields
+                        .iter()
+                        .zip(rhs_fields.iter())
+                        .map(|((name, lhs_ty), (_, rhs_ty))| {
+                            Some((name.clone(), lhs_ty.common_supertype(contract, rhs_ty)?))
+                        })
+                        .collect::<Option<Vec<_>>>()?
+                };
+
+                Some(Self::Tuple {
+                    fields,
+                    span: span.clone(),
+                })
+            }
+
+            // Everything else has no structure to combine, so it's either the same type or not.
+            _ => self.eq(contract, other).then(|| self.clone()),
+        }
+    }
+
     pub fn replace_type_expr(&mut self, old_expr: ExprKey, new_expr: ExprKey) {
         match self {
             Type::Array { ty, range, .. } => {
@@ -909,6 +1057,75 @@ impl Type {
             | Type::Union { .. } => {}
         }
     }
+
+    /// Like `replace_type_expr()` but replaces every expr key found in `keys` at once.
+    pub fn replace_type_expr_by_map(&mut self, keys: &FxHashMap<ExprKey, ExprKey>) {
+        match self {
+            Type::Array { ty, range, .. } => {
+                if let Some(range) = range {
+                    if let Some(new_range) = keys.get(range) {
+                        *range = *new_range;
+                    }
+                }
+
+                ty.replace_type_expr_by_map(keys);
+            }
+
+            Type::Tuple { fields, .. } => {
+                fields
+                    .iter_mut()
+                    .for_each(|(_, field_ty)| field_ty.replace_type_expr_by_map(keys));
+            }
+
+            Type::Alias { ty, .. } => ty.replace_type_expr_by_map(keys),
+
+            Type::Map { ty_from, ty_to, .. } => {
+                ty_from.replace_type_expr_by_map(keys);
+                ty_to.replace_type_expr_by_map(keys);
+            }
+
+            Type::Vector { ty, .. } => ty.replace_type_expr_by_map(keys),
+
+            Type::Error(_)
+            | Type::Unknown(_)
+            | Type::Any(_)
+            | Type::Primitive { .. }
+            | Type::Custom { .. }
+            | Type::Union { .. } => {}
+        }
+    }
+
+    /// Replace every union decl key found in `keys` with its new key.
+    pub fn replace_union_keys(&mut self, keys: &FxHashMap<UnionKey, UnionKey>) {
+        match self {
+            Type::Array { ty, .. } | Type::Alias { ty, .. } | Type::Vector { ty, .. } => {
+                ty.replace_union_keys(keys)
+            }
+
+            Type::Tuple { fields, .. } => {
+                fields
+                    .iter_mut()
+                    .for_each(|(_, field_ty)| field_ty.replace_union_keys(keys));
+            }
+
+            Type::Map { ty_from, ty_to, .. } => {
+                ty_from.replace_union_keys(keys);
+                ty_to.replace_union_keys(keys);
+            }
+
+            Type::Union { decl, .. } => {
+                if let Some(new_decl) = keys.get(decl) {
+                    *decl = *new_decl;
+                }
+            }
+
+            Type::Error(_)
+            | Type::Unknown(_)
+            | Type::Any(_)
+            | Type::Primitive { .. }
+            | Type::Custom { .. } => {}
+        }
+    }
 }
 
 impl Spanned for Type {
@@ -1029,6 +1246,15 @@ pub fn tuple(fields: Vec<Type>) -> Type {
     }
 }
 
+pub fn array(ty: Type) -> Type {
+    Type::Array {
+        ty: Box::new(ty),
+        range: None,
+        size: None,
+        span: empty_span(),
+    }
+}
+
 pub fn vector(ty: Type) -> Type {
     Type::Vector {
         ty: Box::new(ty),
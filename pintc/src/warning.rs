@@ -1,5 +1,8 @@
-use crate::span::{Span, Spanned};
-use ariadne::{FnCache, Label, Report, ReportKind, Source};
+use crate::{
+    error::diagnostic_codes,
+    span::{Span, Spanned},
+};
+use ariadne::{Config, FnCache, Label, Report, ReportKind, Source};
 use std::fmt::{Display, Formatter, Result, Write};
 use thiserror::Error;
 use yansi::{Color, Paint, Style};
@@ -16,8 +19,24 @@ pub struct WarningLabel {
 pub enum Warning {
     #[error("unneeded else branch")]
     MatchUnneededElse { span: Span },
-    #[error("constraint is always `false`")]
-    AlwaysFalseConstraint { span: Span },
+    #[error("constraint is always `true`")]
+    AlwaysTrueConstraint { span: Span },
+    #[error("unused variable `{name}`")]
+    UnusedVar { name: String, span: Span },
+    #[error("chained comparison")]
+    ChainedComparison { span: Span },
+    #[error("unreferenced public variable `{name}`")]
+    UnreferencedPubVar { name: String, span: Span },
+}
+
+diagnostic_codes! {
+    Warning {
+        MatchUnneededElse => "match_unneeded_else",
+        AlwaysTrueConstraint => "always_true_constraint",
+        UnusedVar => "unused_var",
+        ChainedComparison => "chained_comparison",
+        UnreferencedPubVar => "unreferenced_pub_var",
+    }
 }
 
 impl ReportableWarning for Warning {
@@ -30,9 +49,26 @@ impl ReportableWarning for Warning {
                 color: Color::Yellow,
             }],
 
-            AlwaysFalseConstraint { span } => vec![WarningLabel {
-                message: "this constraint always evaluates to `false` and can never be satisfied"
-                    .to_string(),
+            AlwaysTrueConstraint { span } => vec![WarningLabel {
+                message: "this constraint always evaluates to `true` and has no effect".to_string(),
+                span: span.clone(),
+                color: Color::Yellow,
+            }],
+
+            UnusedVar { span, .. } => vec![WarningLabel {
+                message: "this variable is never used".to_string(),
+                span: span.clone(),
+                color: Color::Yellow,
+            }],
+
+            ChainedComparison { span } => vec![WarningLabel {
+                message: "this chain of comparisons has been expanded".to_string(),
+                span: span.clone(),
+                color: Color::Yellow,
+            }],
+
+            UnreferencedPubVar { span, .. } => vec![WarningLabel {
+                message: "this `pub var` is never read by another predicate".to_string(),
                 span: span.clone(),
                 color: Color::Yellow,
             }],
@@ -42,21 +78,34 @@ impl ReportableWarning for Warning {
     fn note(&self) -> Option<String> {
         use Warning::*;
         match self {
-            MatchUnneededElse { .. } | AlwaysFalseConstraint { .. } => None,
+            MatchUnneededElse { .. }
+            | AlwaysTrueConstraint { .. }
+            | UnusedVar { .. }
+            | ChainedComparison { .. }
+            | UnreferencedPubVar { .. } => None,
         }
     }
 
     fn code(&self) -> Option<String> {
-        None
+        Some(self.code_str().to_string())
     }
 
     fn help(&self) -> Option<String> {
         use Warning::*;
         match self {
+            AlwaysTrueConstraint { .. } => Some(
+                "this constraint has been removed and may be deleted from the source".to_string(),
+            ),
+
+            UnusedVar { name, .. } | UnreferencedPubVar { name, .. } => Some(format!(
+                "if this is intentional, consider prefixing it with an underscore: `_{name}`"
+            )),
 
-            AlwaysFalseConstraint { .. } => {
-                Some("if this is intentional, consider removing the containing predicate because its constraints can never be satisfied".to_string())
-            }
+            ChainedComparison { .. } => Some(
+                "`a < b < c` is treated as `a < b && b < c`; use that form explicitly to silence \
+                this warning"
+                    .to_string(),
+            ),
 
             MatchUnneededElse { .. } => None,
         }
@@ -67,7 +116,11 @@ impl Spanned for Warning {
     fn span(&self) -> &Span {
         use Warning::*;
         match self {
-            MatchUnneededElse { span } | AlwaysFalseConstraint { span } => span,
+            MatchUnneededElse { span }
+            | AlwaysTrueConstraint { span }
+            | UnusedVar { span, .. }
+            | ChainedComparison { span }
+            | UnreferencedPubVar { span, .. } => span,
         }
     }
 }
@@ -101,7 +154,8 @@ where
     /// A helpful "note" about the warning
     fn note(&self) -> Option<String>;
 
-    /// A unique warning code
+    /// A unique warning code.  These are reported by `--error-format json` and must not change
+    /// once published.
     fn code(&self) -> Option<String>;
 
     /// Additional information to help the user address the diagnostic
@@ -123,6 +177,7 @@ where
         let mut report_builder =
             Report::build(ReportKind::Warning, warning_file, self.span().start())
                 .with_message(format!("{}", self.bold()))
+                .with_config(Config::default().with_color(yansi::is_enabled()))
                 .with_labels(
                     self.labels()
                         .iter()
@@ -195,3 +250,22 @@ pub fn print_warnings(warnings: &Warnings) {
         warning.print();
     }
 }
+
+/// Print a list of [`Warning`] to stderr as JSON lines, in the same form as
+/// [`crate::error::print_errors_json`].
+pub fn print_warnings_json(warnings: &Warnings) {
+    for warning in &warnings.0 {
+        let span = warning.span();
+        eprintln!(
+            "{}",
+            serde_json::json!({
+                "file": format!("{}", span.context().display()),
+                "start": span.start(),
+                "end": span.end(),
+                "severity": "warning",
+                "message": warning.to_string(),
+                "code": warning.code(),
+            })
+        );
+    }
+}
@@ -13,6 +13,7 @@ fn check(actual: &str, expect: expect_test::Expect) {
 struct Output {
     stdout: String,
     stderr: String,
+    success: bool,
 }
 
 #[cfg(test)]
@@ -25,6 +26,7 @@ fn pintc_command(args: &str) -> Output {
     Output {
         stdout: remove_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
         stderr: remove_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+        success: output.status.success(),
     }
 }
 
@@ -47,7 +49,7 @@ fn err() {
     check(
         &pintc_command("missing.pnt").stderr,
         expect_test::expect![[r#"
-            Error: couldn't read missing.pnt: No such file or directory (os error 2)
+            [file_io] Error: couldn't read missing.pnt: No such file or directory (os error 2)
             Error: could not compile `missing.pnt` due to previous error
         "#]],
     );
@@ -83,21 +85,21 @@ fn compile_errors() {
             .stderr
             .replace(input_file.path().to_str().unwrap(), "filepath"),
         expect_test::expect![[r#"
-            Error: empty tuple types are not allowed
+            [empty_tuple_type] Error: empty tuple types are not allowed
                ╭─[filepath:1:25]
                │
              1 │ predicate test { var t: {} = {}; var a = a[]; }
                │                         ─┬  
                │                          ╰── empty tuple type found
             ───╯
-            Error: empty tuple expressions are not allowed
+            [empty_tuple_expr] Error: empty tuple expressions are not allowed
                ╭─[filepath:1:30]
                │
              1 │ predicate test { var t: {} = {}; var a = a[]; }
                │                              ─┬  
                │                               ╰── empty tuple expression found
             ───╯
-            Error: missing array or map index
+            [empty_index_access] Error: missing array or map index
                ╭─[filepath:1:42]
                │
              1 │ predicate test { var t: {} = {}; var a = a[]; }
@@ -111,6 +113,149 @@ fn compile_errors() {
     check(&output.stdout, expect_test::expect![""]);
 }
 
+#[test]
+fn max_errors() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    let code = r#"predicate test { var t: {} = {}; var a = a[]; var u: {} = {}; }"#;
+    write!(input_file.as_file_mut(), "{code}").unwrap();
+    let filepath = input_file.path().to_str().unwrap();
+
+    // Five errors in total, but only the first two are printed followed by a summary.
+    let output = pintc_command(&format!("{filepath} --max-errors 2"));
+    let stderr = output.stderr.replace(filepath, "filepath");
+    assert_eq!(stderr.matches("Error: ").count(), 3);
+    assert!(stderr.contains("... and 3 more errors"));
+    assert!(stderr.contains("Error: could not compile `filepath` due to 5 previous errors"));
+
+    // Without the flag every error is printed.
+    let output = pintc_command(filepath);
+    assert_eq!(output.stderr.matches("Error: ").count(), 6);
+    assert!(!output.stderr.contains("more errors"));
+
+    // With JSON output the omitted errors aren't summarised since the summary isn't JSON.
+    let output = pintc_command(&format!("{filepath} --max-errors 2 --error-format json"));
+    assert_eq!(
+        output
+            .stderr
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .count(),
+        2
+    );
+    assert!(!output.stderr.contains("more errors"));
+}
+
+#[test]
+fn json_error_format() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var t: {{}} = 1; }}"
+    )
+    .unwrap();
+    let filepath = input_file.path().to_str().unwrap();
+
+    let output = pintc_command(&format!("{filepath} --error-format json"));
+
+    assert!(!input_file.path().with_extension("json").exists());
+
+    // Every diagnostic is a single line of JSON.  The final summary is not.
+    let diagnostics = output
+        .stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(diagnostics.len(), 1);
+
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic["file"], filepath);
+    assert_eq!(diagnostic["start"], 24);
+    assert_eq!(diagnostic["end"], 26);
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["message"], "empty tuple types are not allowed");
+    assert_eq!(diagnostic["code"], "empty_tuple_type");
+
+    assert!(output.stderr.contains("due to previous error"));
+    check(&output.stdout, expect_test::expect![""]);
+}
+
+#[test]
+fn dump_ir() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var x: int; constraint x > 0; }}"
+    )
+    .unwrap();
+
+    let output = pintc_command(&format!(
+        "{} --dump-ir",
+        input_file.path().to_str().unwrap()
+    ));
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+
+    check(&output.stderr, expect_test::expect![""]);
+
+    let ir = serde_json::from_str::<serde_json::Value>(&output.stdout).unwrap();
+    let pred = &ir["predicates"][0];
+    assert_eq!(pred["name"], "::test");
+    assert_eq!(pred["vars"][0]["name"], "::x");
+    assert_eq!(pred["vars"][0]["ty"], "int");
+
+    let constraint = &pred["constraints"][0];
+    assert_eq!(constraint["kind"], "binary_op");
+    assert_eq!(constraint["op"], ">");
+    assert_eq!(constraint["ty"], "bool");
+    assert_eq!(constraint["lhs"]["kind"], "path");
+    assert_eq!(constraint["lhs"]["path"], "::x");
+    assert_eq!(constraint["rhs"]["kind"], "immediate");
+    assert_eq!(constraint["rhs"]["value"], "0");
+}
+
+#[test]
+fn cfg_features() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        r#"
+predicate test {{
+    var x: int;
+    constraint x > 0;
+
+    @cfg(feature = "debug")
+    constraint x < 100;
+
+    @cfg(feature = "debug")
+    if x > 5 {{
+        constraint x < 50;
+    }} else {{
+        constraint x > 1;
+    }}
+
+    @cfg(not(feature = "debug"))
+    constraint x < 1000;
+}}
+"#
+    )
+    .unwrap();
+    let filepath = input_file.path().to_str().unwrap();
+
+    let count_constraints = |output: &Output| output.stdout.matches("constraint ").count();
+
+    let release = pintc_command(&format!("{filepath} --print-parsed"));
+    check(&release.stderr, expect_test::expect![""]);
+    assert_eq!(count_constraints(&release), 2);
+    assert!(release.stdout.contains("(::x < 1000)"));
+
+    let debug = pintc_command(&format!("{filepath} --print-parsed --cfg debug"));
+    check(&debug.stderr, expect_test::expect![""]);
+    assert_eq!(count_constraints(&debug), 4);
+    assert!(!debug.stdout.contains("(::x < 1000)"));
+
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+}
+
 #[test]
 fn default_output() {
     let mut input_file = tempfile::NamedTempFile::new().unwrap();
@@ -145,3 +290,328 @@ fn explicit_output() {
     check(&output.stderr, expect_test::expect![""]);
     check(&output.stdout, expect_test::expect![""]);
 }
+
+#[test]
+fn check_only() {
+    // A type error is reported and nothing is written.
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var x: int; constraint x == y; }}"
+    )
+    .unwrap();
+
+    let output = pintc_command(&format!("{} --check", input_file.path().to_str().unwrap()));
+
+    assert!(!input_file.path().with_extension("json").exists());
+    assert!(output
+        .stderr
+        .contains("Error: cannot find value `::y` in this scope"));
+    assert!(output.stderr.contains("not found in this scope"));
+    assert!(output.stderr.contains("due to previous error"));
+    check(&output.stdout, expect_test::expect![""]);
+
+    // A well typed program produces no diagnostics and no output files.
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var x: int; constraint x == 1; }}"
+    )
+    .unwrap();
+
+    let output = pintc_command(&format!("{} --check", input_file.path().to_str().unwrap()));
+
+    assert!(!input_file.path().with_extension("json").exists());
+    check(&output.stderr, expect_test::expect![""]);
+    check(&output.stdout, expect_test::expect![""]);
+}
+
+#[test]
+fn deny_warnings() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var x: int; var y: int; constraint x == 1; }}"
+    )
+    .unwrap();
+
+    // Without `--deny-warnings` the unused variable is only a warning.
+    let output = pintc_command(input_file.path().to_str().unwrap());
+    assert!(output.success);
+    assert!(output.stderr.contains("Warning: unused variable `y`"));
+    assert!(input_file.path().with_extension("json").exists());
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+
+    // With it, the compilation fails and nothing is written.
+    let output = pintc_command(&format!(
+        "{} --deny-warnings",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(!output.success);
+    assert!(output.stderr.contains("Warning: unused variable `y`"));
+    assert!(output.stderr.contains("due to 1 denied warning"));
+    assert!(!input_file.path().with_extension("json").exists());
+    check(&output.stdout, expect_test::expect![""]);
+
+    // The same goes for `--check`.
+    let output = pintc_command(&format!(
+        "{} --check --deny-warnings",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(!output.success);
+    assert!(output.stderr.contains("due to 1 denied warning"));
+}
+
+#[test]
+fn salt() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var s = __this_contract_salt(); }}"
+    )
+    .unwrap();
+
+    // The salt is pushed one word at a time, so the last word holds the low bytes.
+    let output = pintc_command(&format!(
+        "{} --print-asm --salt {}",
+        input_file.path().to_str().unwrap(),
+        "0x000000000000000000000000000000000000000000000000000000000000002a"
+    ));
+    assert!(output.success);
+    assert!(
+        output.stdout.contains("Stack(Push(42))"),
+        "{}",
+        output.stdout
+    );
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+
+    // Salts must be exactly 32 bytes of hex.
+    let output = pintc_command(&format!(
+        "{} --salt 0x2a",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(!output.success);
+    assert!(output
+        .stderr
+        .contains("must be exactly 32 bytes, found 2 hexadecimal digits"));
+
+    let output = pintc_command(&format!(
+        "{} --salt 0xnothex",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(!output.success);
+    assert!(output
+        .stderr
+        .contains("must contain only hexadecimal digits"));
+}
+
+#[test]
+fn asm_text() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(input_file.as_file_mut(), "predicate test {{}}").unwrap();
+
+    let output = pintc_command(&format!(
+        "{} --asm-text",
+        input_file.path().to_str().unwrap()
+    ));
+
+    let asm_file = input_file.path().with_extension("asm");
+    assert!(asm_file.exists());
+    let listing = fs::read_to_string(&asm_file).unwrap();
+    let _ = fs::remove_file(asm_file);
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+
+    assert!(listing.starts_with("predicate ::test {"));
+    assert!(listing.contains("--- Constraints ---"));
+    check(&output.stderr, expect_test::expect![""]);
+    check(&output.stdout, expect_test::expect![""]);
+}
+
+#[test]
+fn print_abi() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "storage {{ counter: int, owner: b256, }} predicate test {{}}"
+    )
+    .unwrap();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut output_file = PathBuf::from(temp_dir.path());
+    output_file.push("contract.json");
+    let output = pintc_command(&format!(
+        "{} -o {} --abi",
+        input_file.path().to_str().unwrap(),
+        output_file.to_str().unwrap(),
+    ));
+
+    check(&output.stderr, expect_test::expect![""]);
+
+    let abi: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    let storage_names = abi["storage"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|var| var["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(storage_names, vec!["counter", "owner"]);
+}
+
+#[test]
+fn time() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(input_file.as_file_mut(), "predicate test {{}}").unwrap();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut output_file = PathBuf::from(temp_dir.path());
+    output_file.push("contract.json");
+    let output = pintc_command(&format!(
+        "{} -o {} --time",
+        input_file.path().to_str().unwrap(),
+        output_file.to_str().unwrap(),
+    ));
+
+    let phases = output
+        .stderr
+        .lines()
+        .map(|line| line.split(':').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        phases,
+        vec!["parse", "type check", "flatten", "optimize", "asm gen"]
+    );
+    check(&output.stdout, expect_test::expect![""]);
+}
+
+#[test]
+fn no_color() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate test {{ var a = a[]; }}"
+    )
+    .unwrap();
+    let input_path = input_file.path().to_str().unwrap();
+
+    let raw_stderr = |args: &[&str], no_color_env: Option<&str>| {
+        let mut command = test_bin::get_test_bin("pintc");
+        command.args(args);
+        match no_color_env {
+            Some(val) => command.env("NO_COLOR", val),
+            None => command.env_remove("NO_COLOR"),
+        };
+        let output = command.output().expect("failed to start pintc");
+        String::from_utf8_lossy(&output.stderr).to_string()
+    };
+
+    // Both the flag and the environment variable disable escape sequences entirely.
+    for stderr in [
+        raw_stderr(&[input_path, "--no-color"], None),
+        raw_stderr(&[input_path], Some("1")),
+    ] {
+        assert!(stderr.contains("missing array or map index"));
+        assert!(
+            !stderr.contains('\x1b'),
+            "unexpected escape sequence in:\n{stderr}"
+        );
+        assert_eq!(stderr, remove_ansi_codes(&stderr));
+    }
+}
+
+#[test]
+fn entry() {
+    let mut input_file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        input_file.as_file_mut(),
+        "predicate Foo {{ var x: int; constraint x == 1; }} predicate Bar {{ var y: int; }}"
+    )
+    .unwrap();
+
+    let output = pintc_command(&format!(
+        "{} --print-asm --entry Foo",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(output.success);
+    assert!(
+        output.stdout.contains("predicate ::Foo"),
+        "{}",
+        output.stdout
+    );
+    assert!(
+        !output.stdout.contains("predicate ::Bar"),
+        "{}",
+        output.stdout
+    );
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+
+    // The ABI only describes the compiled predicates too.
+    let output = pintc_command(&format!(
+        "{} --abi --entry Foo",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(output.success);
+    let abi: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    let pred_names = abi["predicates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|pred| pred["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(pred_names, ["::Foo"]);
+    let _ = fs::remove_file(input_file.path().with_extension("json"));
+
+    let output = pintc_command(&format!(
+        "{} --entry Baz",
+        input_file.path().to_str().unwrap()
+    ));
+    assert!(!output.success);
+    assert!(output
+        .stderr
+        .contains("cannot find predicate `Baz` in this contract"));
+}
+
+#[test]
+fn stdin() {
+    let pipe_source = |args: &[&str], src: &str| {
+        let mut child = test_bin::get_test_bin("pintc")
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("failed to start pintc");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(src.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        Output {
+            stdout: remove_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
+            stderr: remove_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+            success: output.status.success(),
+        }
+    };
+
+    let output = pipe_source(&["-", "--print-asm"], "predicate test { constraint true; }");
+    assert!(output.success, "{}", output.stderr);
+    assert!(
+        output.stdout.contains("predicate ::test"),
+        "{}",
+        output.stdout
+    );
+
+    // Nothing is written without an output path.
+    assert!(!PathBuf::from("<stdin>.json").exists());
+    assert!(!PathBuf::from("stdin.json").exists());
+
+    // Diagnostics name the source `<stdin>`.
+    let output = pipe_source(&["-"], "predicate test { constraint 1; }");
+    assert!(!output.success);
+    assert!(
+        output.stderr.contains("could not compile `<stdin>`"),
+        "{}",
+        output.stderr
+    );
+}
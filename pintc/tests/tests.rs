@@ -266,11 +266,40 @@ fn optimize(
 ) -> Option<Contract> {
     let handler = Handler::default();
     let optimized = pred.optimize(&handler);
+    let (errors, warnings) = handler.consume();
+
+    if !errors.is_empty() {
+        let err = Errors(errors);
+        if let Some(optimization_error_str) = &test_data.optimization_failure {
+            similar_asserts::assert_eq!(optimization_error_str.trim_end(), format!("{err}"));
+        } else if test_data.optimized.is_some() {
+            failed_tests.push(path.display().to_string());
+            println!(
+                "{} {}. {}\n{}",
+                "FAILED TO OPTIMIZE INTERMEDIATE INTENT".red(),
+                path.display().to_string().cyan(),
+                "Reported errors:".red(),
+                format!("{err}").yellow(),
+            );
+        }
+
+        return None;
+    }
+
+    if test_data.optimization_failure.is_some() {
+        failed_tests.push(path.display().to_string());
+        println!(
+            "{} {}.",
+            "UNEXPECTED SUCCESSFUL OPTIMIZATION".red(),
+            path.display().to_string().cyan(),
+        );
+    }
+
     if let Some(expected_optimized_str) = &test_data.optimized {
         similar_asserts::assert_eq!(expected_optimized_str.trim(), format!("{optimized}").trim());
 
         if let Some(expected_warnings_str) = &test_data.warnings {
-            let warnings = Warnings(handler.consume().1);
+            let warnings = Warnings(warnings);
             similar_asserts::assert_eq!(expected_warnings_str.trim(), format!("{warnings}").trim());
         } else {
             failed_tests.push(path.display().to_string());
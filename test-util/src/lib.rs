@@ -98,6 +98,7 @@ pub struct TestData {
     pub flattened: Option<String>,
     pub flattening_failure: Option<String>,
     pub optimized: Option<String>,
+    pub optimization_failure: Option<String>,
     pub warnings: Option<String>,
     pub db: Option<String>,
 }
@@ -114,6 +115,7 @@ pub struct TestData {
 //   * flattened
 //   * flattening_failure
 //   * optimized
+//   * optimization_failure
 //   * db
 //
 // e.g. A simple test file may be:
@@ -146,6 +148,7 @@ pub fn parse_test_data(path: &Path) -> anyhow::Result<TestData> {
         Flattened,
         FlatteningFailure,
         Optimized,
+        OptimizationFailure,
         Warnings,
         Db,
     }
@@ -154,7 +157,7 @@ pub fn parse_test_data(path: &Path) -> anyhow::Result<TestData> {
 
     let comment_re = regex::Regex::new(r"^\s*//")?;
     let open_sect_re = regex::Regex::new(
-        r"^\s*//\s*(parsed|parse_failure|typecheck_failure|flattened|flattening_failure|optimized|warnings|db)\s*<<<",
+        r"^\s*//\s*(parsed|parse_failure|typecheck_failure|flattened|flattening_failure|optimized|optimization_failure|warnings|db)\s*<<<",
     )?;
     let close_sect_re = regex::Regex::new(r"^\s*//\s*>>>")?;
 
@@ -179,6 +182,7 @@ pub fn parse_test_data(path: &Path) -> anyhow::Result<TestData> {
                 "flattening_failure" => cur_section = Section::FlatteningFailure,
                 "typecheck_failure" => cur_section = Section::TypeCheckFailure,
                 "optimized" => cur_section = Section::Optimized,
+                "optimization_failure" => cur_section = Section::OptimizationFailure,
                 "warnings" => cur_section = Section::Warnings,
                 "db" => cur_section = Section::Db,
                 _ => unreachable!("We can't capture strings not in the regex."),
@@ -219,6 +223,9 @@ pub fn parse_test_data(path: &Path) -> anyhow::Result<TestData> {
                 Section::Optimized => {
                     test_data.optimized = Some(section_str);
                 }
+                Section::OptimizationFailure => {
+                    test_data.optimization_failure = Some(section_str);
+                }
                 Section::Warnings => {
                     test_data.warnings = Some(section_str);
                 }
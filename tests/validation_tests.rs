@@ -64,13 +64,7 @@ async fn validation_e2e() -> anyhow::Result<()> {
 
         // Parsed contract -> Flattened contract
         let flattened = unwrap_or_continue!(
-            parsed.compile(
-                &handler,
-                CompileOptions {
-                    skip_optimize: false,
-                    print_flat: false,
-                }
-            ),
+            parsed.compile(&handler, &CompileOptions::default()),
             "compile",
             failed_tests,
             path,
@@ -79,7 +73,7 @@ async fn validation_e2e() -> anyhow::Result<()> {
 
         // Flattened contract -> Assembly (aka collection of compiled predicates)
         let compiled_contract = unwrap_or_continue!(
-            pintc::asm_gen::compile_contract(&handler, &flattened),
+            pintc::asm_gen::compile_contract(&handler, &flattened, &CompileOptions::default()),
             "asm gen",
             failed_tests,
             path,